@@ -1,8 +1,15 @@
 use ast::prettyprinter::PrettyPrinter;
 use ast::visitor::Visitor;
+use std::process::ExitCode;
+
 use parser::parse_stmts;
 
-fn main() {
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "fmt") {
+        return cli::fmt::run(&args[1..]);
+    }
+
     let line = "BEGIN;
 SAVEPOINT s1;
 COMMIT;
@@ -20,10 +27,9 @@ DROP TABLE IF EXISTS t1;";
             }
         }
         Err(parse_errors) => {
-            for err in parse_errors {
-                println!("Error: {}\n (span {}, {})", err.kind, err.span.start(), err.span.end());
-            }
+            cli::print_errors("<demo>", line, &parse_errors);
         }
     }
     // let parsed = result.unwrap();
+    ExitCode::SUCCESS
 }
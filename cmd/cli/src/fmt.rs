@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use formatter::{format, CommaStyle, FormatOptions, KeywordCase};
+
+const USAGE: &str = "usage: cli fmt [--lower] [--indent N] [--width N] [--leading-commas] [FILE]
+
+Formats FILE, or standard input when no file is given, and writes the result
+to standard output. Nothing is written if the input has syntax errors.";
+
+/// Run `cli fmt` with the arguments following the subcommand.
+pub fn run(args: &[String]) -> ExitCode {
+    let mut options = FormatOptions::default();
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lower" => options.keyword_case = KeywordCase::Lower,
+            "--leading-commas" => options.comma_style = CommaStyle::Leading,
+            "--indent" | "--width" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("{arg} expects a number\n\n{USAGE}");
+                    return ExitCode::from(2);
+                };
+                if arg == "--indent" {
+                    options.indent_width = value;
+                } else {
+                    options.line_width = value;
+                }
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("unexpected argument {arg}\n\n{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let source = match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match format(&source, &options) {
+        Ok(formatted) => {
+            print!("{formatted}");
+            ExitCode::SUCCESS
+        }
+        Err(parse_errors) => {
+            crate::print_errors(path.map_or("<stdin>", String::as_str), &source, &parse_errors);
+            ExitCode::FAILURE
+        }
+    }
+}
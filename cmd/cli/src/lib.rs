@@ -1 +1,20 @@
+use std::io::{self, IsTerminal};
 
+use parser::ParseError;
+use source_index::diagnostic::{Renderer, Style};
+
+pub mod fmt;
+
+/// Print `errors` found in `source` to stderr, colored when stderr is a
+/// terminal and `NO_COLOR` is not set.
+pub fn print_errors(name: &str, source: &str, errors: &[ParseError]) {
+    let style = if io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        Style::Colored
+    } else {
+        Style::Plain
+    };
+    let renderer = Renderer::new(name, source, style);
+    for error in errors {
+        eprintln!("{}", renderer.render(&error.to_diagnostic(source)));
+    }
+}
@@ -1,6 +1,8 @@
 pub use nodes::*;
 pub mod name;
 mod nodes;
+mod unparse;
 
 pub mod prettyprinter;
 pub mod visitor;
+pub mod visitor_mut;
@@ -13,10 +13,7 @@ impl Name {
     }
 
     pub fn is_valid(&self) -> bool {
-        if self.0 == "" {
-            return false;
-        }
-        return true;
+        !self.0.is_empty()
     }
 
     pub fn as_str(&self) -> &str {
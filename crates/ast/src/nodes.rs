@@ -15,12 +15,38 @@ pub enum Stmt {
     Alter(StmtAlter),
     Begin(StmtBegin),
     Commit(StmtCommit),
+    Create(StmtCreate),
+    Delete(StmtDelete),
     Drop(StmtDrop),
+    Insert(StmtInsert),
     Invalid(StmtInvalid),
     Reindex(StmtReindex),
     Release(StmtRelease),
     Rollback(StmtRollback),
     Savepoint(StmtSavepoint),
+    Select(Box<StmtSelect>),
+    Update(StmtUpdate),
+}
+
+impl Spanned for Stmt {
+    fn span(&self) -> Span {
+        match self {
+            Stmt::Alter(stmt) => stmt.span,
+            Stmt::Begin(stmt) => stmt.span,
+            Stmt::Commit(stmt) => stmt.span,
+            Stmt::Create(stmt) => stmt.span,
+            Stmt::Delete(stmt) => stmt.span,
+            Stmt::Drop(stmt) => stmt.span,
+            Stmt::Insert(stmt) => stmt.span,
+            Stmt::Invalid(stmt) => stmt.span,
+            Stmt::Reindex(stmt) => stmt.span,
+            Stmt::Release(stmt) => stmt.span,
+            Stmt::Rollback(stmt) => stmt.span,
+            Stmt::Savepoint(stmt) => stmt.span,
+            Stmt::Select(stmt) => stmt.span,
+            Stmt::Update(stmt) => stmt.span,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -58,6 +84,83 @@ impl From<StmtCommit> for Stmt {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct StmtCreate {
+    pub span: Span,
+    /// `IF NOT EXISTS`
+    pub exist_check: bool,
+    pub id: Identifier,
+    pub kind: CreateKind,
+}
+
+impl From<StmtCreate> for Stmt {
+    fn from(value: StmtCreate) -> Self {
+        Stmt::Create(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreateKind {
+    Table(CreateTable),
+    Index(CreateIndex),
+}
+
+impl CreateKind {
+    pub fn target(&self) -> DdlTargetKind {
+        match self {
+            Self::Table(_) => DdlTargetKind::Table,
+            Self::Index(_) => DdlTargetKind::Index,
+        }
+    }
+}
+
+/// `[UNIQUE]? INDEX ... ON table-name (<indexed-column-list>) [WHERE <expr>]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateIndex {
+    pub span: Span,
+    pub unique: bool,
+    pub table: Identifier,
+    pub columns: Vec<IndexedColumn>,
+    /// Predicate of a partial index
+    pub where_clause: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateTable {
+    pub span: Span,
+    pub source: CreateTableSource,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreateTableSource {
+    /// `(<column-def> [, <column-def>]* [, <table-constraint>]*)`
+    Columns(CreateTableColumns),
+    /// `AS <select-stmt>`
+    Select(Box<StmtSelect>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateTableColumns {
+    pub span: Span,
+    pub columns: Vec<ColumnDef>,
+    pub constraints: Vec<TableConstraint>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StmtDelete {
+    pub span: Span,
+    pub with: Option<WithClause>,
+    pub table: QuantifiedTableName,
+    pub where_clause: Option<Expr>,
+    pub returning: Option<ReturningClause>,
+}
+
+impl From<StmtDelete> for Stmt {
+    fn from(value: StmtDelete) -> Self {
+        Stmt::Delete(value)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct StmtDrop {
     pub span: Span,
@@ -83,6 +186,22 @@ impl Display for DdlTargetKind {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct StmtInsert {
+    pub span: Span,
+    pub with: Option<WithClause>,
+    pub table: TableName,
+    pub columns: Vec<Identifier>,
+    pub action: InsertAction,
+    pub returning: Option<ReturningClause>,
+}
+
+impl From<StmtInsert> for Stmt {
+    fn from(value: StmtInsert) -> Self {
+        Stmt::Insert(value)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct StmtInvalid {
     pub span: Span,
@@ -142,6 +261,46 @@ impl From<StmtSavepoint> for Stmt {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct StmtSelect {
+    pub span: Span,
+    pub with: Option<WithClause>,
+    pub distinct: bool,
+    pub columns: Vec<ResultColumn>,
+    pub from: Option<FromClause>,
+    pub where_clause: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub window: Vec<NamedWindow>,
+    pub compound: Option<CompoundSelect>,
+    pub order_by: Vec<OrderingTerm>,
+    pub limit: Option<LimitClause>,
+}
+
+impl From<StmtSelect> for Stmt {
+    fn from(value: StmtSelect) -> Self {
+        Stmt::Select(Box::new(value))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StmtUpdate {
+    pub span: Span,
+    pub with: Option<WithClause>,
+    pub conflict_action: Option<ConflictAction>,
+    pub table: QuantifiedTableName,
+    pub assignments: Vec<Assignment>,
+    pub from: Option<FromClause>,
+    pub where_clause: Option<Expr>,
+    pub returning: Option<ReturningClause>,
+}
+
+impl From<StmtUpdate> for Stmt {
+    fn from(value: StmtUpdate) -> Self {
+        Stmt::Update(value)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct AlterTableAction {
     pub span: Span,
@@ -180,6 +339,328 @@ pub struct AlterTableDrop {
     pub id: Identifier,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultColumn {
+    pub span: Span,
+    pub kind: ResultColumnKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResultColumnKind {
+    /// `*`
+    All,
+    /// `table-name.*`
+    TableAll(Identifier),
+    /// `<expr> [AS column-alias]?`
+    Expr(Expr, Option<Identifier>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FromClause {
+    pub span: Span,
+    pub item: FromItem,
+}
+
+/// A `<table-or-subquery>` or a `<join-clause>` built from them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FromItem {
+    pub span: Span,
+    pub kind: FromItemKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FromItemKind {
+    Table(QuantifiedTableName),
+    Subquery(FromSubquery),
+    Join(JoinClause),
+}
+
+/// A derived table, `(<select-stmt>) [AS table-alias]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FromSubquery {
+    pub span: Span,
+    pub select: Box<StmtSelect>,
+    pub alias: Option<Identifier>,
+}
+
+/// A single join between two from items. Chains of joins are left-deep, so
+/// `a JOIN b JOIN c` has `a JOIN b` on its left. A parenthesised join tree
+/// appears as a [`FromItemKind::Join`] on the right.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinClause {
+    pub span: Span,
+    pub left: Box<FromItem>,
+    pub op: JoinOperator,
+    pub right: Box<FromItem>,
+    pub constraint: Option<JoinConstraint>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinOperator {
+    pub span: Span,
+    pub natural: bool,
+    pub kind: JoinKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinKind {
+    /// `,`
+    Comma,
+    Cross,
+    /// `JOIN` or `INNER JOIN`
+    Inner,
+    /// `LEFT [OUTER]? JOIN`
+    Left,
+    /// `RIGHT [OUTER]? JOIN`
+    Right,
+    /// `FULL [OUTER]? JOIN`
+    Full,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinConstraint {
+    On(Expr),
+    Using(Vec<Identifier>),
+}
+
+/// A table name with an optional alias, `table-name [AS table-alias]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableName {
+    pub span: Span,
+    pub id: Identifier,
+    pub alias: Option<Identifier>,
+}
+
+/// `table-name [AS alias]? [INDEXED BY index-name | NOT INDEXED]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuantifiedTableName {
+    pub span: Span,
+    pub id: Identifier,
+    pub alias: Option<Identifier>,
+    pub indexed: Option<IndexedHint>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IndexedHint {
+    IndexedBy(Identifier),
+    NotIndexed,
+}
+
+/// The right hand side of a compound select. The ORDER BY and LIMIT clauses
+/// of a compound select belong to the outermost [`StmtSelect`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundSelect {
+    pub span: Span,
+    pub op: CompoundOperator,
+    pub select: Box<StmtSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompoundOperator {
+    Union,
+    UnionAll,
+    Intersect,
+    Except,
+}
+
+impl Display for CompoundOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Union => write!(f, "UNION"),
+            Self::UnionAll => write!(f, "UNION ALL"),
+            Self::Intersect => write!(f, "INTERSECT"),
+            Self::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderingTerm {
+    pub span: Span,
+    pub expr: Expr,
+    pub collation: Option<Identifier>,
+    pub order: Option<Order>,
+    pub nulls: Option<NullsOrder>,
+}
+
+/// `NULLS [FIRST | LAST]`
+#[derive(Clone, Debug, PartialEq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// A named window in the `WINDOW` clause, `window-name AS <window-defn>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedWindow {
+    pub span: Span,
+    pub id: Identifier,
+    pub spec: WindowSpec,
+}
+
+/// `([base-window-name]? [PARTITION BY <expr-list>]? [ORDER BY <ordering-term-list>]? [<frame-spec>]?)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowSpec {
+    pub span: Span,
+    pub base: Option<Identifier>,
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderingTerm>,
+    pub frame: Option<WindowFrame>,
+}
+
+/// `[RANGE | ROWS | GROUPS] [BETWEEN <frame-bound> AND <frame-bound> | <frame-bound>] [EXCLUDE ...]?`
+///
+/// A frame with a single bound has no `end`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowFrame {
+    pub span: Span,
+    pub units: FrameUnits,
+    pub start: FrameBound,
+    pub end: Option<FrameBound>,
+    pub exclude: Option<FrameExclude>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FrameUnits {
+    Range,
+    Rows,
+    Groups,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(Box<Expr>),
+    CurrentRow,
+    Following(Box<Expr>),
+    UnboundedFollowing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FrameExclude {
+    NoOthers,
+    CurrentRow,
+    Group,
+    Ties,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LimitClause {
+    pub span: Span,
+    pub limit: Expr,
+    pub offset: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertAction {
+    pub span: Span,
+    pub kind: InsertActionKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InsertActionKind {
+    Values(InsertValues),
+    Select(InsertSelect),
+    DefaultValues,
+}
+
+/// `VALUES (<expr-list>) [, (<expr-list>)]* [<upsert-clause>]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertValues {
+    pub span: Span,
+    pub rows: Vec<Vec<Expr>>,
+    pub upsert: Option<UpsertClause>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertSelect {
+    pub span: Span,
+    pub select: Box<StmtSelect>,
+    pub upsert: Option<UpsertClause>,
+}
+
+/// `ON CONFLICT [(<indexed-column-list>) [WHERE <expr>]?]? DO <upsert-action>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpsertClause {
+    pub span: Span,
+    pub target: Option<UpsertTarget>,
+    pub action: UpsertAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpsertTarget {
+    pub span: Span,
+    pub columns: Vec<IndexedColumn>,
+    pub where_clause: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpsertAction {
+    Nothing,
+    Update(UpsertUpdate),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpsertUpdate {
+    pub span: Span,
+    pub assignments: Vec<Assignment>,
+    pub where_clause: Option<Expr>,
+}
+
+/// A single `SET` target, `[column-name | (<column-name-list>)] = <expr>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    pub span: Span,
+    pub target: AssignmentTarget,
+    pub expr: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssignmentTarget {
+    Column(Identifier),
+    ColumnList(Vec<Identifier>),
+}
+
+/// `WITH [RECURSIVE]? <common-table-expression> [, <common-table-expression>]*`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithClause {
+    pub span: Span,
+    pub recursive: bool,
+    pub ctes: Vec<CommonTableExpr>,
+}
+
+/// `table-name [( <column-name-list> )]? AS [[NOT]? MATERIALIZED]? ( <select-stmt> )`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommonTableExpr {
+    pub span: Span,
+    pub id: Identifier,
+    pub columns: Vec<Identifier>,
+    pub materialized: Option<MaterializeHint>,
+    pub select: Box<StmtSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MaterializeHint {
+    Materialized,
+    NotMaterialized,
+}
+
+/// `RETURNING [* | <expr> [AS column-alias]?] [, ...]*`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReturningClause {
+    pub span: Span,
+    pub columns: Vec<ResultColumn>,
+}
+
+/// `[column-name | <expr>] [COLLATE collation-name]? [ASC|DESC]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedColumn {
+    pub span: Span,
+    pub expr: Expr,
+    pub collation: Option<Identifier>,
+    pub order: Option<Order>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ColumnDef {
     pub span: Span,
@@ -197,6 +678,8 @@ pub struct ColumnConstraintList {
 #[derive(Clone, Debug, PartialEq)]
 pub struct ColumnConstraint {
     pub span: Span,
+    /// Name given by a leading `CONSTRAINT name`
+    pub name: Option<Identifier>,
     pub kind: ColumnConstraintKind
 }
 
@@ -211,6 +694,37 @@ pub enum ColumnConstraintKind {
     Foreign(ForeignKeyClause),
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableConstraint {
+    pub span: Span,
+    /// Name given by a leading `CONSTRAINT name`
+    pub name: Option<Identifier>,
+    pub kind: TableConstraintKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TableConstraintKind {
+    PrimaryKey(TableConstraintKey),
+    Unique(TableConstraintKey),
+    Check(Expr),
+    ForeignKey(TableConstraintForeignKey),
+}
+
+/// The column list of a table level PRIMARY KEY or UNIQUE constraint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableConstraintKey {
+    pub span: Span,
+    pub columns: Vec<IndexedColumn>,
+    pub conflict_action: Option<ConflictAction>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableConstraintForeignKey {
+    pub span: Span,
+    pub columns: Vec<Identifier>,
+    pub clause: ForeignKeyClause,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ColumnConstraintPrimaryKey {
     pub span: Span,
@@ -222,7 +736,8 @@ pub struct ColumnConstraintPrimaryKey {
 #[derive(Clone, Debug, PartialEq)]
 pub enum ColumnConstraintDefault {
     ParenExpr(Expr),
-    LiteralValue(LiteralValue)
+    LiteralValue(LiteralValue),
+    SignedNumber(SignedNumber),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -304,7 +819,52 @@ pub struct TypeNameNumberField {
 pub enum Expr {
     BinOp(ExprBinaryOp),
     UnaryOp(ExprUnaryOp),
+    Compare(ExprCompare),
+    BoolOp(ExprBoolOp),
     LiteralValue(LiteralValue),
+    Column(ExprColumn),
+    List(ExprList),
+    Subquery(ExprSubquery),
+    Collate(ExprCollate),
+    Like(ExprLike),
+    IsNull(ExprIsNull),
+    Is(ExprIs),
+    Between(ExprBetween),
+    In(ExprIn),
+    Exists(ExprExists),
+    Function(ExprFunction),
+    Case(ExprCase),
+    Cast(ExprCast),
+    Parameter(ExprParameter),
+    /// Placeholder for an expression that failed to parse.
+    Invalid(ExprInvalid),
+}
+
+impl Spanned for Expr {
+    fn span(&self) -> Span {
+        match self {
+            Expr::BinOp(expr) => expr.span,
+            Expr::UnaryOp(expr) => expr.span,
+            Expr::Compare(expr) => expr.span,
+            Expr::BoolOp(expr) => expr.span,
+            Expr::LiteralValue(literal) => literal.span(),
+            Expr::Column(expr) => expr.span,
+            Expr::List(expr) => expr.span,
+            Expr::Subquery(expr) => expr.span,
+            Expr::Collate(expr) => expr.span,
+            Expr::Like(expr) => expr.span,
+            Expr::IsNull(expr) => expr.span,
+            Expr::Is(expr) => expr.span,
+            Expr::Between(expr) => expr.span,
+            Expr::In(expr) => expr.span,
+            Expr::Exists(expr) => expr.span,
+            Expr::Function(expr) => expr.span,
+            Expr::Case(expr) => expr.span,
+            Expr::Cast(expr) => expr.span,
+            Expr::Parameter(expr) => expr.span,
+            Expr::Invalid(expr) => expr.span,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -320,6 +880,20 @@ pub enum LiteralValue {
     FloatLiteral(FloatLiteral),
     NullLiteral(NullLiteral),
     BoolLiteral(BoolLiteral),
+    Blob(BlobLiteral),
+}
+
+impl Spanned for LiteralValue {
+    fn span(&self) -> Span {
+        match self {
+            LiteralValue::StringLiteral(literal) => literal.span,
+            LiteralValue::IntLiteral(literal) => literal.span,
+            LiteralValue::FloatLiteral(literal) => literal.span,
+            LiteralValue::NullLiteral(literal) => literal.span,
+            LiteralValue::BoolLiteral(literal) => literal.span,
+            LiteralValue::Blob(literal) => literal.span,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -328,6 +902,13 @@ pub struct StringLiteral {
     pub value: String,
 }
 
+/// `X'<hex digits>'`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlobLiteral {
+    pub span: Span,
+    pub value: Vec<u8>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct BoolLiteral {
     pub span: Span,
@@ -366,6 +947,187 @@ pub struct ExprUnaryOp {
     pub operand: Box<Expr>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprCompare {
+    pub span: Span,
+    pub left: Box<Expr>,
+    pub op: CmpOperator,
+    pub right: Box<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprBoolOp {
+    pub span: Span,
+    pub left: Box<Expr>,
+    pub op: BooleanOperator,
+    pub right: Box<Expr>,
+}
+
+/// A parenthesised list of expressions, `(<expr> [, <expr>]*)`. A single
+/// parenthesised expression is a list of length one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprList {
+    pub span: Span,
+    pub exprs: Vec<Expr>,
+}
+
+/// A parenthesised select used as a scalar value, `(<select-stmt>)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprSubquery {
+    pub span: Span,
+    pub select: Box<StmtSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprCollate {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub collation: Identifier,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprLike {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub negated: bool,
+    pub pattern: Box<Expr>,
+}
+
+/// `<expr> IS [NOT] NULL` or `<expr> NOT NULL`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprIsNull {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub negated: bool,
+}
+
+/// `<expr> IS [NOT] [DISTINCT FROM] <expr>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprIs {
+    pub span: Span,
+    pub left: Box<Expr>,
+    pub negated: bool,
+    pub distinct_from: bool,
+    pub right: Box<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprBetween {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub negated: bool,
+    pub low: Box<Expr>,
+    pub high: Box<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprIn {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub negated: bool,
+    pub kind: ExprInKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprInKind {
+    List(Vec<Expr>),
+    Select(Box<StmtSelect>),
+    Table(Identifier),
+}
+
+/// `[NOT]? EXISTS (<select-stmt>)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprExists {
+    pub span: Span,
+    pub negated: bool,
+    pub select: Box<StmtSelect>,
+}
+
+/// `function-name([[DISTINCT]? <expr-list> | *]?) [FILTER (WHERE <expr>)]? [OVER <over-clause>]?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprFunction {
+    pub span: Span,
+    pub name: Identifier,
+    pub distinct: bool,
+    pub args: FunctionArgs,
+    pub filter: Option<Box<Expr>>,
+    pub over: Option<OverClause>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FunctionArgs {
+    /// `*`, as in `COUNT(*)`
+    Star,
+    /// Possibly empty argument list
+    List(Vec<Expr>),
+}
+
+/// `CASE [<expr>]? [WHEN <expr> THEN <expr>]+ [ELSE <expr>]? END`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprCase {
+    pub span: Span,
+    pub operand: Option<Box<Expr>>,
+    pub branches: Vec<CaseBranch>,
+    pub else_expr: Option<Box<Expr>>,
+}
+
+/// `WHEN <expr> THEN <expr>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseBranch {
+    pub span: Span,
+    pub condition: Expr,
+    pub result: Expr,
+}
+
+/// `CAST(<expr> AS <type-name>)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprCast {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub type_name: TypeName,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OverClause {
+    Window(Box<WindowSpec>),
+    Named(Identifier),
+}
+
+/// A bind parameter. `index` is the 1-based position a value is bound to.
+/// Anonymous parameters take the next unused index and named parameters
+/// share the index of the first parameter with the same name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprParameter {
+    pub span: Span,
+    pub kind: ParameterKind,
+    pub index: u32,
+}
+
+/// Spans the tokens skipped while recovering from the error, empty if
+/// nothing was skipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprInvalid {
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterKind {
+    /// `?`
+    Anonymous,
+    /// `?NNN` or `$NNN`
+    Numbered(u32),
+    /// `:name`, `@name` or `$name`, including the prefix character
+    Named(Name),
+}
+
+/// A column reference, `[table-name.]?column-name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprColumn {
+    pub span: Span,
+    pub table: Option<Identifier>,
+    pub column: Identifier,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Identifier {
     pub id: Name,
@@ -380,9 +1142,22 @@ impl Identifier {
         }
     }
 
+    /// Placeholder for a name that is missing or failed to parse. It has an
+    /// empty name.
+    pub fn invalid(span: Span) -> Self {
+        Self {
+            id: Name::empty(),
+            span,
+        }
+    }
+
     pub fn id(&self) -> &Name {
         &self.id
     }
+
+    pub fn is_valid(&self) -> bool {
+        self.id.is_valid()
+    }
 }
 
 impl Spanned for Identifier {
@@ -403,6 +1178,7 @@ pub enum BinaryOperator {
     Sub,
     Mult,
     Div,
+    Mod,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -9,10 +9,16 @@ impl PrettyPrinter {
     }
 }
 
+impl Default for PrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Visitor for PrettyPrinter {
     fn visit_stmt(&mut self, stmt: &Stmt) {
         match stmt {
-            Stmt::Alter(ast::StmtAlter { span, id, action }) => {
+            Stmt::Alter(ast::StmtAlter { span, id, action: _ }) => {
                 println!("ALTER (span {}, {})", span.start(), span.end());
                 println!(
                     "\tid: {} (span {}, {})",
@@ -33,16 +39,13 @@ impl Visitor for PrettyPrinter {
             }
             Stmt::Reindex(ast::StmtReindex { span, id }) => {
                 println!("REINDEX (span {}, {})", span.start(), span.end());
-                match id {
-                    Some(id) => {
-                        println!(
-                            "\tid: {} (span {}, {})",
-                            &id.id,
-                            &id.span.start(),
-                            &id.span.end()
-                        );
-                    }
-                    None => {}
+                if let Some(id) = id {
+                    println!(
+                        "\tid: {} (span {}, {})",
+                        &id.id,
+                        &id.span.start(),
+                        &id.span.end()
+                    );
                 }
             }
             Stmt::Release(ast::StmtRelease { span, id }) => {
@@ -56,16 +59,13 @@ impl Visitor for PrettyPrinter {
             }
             Stmt::Rollback(ast::StmtRollback { span, id }) => {
                 println!("ROLLBACK (span {}, {})", span.start(), span.end());
-                match id {
-                    Some(id) => {
-                        println!(
-                            "\tid: {} (span {}, {})",
-                            &id.id,
-                            &id.span.start(),
-                            &id.span.end()
-                        );
-                    }
-                    None => {}
+                if let Some(id) = id {
+                    println!(
+                        "\tid: {} (span {}, {})",
+                        &id.id,
+                        &id.span.start(),
+                        &id.span.end()
+                    );
                 }
             }
             Stmt::Savepoint(ast::StmtSavepoint { span, id }) => {
@@ -77,6 +77,56 @@ impl Visitor for PrettyPrinter {
                     &id.span.end()
                 );
             }
+            Stmt::Create(ast::StmtCreate { span, exist_check, id, kind }) => {
+                println!("CREATE (span {}, {})", span.start(), span.end());
+                println!(
+                    "\tkind: {}\n\texist check: {}",
+                    kind.target(), exist_check
+                );
+                println!(
+                    "\tid: {} (span {}, {})",
+                    &id.id,
+                    &id.span.start(),
+                    &id.span.end()
+                );
+            }
+            Stmt::Delete(ast::StmtDelete { span, table, .. }) => {
+                println!("DELETE (span {}, {})", span.start(), span.end());
+                println!(
+                    "\ttable: {} (span {}, {})",
+                    &table.id.id,
+                    &table.id.span.start(),
+                    &table.id.span.end()
+                );
+            }
+            Stmt::Insert(ast::StmtInsert { span, table, columns, .. }) => {
+                println!("INSERT (span {}, {})", span.start(), span.end());
+                println!(
+                    "\ttable: {} (span {}, {})\n\tcolumns: {}",
+                    &table.id.id,
+                    &table.id.span.start(),
+                    &table.id.span.end(),
+                    columns.len()
+                );
+            }
+            Stmt::Select(select) => {
+                let ast::StmtSelect { span, distinct, columns, .. } = &**select;
+                println!("SELECT (span {}, {})", span.start(), span.end());
+                println!(
+                    "\tdistinct: {}\n\tresult columns: {}",
+                    distinct, columns.len()
+                );
+            }
+            Stmt::Update(ast::StmtUpdate { span, table, assignments, .. }) => {
+                println!("UPDATE (span {}, {})", span.start(), span.end());
+                println!(
+                    "\ttable: {} (span {}, {})\n\tassignments: {}",
+                    &table.id.id,
+                    &table.id.span.start(),
+                    &table.id.span.end(),
+                    assignments.len()
+                );
+            }
             Stmt::Drop(ast::StmtDrop { span, kind, exist_check, id}) => {
                 println!("DROP (span {}, {})", span.start(), span.end());
                 println!(
@@ -0,0 +1,1165 @@
+//! SQL text for every node of the tree.
+//!
+//! The [`Display`] implementations print canonical SQL: keywords in upper
+//! case, single spaces between tokens, and optional noise words (`OUTER`,
+//! `TRANSACTION`, ...) left out. Parsing the output of a parsed tree yields
+//! the same tree up to spans. Explicit parentheses are part of the tree as
+//! [`ExprList`], so expressions are printed without adding any of their own.
+//!
+//! [`StmtInvalid`], [`ExprInvalid`] and [`Identifier::invalid`] placeholders
+//! left by error recovery have no source text and print as empty strings.
+
+use std::fmt::{Display, Formatter, Result};
+
+use crate::*;
+
+/// Words the lexer turns into keywords. Identifiers matching one of them,
+/// ignoring case, are quoted. Keep in sync with `KEYWORDS` in the parser tokens,
+/// which the parser tests check.
+const KEYWORDS: &[&str] = &[
+    "ABORT", "ACTION", "ADD", "ALL", "ALTER", "AND", "AS", "ASC", "BEGIN", "BETWEEN", "BY",
+    "CASCADE", "CASE", "CAST", "CHAR", "CHECK", "COLLATE", "COLUMN", "COMMIT", "CONFLICT",
+    "CONSTRAINT", "CREATE", "CROSS", "CURRENT", "DEFAULT", "DELETE", "DESC", "DISTINCT", "DO",
+    "DROP", "ELSE", "END", "EXCEPT", "EXCLUDE", "EXISTS", "FAIL", "FALSE", "FILTER", "FIRST",
+    "FOLLOWING", "FOREIGN", "FROM", "FULL", "GROUP", "GROUPS", "HAVING", "IF", "IGNORE", "IN",
+    "INDEX", "INDEXED", "INNER", "INSERT", "INTEGER", "INTERSECT", "INTO", "IS", "JOIN", "KEY",
+    "LAST", "LEFT", "LIKE", "LIMIT", "MATERIALIZED", "NATURAL", "NO", "NOT", "NOTHING", "NULL",
+    "NULLS", "NUMERIC", "OFFSET", "ON", "OR", "ORDER", "OTHERS", "OUTER", "OVER", "PARTITION",
+    "PRECEDING", "PRIMARY", "RANGE", "RECURSIVE", "REFERENCES", "REINDEX", "RELEASE", "RENAME",
+    "REPLACE", "RESTRICT", "RETURNING", "RIGHT", "ROLLBACK", "ROW", "ROWS", "SAVEPOINT",
+    "SELECT", "SERIAL", "SET", "TABLE", "THEN", "TIES", "TO", "TRANSACTION", "TRUE", "UNBOUNDED",
+    "UNION", "UNIQUE", "UPDATE", "USING", "VACUUM", "VALUES", "VARCHAR", "WHEN", "WHERE",
+    "WINDOW", "WITH",
+];
+
+/// Items printed with a separator between them.
+struct Separated<'a, T>(&'a [T], &'static str);
+
+impl<T: Display> Display for Separated<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.1)?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+fn comma_separated<T>(items: &[T]) -> Separated<'_, T> {
+    Separated(items, ", ")
+}
+
+impl Display for Stmts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, stmt) in self.body.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{stmt};")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Stmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Stmt::Alter(stmt) => write!(f, "{stmt}"),
+            Stmt::Begin(stmt) => write!(f, "{stmt}"),
+            Stmt::Commit(stmt) => write!(f, "{stmt}"),
+            Stmt::Create(stmt) => write!(f, "{stmt}"),
+            Stmt::Delete(stmt) => write!(f, "{stmt}"),
+            Stmt::Drop(stmt) => write!(f, "{stmt}"),
+            Stmt::Insert(stmt) => write!(f, "{stmt}"),
+            Stmt::Invalid(stmt) => write!(f, "{stmt}"),
+            Stmt::Reindex(stmt) => write!(f, "{stmt}"),
+            Stmt::Release(stmt) => write!(f, "{stmt}"),
+            Stmt::Rollback(stmt) => write!(f, "{stmt}"),
+            Stmt::Savepoint(stmt) => write!(f, "{stmt}"),
+            Stmt::Select(stmt) => write!(f, "{stmt}"),
+            Stmt::Update(stmt) => write!(f, "{stmt}"),
+        }
+    }
+}
+
+impl Display for StmtAlter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ALTER TABLE {} {}", self.id, self.action)
+    }
+}
+
+impl Display for StmtBegin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("BEGIN")
+    }
+}
+
+impl Display for StmtCommit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("COMMIT")
+    }
+}
+
+impl Display for StmtCreate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let exist_check = if self.exist_check { "IF NOT EXISTS " } else { "" };
+        match &self.kind {
+            CreateKind::Table(table) => {
+                write!(f, "CREATE TABLE {exist_check}{} {table}", self.id)
+            }
+            CreateKind::Index(index) => {
+                let unique = if index.unique { "UNIQUE " } else { "" };
+                write!(f, "CREATE {unique}INDEX {exist_check}{} {index}", self.id)
+            }
+        }
+    }
+}
+
+/// The part of `CREATE INDEX` following the index name.
+impl Display for CreateIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ON {} ({})", self.table, comma_separated(&self.columns))?;
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The part of `CREATE TABLE` following the table name.
+impl Display for CreateTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.source {
+            CreateTableSource::Columns(columns) => write!(f, "{columns}"),
+            CreateTableSource::Select(select) => write!(f, "AS {select}"),
+        }
+    }
+}
+
+impl Display for CreateTableColumns {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({}", comma_separated(&self.columns))?;
+        for constraint in &self.constraints {
+            write!(f, ", {constraint}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Display for StmtDelete {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+        write!(f, "DELETE FROM {}", self.table)?;
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        if let Some(returning) = &self.returning {
+            write!(f, " {returning}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StmtDrop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let exist_check = if self.exist_check { "IF EXISTS " } else { "" };
+        write!(f, "DROP {} {exist_check}{}", self.kind, self.id)
+    }
+}
+
+impl Display for StmtInsert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+        write!(f, "INSERT INTO {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", comma_separated(&self.columns))?;
+        }
+        write!(f, " {}", self.action)?;
+        if let Some(returning) = &self.returning {
+            write!(f, " {returning}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StmtInvalid {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> Result {
+        Ok(())
+    }
+}
+
+impl Display for StmtReindex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("REINDEX")?;
+        if let Some(id) = &self.id {
+            write!(f, " {id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StmtRelease {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "RELEASE SAVEPOINT {}", self.id)
+    }
+}
+
+impl Display for StmtRollback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("ROLLBACK")?;
+        if let Some(id) = &self.id {
+            write!(f, " TO SAVEPOINT {id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StmtSavepoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "SAVEPOINT {}", self.id)
+    }
+}
+
+impl Display for StmtSelect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+        f.write_str("SELECT ")?;
+        if self.distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        write!(f, "{}", comma_separated(&self.columns))?;
+        if let Some(from) = &self.from {
+            write!(f, " {from}")?;
+        }
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY {}", comma_separated(&self.group_by))?;
+        }
+        if let Some(expr) = &self.having {
+            write!(f, " HAVING {expr}")?;
+        }
+        if !self.window.is_empty() {
+            write!(f, " WINDOW {}", comma_separated(&self.window))?;
+        }
+        if let Some(compound) = &self.compound {
+            write!(f, " {compound}")?;
+        }
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", comma_separated(&self.order_by))?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " {limit}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StmtUpdate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+        f.write_str("UPDATE ")?;
+        if let Some(action) = &self.conflict_action {
+            write!(f, "OR {action} ")?;
+        }
+        write!(f, "{} SET {}", self.table, comma_separated(&self.assignments))?;
+        if let Some(from) = &self.from {
+            write!(f, " {from}")?;
+        }
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        if let Some(returning) = &self.returning {
+            write!(f, " {returning}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AlterTableAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            AlterTableActionKind::Rename(rename) => write!(f, "{rename}"),
+            AlterTableActionKind::Add(add) => write!(f, "{add}"),
+            AlterTableActionKind::Drop(drop) => write!(f, "{drop}"),
+        }
+    }
+}
+
+impl Display for AlterTableRename {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            AlterTableRenameKind::Table(id) => write!(f, "RENAME TO {id}"),
+            AlterTableRenameKind::Column(from, to) => write!(f, "RENAME COLUMN {from} TO {to}"),
+        }
+    }
+}
+
+impl Display for AlterTableAdd {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ADD COLUMN {}", self.column)
+    }
+}
+
+impl Display for AlterTableDrop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "DROP COLUMN {}", self.id)
+    }
+}
+
+impl Display for ResultColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            ResultColumnKind::All => f.write_str("*"),
+            ResultColumnKind::TableAll(table) => write!(f, "{table}.*"),
+            ResultColumnKind::Expr(expr, alias) => {
+                write!(f, "{expr}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for FromClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "FROM {}", self.item)
+    }
+}
+
+impl Display for FromItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            FromItemKind::Table(table) => write!(f, "{table}"),
+            FromItemKind::Subquery(subquery) => write!(f, "{subquery}"),
+            FromItemKind::Join(join) => write!(f, "{join}"),
+        }
+    }
+}
+
+impl Display for FromSubquery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({})", self.select)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {alias}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Joins are left-deep, so only a join on the right needs parentheses.
+impl Display for JoinClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.left)?;
+        if self.op.kind == JoinKind::Comma {
+            f.write_str(", ")?;
+        } else {
+            write!(f, " {} ", self.op)?;
+        }
+        match &self.right.kind {
+            FromItemKind::Join(_) => write!(f, "({})", self.right)?,
+            _ => write!(f, "{}", self.right)?,
+        }
+        if let Some(constraint) = &self.constraint {
+            write!(f, " {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for JoinOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.natural {
+            f.write_str("NATURAL ")?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl Display for JoinKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Comma => f.write_str(","),
+            Self::Cross => f.write_str("CROSS JOIN"),
+            Self::Inner => f.write_str("JOIN"),
+            Self::Left => f.write_str("LEFT JOIN"),
+            Self::Right => f.write_str("RIGHT JOIN"),
+            Self::Full => f.write_str("FULL JOIN"),
+        }
+    }
+}
+
+impl Display for JoinConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::On(expr) => write!(f, "ON {expr}"),
+            Self::Using(columns) => write!(f, "USING ({})", comma_separated(columns)),
+        }
+    }
+}
+
+impl Display for TableName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.id)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {alias}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for QuantifiedTableName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.id)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {alias}")?;
+        }
+        if let Some(indexed) = &self.indexed {
+            write!(f, " {indexed}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for IndexedHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::IndexedBy(index) => write!(f, "INDEXED BY {index}"),
+            Self::NotIndexed => f.write_str("NOT INDEXED"),
+        }
+    }
+}
+
+impl Display for CompoundSelect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} {}", self.op, self.select)
+    }
+}
+
+impl Display for OrderingTerm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.expr)?;
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {collation}")?;
+        }
+        if let Some(order) = &self.order {
+            write!(f, " {order}")?;
+        }
+        if let Some(nulls) = &self.nulls {
+            write!(f, " {nulls}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for NullsOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::First => f.write_str("NULLS FIRST"),
+            Self::Last => f.write_str("NULLS LAST"),
+        }
+    }
+}
+
+impl Display for NamedWindow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} AS {}", self.id, self.spec)
+    }
+}
+
+impl Display for WindowSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut parts = Vec::new();
+        if let Some(base) = &self.base {
+            parts.push(base.to_string());
+        }
+        if !self.partition_by.is_empty() {
+            parts.push(format!("PARTITION BY {}", comma_separated(&self.partition_by)));
+        }
+        if !self.order_by.is_empty() {
+            parts.push(format!("ORDER BY {}", comma_separated(&self.order_by)));
+        }
+        if let Some(frame) = &self.frame {
+            parts.push(frame.to_string());
+        }
+        write!(f, "({})", Separated(&parts, " "))
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} ", self.units)?;
+        match &self.end {
+            Some(end) => write!(f, "BETWEEN {} AND {end}", self.start)?,
+            None => write!(f, "{}", self.start)?,
+        }
+        if let Some(exclude) = &self.exclude {
+            write!(f, " {exclude}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for FrameUnits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Range => f.write_str("RANGE"),
+            Self::Rows => f.write_str("ROWS"),
+            Self::Groups => f.write_str("GROUPS"),
+        }
+    }
+}
+
+impl Display for FrameBound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::UnboundedPreceding => f.write_str("UNBOUNDED PRECEDING"),
+            Self::Preceding(expr) => write!(f, "{expr} PRECEDING"),
+            Self::CurrentRow => f.write_str("CURRENT ROW"),
+            Self::Following(expr) => write!(f, "{expr} FOLLOWING"),
+            Self::UnboundedFollowing => f.write_str("UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+impl Display for FrameExclude {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::NoOthers => f.write_str("EXCLUDE NO OTHERS"),
+            Self::CurrentRow => f.write_str("EXCLUDE CURRENT ROW"),
+            Self::Group => f.write_str("EXCLUDE GROUP"),
+            Self::Ties => f.write_str("EXCLUDE TIES"),
+        }
+    }
+}
+
+impl Display for LimitClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "LIMIT {}", self.limit)?;
+        if let Some(offset) = &self.offset {
+            write!(f, " OFFSET {offset}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InsertAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            InsertActionKind::Values(values) => write!(f, "{values}"),
+            InsertActionKind::Select(select) => write!(f, "{select}"),
+            InsertActionKind::DefaultValues => f.write_str("DEFAULT VALUES"),
+        }
+    }
+}
+
+impl Display for InsertValues {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("VALUES ")?;
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "({})", comma_separated(row))?;
+        }
+        if let Some(upsert) = &self.upsert {
+            write!(f, " {upsert}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InsertSelect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.select)?;
+        if let Some(upsert) = &self.upsert {
+            write!(f, " {upsert}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for UpsertClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("ON CONFLICT ")?;
+        if let Some(target) = &self.target {
+            write!(f, "{target} ")?;
+        }
+        write!(f, "DO {}", self.action)
+    }
+}
+
+impl Display for UpsertTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({})", comma_separated(&self.columns))?;
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for UpsertAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Nothing => f.write_str("NOTHING"),
+            Self::Update(update) => write!(f, "{update}"),
+        }
+    }
+}
+
+impl Display for UpsertUpdate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "UPDATE SET {}", comma_separated(&self.assignments))?;
+        if let Some(expr) = &self.where_clause {
+            write!(f, " WHERE {expr}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Assignment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} = {}", self.target, self.expr)
+    }
+}
+
+impl Display for AssignmentTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Column(column) => write!(f, "{column}"),
+            Self::ColumnList(columns) => write!(f, "({})", comma_separated(columns)),
+        }
+    }
+}
+
+impl Display for WithClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("WITH ")?;
+        if self.recursive {
+            f.write_str("RECURSIVE ")?;
+        }
+        write!(f, "{}", comma_separated(&self.ctes))
+    }
+}
+
+impl Display for CommonTableExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.id)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", comma_separated(&self.columns))?;
+        }
+        f.write_str(" AS ")?;
+        if let Some(materialized) = &self.materialized {
+            write!(f, "{materialized} ")?;
+        }
+        write!(f, "({})", self.select)
+    }
+}
+
+impl Display for MaterializeHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Materialized => f.write_str("MATERIALIZED"),
+            Self::NotMaterialized => f.write_str("NOT MATERIALIZED"),
+        }
+    }
+}
+
+impl Display for ReturningClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "RETURNING {}", comma_separated(&self.columns))
+    }
+}
+
+impl Display for IndexedColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.expr)?;
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {collation}")?;
+        }
+        if let Some(order) = &self.order {
+            write!(f, " {order}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ColumnDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} {}", self.id, self.type_name)?;
+        if !self.constraint_list.constraints.is_empty() {
+            write!(f, " {}", self.constraint_list)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ColumnConstraintList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", Separated(&self.constraints, " "))
+    }
+}
+
+impl Display for ColumnConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+        match &self.kind {
+            ColumnConstraintKind::PrimaryKey(primary_key) => write!(f, "{primary_key}"),
+            ColumnConstraintKind::NotNull(action) => {
+                f.write_str("NOT NULL")?;
+                write_conflict_clause(f, action)
+            }
+            ColumnConstraintKind::Unique(action) => {
+                f.write_str("UNIQUE")?;
+                write_conflict_clause(f, action)
+            }
+            ColumnConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+            ColumnConstraintKind::Default(default) => write!(f, "DEFAULT {default}"),
+            ColumnConstraintKind::Collate(collation) => write!(f, "COLLATE {collation}"),
+            ColumnConstraintKind::Foreign(clause) => write!(f, "REFERENCES {clause}"),
+        }
+    }
+}
+
+// Write ` ON CONFLICT <action>` if there is an action.
+fn write_conflict_clause(f: &mut Formatter<'_>, action: &Option<ConflictAction>) -> Result {
+    match action {
+        Some(action) => write!(f, " ON CONFLICT {action}"),
+        None => Ok(()),
+    }
+}
+
+impl Display for ColumnConstraintPrimaryKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("PRIMARY KEY")?;
+        if let Some(order) = &self.order {
+            write!(f, " {order}")?;
+        }
+        write_conflict_clause(f, &self.conflict_action)
+    }
+}
+
+impl Display for ColumnConstraintDefault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::ParenExpr(expr) => write!(f, "({expr})"),
+            Self::LiteralValue(literal) => write!(f, "{literal}"),
+            // An unsigned number would parse back as a literal value.
+            Self::SignedNumber(number) if !number.is_negative() => write!(f, "+{number}"),
+            Self::SignedNumber(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+        match &self.kind {
+            TableConstraintKind::PrimaryKey(key) => write!(f, "PRIMARY KEY {key}"),
+            TableConstraintKind::Unique(key) => write!(f, "UNIQUE {key}"),
+            TableConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+            TableConstraintKind::ForeignKey(foreign_key) => write!(f, "{foreign_key}"),
+        }
+    }
+}
+
+impl Display for TableConstraintKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({})", comma_separated(&self.columns))?;
+        write_conflict_clause(f, &self.conflict_action)
+    }
+}
+
+impl Display for TableConstraintForeignKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "FOREIGN KEY ({}) REFERENCES {}",
+            comma_separated(&self.columns),
+            self.clause
+        )
+    }
+}
+
+/// The part of a foreign key constraint following `REFERENCES`.
+impl Display for ForeignKeyClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.id)?;
+        if !self.column_names.is_empty() {
+            write!(f, " ({})", comma_separated(&self.column_names))?;
+        }
+        if let Some(on) = &self.foreign_key_clause_on {
+            write!(f, " {on}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ForeignKeyClauseOn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ON {} {}", self.kind, self.action)
+    }
+}
+
+impl Display for ForeignKeyClauseOnKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Delete => f.write_str("DELETE"),
+            Self::Update => f.write_str("UPDATE"),
+        }
+    }
+}
+
+impl Display for ForeignKeyClauseActions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Set(ForeignKeyClauseActionSet::Null) => f.write_str("SET NULL"),
+            Self::Set(ForeignKeyClauseActionSet::Default) => f.write_str("SET DEFAULT"),
+            Self::Cascade => f.write_str("CASCADE"),
+            Self::Restrict => f.write_str("RESTRICT"),
+            Self::NoAction => f.write_str("NO ACTION"),
+        }
+    }
+}
+
+impl Display for ConflictAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Rollback => f.write_str("ROLLBACK"),
+            Self::Abort => f.write_str("ABORT"),
+            Self::Fail => f.write_str("FAIL"),
+            Self::Ignore => f.write_str("IGNORE"),
+            Self::Replace => f.write_str("REPLACE"),
+        }
+    }
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Asc => f.write_str("ASC"),
+            Self::Desc => f.write_str("DESC"),
+        }
+    }
+}
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.external_type)?;
+        if let Some(number_field) = &self.number_field {
+            write!(f, "{number_field}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ExternalType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Char => f.write_str("CHAR"),
+            Self::Integer => f.write_str("INTEGER"),
+            Self::Numeric => f.write_str("NUMERIC"),
+            Self::Serial => f.write_str("SERIAL"),
+            Self::Varchar => f.write_str("VARCHAR"),
+        }
+    }
+}
+
+impl Display for TypeNameNumberField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({}", self.first)?;
+        if let Some(second) = &self.second {
+            write!(f, ", {second}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl SignedNumber {
+    fn is_negative(&self) -> bool {
+        match self {
+            Self::Integer(int) => int.value < 0,
+            Self::Float(float) => float.value.is_sign_negative(),
+        }
+    }
+}
+
+/// A signed number carries its sign in the value, unlike an integer literal.
+impl Display for SignedNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Integer(int) => write!(f, "{}", int.value),
+            Self::Float(float) => write!(f, "{}", float),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Expr::BinOp(expr) => write!(f, "{} {} {}", expr.left, expr.op, expr.right),
+            Expr::UnaryOp(expr) => write!(f, "{expr}"),
+            Expr::Compare(expr) => write!(f, "{} {} {}", expr.left, expr.op, expr.right),
+            Expr::BoolOp(expr) => write!(f, "{} {} {}", expr.left, expr.op, expr.right),
+            Expr::LiteralValue(literal) => write!(f, "{literal}"),
+            Expr::Column(expr) => write!(f, "{expr}"),
+            Expr::List(expr) => write!(f, "({})", comma_separated(&expr.exprs)),
+            Expr::Subquery(expr) => write!(f, "({})", expr.select),
+            Expr::Collate(expr) => write!(f, "{} COLLATE {}", expr.expr, expr.collation),
+            Expr::Like(expr) => {
+                let not = if expr.negated { "NOT " } else { "" };
+                write!(f, "{} {not}LIKE {}", expr.expr, expr.pattern)
+            }
+            Expr::IsNull(expr) => {
+                let not = if expr.negated { "NOT " } else { "" };
+                write!(f, "{} IS {not}NULL", expr.expr)
+            }
+            Expr::Is(expr) => {
+                let not = if expr.negated { "NOT " } else { "" };
+                let distinct = if expr.distinct_from { "DISTINCT FROM " } else { "" };
+                write!(f, "{} IS {not}{distinct}{}", expr.left, expr.right)
+            }
+            Expr::Between(expr) => {
+                let not = if expr.negated { "NOT " } else { "" };
+                write!(f, "{} {not}BETWEEN {} AND {}", expr.expr, expr.low, expr.high)
+            }
+            Expr::In(expr) => write!(f, "{expr}"),
+            Expr::Exists(expr) => {
+                let not = if expr.negated { "NOT " } else { "" };
+                write!(f, "{not}EXISTS ({})", expr.select)
+            }
+            Expr::Function(expr) => write!(f, "{expr}"),
+            Expr::Case(expr) => write!(f, "{expr}"),
+            Expr::Cast(expr) => write!(f, "CAST({} AS {})", expr.expr, expr.type_name),
+            Expr::Parameter(expr) => write!(f, "{}", expr.kind),
+            Expr::Invalid(_) => Ok(()),
+        }
+    }
+}
+
+impl Display for ExprUnaryOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let operand = self.operand.to_string();
+        match self.op {
+            UnaryOperator::Not => write!(f, "NOT {operand}"),
+            // Keep `- -1` from turning into a `--` comment.
+            _ if operand.starts_with(['-', '+']) => write!(f, "{} {operand}", self.op),
+            _ => write!(f, "{}{operand}", self.op),
+        }
+    }
+}
+
+impl Display for ExprIn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let not = if self.negated { "NOT " } else { "" };
+        write!(f, "{} {not}IN ", self.expr)?;
+        match &self.kind {
+            ExprInKind::List(exprs) => write!(f, "({})", comma_separated(exprs)),
+            ExprInKind::Select(select) => write!(f, "({select})"),
+            ExprInKind::Table(table) => write!(f, "{table}"),
+        }
+    }
+}
+
+impl Display for ExprFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}(", self.name)?;
+        if self.distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        match &self.args {
+            FunctionArgs::Star => f.write_str("*")?,
+            FunctionArgs::List(args) => write!(f, "{}", comma_separated(args))?,
+        }
+        f.write_str(")")?;
+        if let Some(filter) = &self.filter {
+            write!(f, " FILTER (WHERE {filter})")?;
+        }
+        if let Some(over) = &self.over {
+            write!(f, " OVER {over}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for OverClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Window(spec) => write!(f, "{spec}"),
+            Self::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Display for ExprCase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("CASE")?;
+        if let Some(operand) = &self.operand {
+            write!(f, " {operand}")?;
+        }
+        for branch in &self.branches {
+            write!(f, " {branch}")?;
+        }
+        if let Some(else_expr) = &self.else_expr {
+            write!(f, " ELSE {else_expr}")?;
+        }
+        f.write_str(" END")
+    }
+}
+
+impl Display for CaseBranch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "WHEN {} THEN {}", self.condition, self.result)
+    }
+}
+
+impl Display for ParameterKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Anonymous => f.write_str("?"),
+            Self::Numbered(number) => write!(f, "?{number}"),
+            Self::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Display for ExprColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if let Some(table) = &self.table {
+            write!(f, "{table}.")?;
+        }
+        write!(f, "{}", self.column)
+    }
+}
+
+/// Identifiers that would not lex back as the same bare name are quoted.
+impl Display for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if !self.is_valid() {
+            return Ok(());
+        }
+        let name = self.id.as_str();
+        let mut chars = name.chars();
+        let bare = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            && !KEYWORDS.contains(&name.to_ascii_uppercase().as_str());
+        if bare {
+            f.write_str(name)
+        } else {
+            write!(f, "\"{}\"", name.replace('"', "\"\""))
+        }
+    }
+}
+
+impl Display for LiteralValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::StringLiteral(literal) => write!(f, "{literal}"),
+            Self::IntLiteral(literal) => write!(f, "{literal}"),
+            Self::FloatLiteral(literal) => write!(f, "{literal}"),
+            Self::NullLiteral(_) => f.write_str("NULL"),
+            Self::BoolLiteral(literal) => f.write_str(if literal.value { "TRUE" } else { "FALSE" }),
+            Self::Blob(literal) => write!(f, "{literal}"),
+        }
+    }
+}
+
+impl Display for StringLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "'{}'", self.value.replace('\'', "''"))
+    }
+}
+
+impl Display for BlobLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("X'")?;
+        for byte in &self.value {
+            write!(f, "{byte:02X}")?;
+        }
+        f.write_str("'")
+    }
+}
+
+/// Integer literals are unsigned in SQL text. A negative value only comes
+/// from a hexadecimal literal above `i64::MAX` and is printed as one.
+impl Display for IntLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.value < 0 {
+            write!(f, "0x{:X}", self.value as u64)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+/// Uses the shortest representation that reads back as the same value,
+/// always with a decimal point or an exponent.
+impl Display for FloatLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl Display for BooleanOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::And => f.write_str("AND"),
+            Self::Or => f.write_str("OR"),
+        }
+    }
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Add => f.write_str("+"),
+            Self::Sub => f.write_str("-"),
+            Self::Mult => f.write_str("*"),
+            Self::Div => f.write_str("/"),
+            Self::Mod => f.write_str("%"),
+        }
+    }
+}
+
+impl Display for CmpOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Gt => f.write_str(">"),
+            Self::GtE => f.write_str(">="),
+            Self::Lt => f.write_str("<"),
+            Self::LtE => f.write_str("<="),
+            Self::NotEq => f.write_str("!="),
+            Self::Eq => f.write_str("="),
+        }
+    }
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Not => f.write_str("NOT"),
+            Self::Positive => f.write_str("+"),
+            Self::Negative => f.write_str("-"),
+        }
+    }
+}
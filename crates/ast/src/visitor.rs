@@ -1,19 +1,720 @@
+//! A read-only traversal of the syntax tree.
+//!
+//! Every `visit_*` method defaults to the matching `walk_*` function, which
+//! visits the children of the node in source order. An implementation only
+//! overrides the nodes it is interested in and calls the `walk_*` function
+//! from its override when it still wants the children to be visited.
+//!
+//! Keyword-like enums without a span (operators, [`ast::Order`],
+//! [`ast::ConflictAction`], ...) are not visited on their own, they are
+//! available on the node that holds them.
+
 use crate::{self as ast, Stmt};
 
 pub trait Visitor {
-    fn visit_stmt(&mut self, stmt: &Stmt) {}
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt);
+    }
+    fn visit_stmt_alter(&mut self, stmt: &ast::StmtAlter) {
+        walk_stmt_alter(self, stmt);
+    }
+    fn visit_stmt_begin(&mut self, _stmt: &ast::StmtBegin) {}
+    fn visit_stmt_commit(&mut self, _stmt: &ast::StmtCommit) {}
+    fn visit_stmt_create(&mut self, stmt: &ast::StmtCreate) {
+        walk_stmt_create(self, stmt);
+    }
+    fn visit_stmt_delete(&mut self, stmt: &ast::StmtDelete) {
+        walk_stmt_delete(self, stmt);
+    }
+    fn visit_stmt_drop(&mut self, stmt: &ast::StmtDrop) {
+        walk_stmt_drop(self, stmt);
+    }
+    fn visit_stmt_insert(&mut self, stmt: &ast::StmtInsert) {
+        walk_stmt_insert(self, stmt);
+    }
+    fn visit_stmt_invalid(&mut self, _stmt: &ast::StmtInvalid) {}
+    fn visit_stmt_reindex(&mut self, stmt: &ast::StmtReindex) {
+        walk_stmt_reindex(self, stmt);
+    }
+    fn visit_stmt_release(&mut self, stmt: &ast::StmtRelease) {
+        walk_stmt_release(self, stmt);
+    }
+    fn visit_stmt_rollback(&mut self, stmt: &ast::StmtRollback) {
+        walk_stmt_rollback(self, stmt);
+    }
+    fn visit_stmt_savepoint(&mut self, stmt: &ast::StmtSavepoint) {
+        walk_stmt_savepoint(self, stmt);
+    }
+    /// Called for top level selects as well as for every nested select
+    /// (subqueries, compound selects, CTE bodies, ...).
+    fn visit_stmt_select(&mut self, stmt: &ast::StmtSelect) {
+        walk_stmt_select(self, stmt);
+    }
+    fn visit_stmt_update(&mut self, stmt: &ast::StmtUpdate) {
+        walk_stmt_update(self, stmt);
+    }
+
+    fn visit_alter_table_action(&mut self, action: &ast::AlterTableAction) {
+        walk_alter_table_action(self, action);
+    }
+    fn visit_create_index(&mut self, index: &ast::CreateIndex) {
+        walk_create_index(self, index);
+    }
+    fn visit_create_table(&mut self, table: &ast::CreateTable) {
+        walk_create_table(self, table);
+    }
+    fn visit_column_def(&mut self, column: &ast::ColumnDef) {
+        walk_column_def(self, column);
+    }
+    fn visit_column_constraint(&mut self, constraint: &ast::ColumnConstraint) {
+        walk_column_constraint(self, constraint);
+    }
+    fn visit_table_constraint(&mut self, constraint: &ast::TableConstraint) {
+        walk_table_constraint(self, constraint);
+    }
+    fn visit_foreign_key_clause(&mut self, clause: &ast::ForeignKeyClause) {
+        walk_foreign_key_clause(self, clause);
+    }
+    fn visit_type_name(&mut self, type_name: &ast::TypeName) {
+        walk_type_name(self, type_name);
+    }
+    fn visit_signed_number(&mut self, _number: &ast::SignedNumber) {}
+
+    fn visit_with_clause(&mut self, with: &ast::WithClause) {
+        walk_with_clause(self, with);
+    }
+    fn visit_common_table_expr(&mut self, cte: &ast::CommonTableExpr) {
+        walk_common_table_expr(self, cte);
+    }
+    fn visit_result_column(&mut self, column: &ast::ResultColumn) {
+        walk_result_column(self, column);
+    }
+    fn visit_from_clause(&mut self, from: &ast::FromClause) {
+        walk_from_clause(self, from);
+    }
+    fn visit_from_item(&mut self, item: &ast::FromItem) {
+        walk_from_item(self, item);
+    }
+    fn visit_join_clause(&mut self, join: &ast::JoinClause) {
+        walk_join_clause(self, join);
+    }
+    fn visit_join_constraint(&mut self, constraint: &ast::JoinConstraint) {
+        walk_join_constraint(self, constraint);
+    }
+    fn visit_table_name(&mut self, table: &ast::TableName) {
+        walk_table_name(self, table);
+    }
+    fn visit_quantified_table_name(&mut self, table: &ast::QuantifiedTableName) {
+        walk_quantified_table_name(self, table);
+    }
+    fn visit_compound_select(&mut self, compound: &ast::CompoundSelect) {
+        walk_compound_select(self, compound);
+    }
+    fn visit_ordering_term(&mut self, term: &ast::OrderingTerm) {
+        walk_ordering_term(self, term);
+    }
+    fn visit_named_window(&mut self, window: &ast::NamedWindow) {
+        walk_named_window(self, window);
+    }
+    fn visit_window_spec(&mut self, spec: &ast::WindowSpec) {
+        walk_window_spec(self, spec);
+    }
+    fn visit_window_frame(&mut self, frame: &ast::WindowFrame) {
+        walk_window_frame(self, frame);
+    }
+    fn visit_frame_bound(&mut self, bound: &ast::FrameBound) {
+        walk_frame_bound(self, bound);
+    }
+    fn visit_limit_clause(&mut self, limit: &ast::LimitClause) {
+        walk_limit_clause(self, limit);
+    }
+
+    fn visit_insert_action(&mut self, action: &ast::InsertAction) {
+        walk_insert_action(self, action);
+    }
+    fn visit_upsert_clause(&mut self, upsert: &ast::UpsertClause) {
+        walk_upsert_clause(self, upsert);
+    }
+    fn visit_assignment(&mut self, assignment: &ast::Assignment) {
+        walk_assignment(self, assignment);
+    }
+    fn visit_returning_clause(&mut self, returning: &ast::ReturningClause) {
+        walk_returning_clause(self, returning);
+    }
+    fn visit_indexed_column(&mut self, column: &ast::IndexedColumn) {
+        walk_indexed_column(self, column);
+    }
+
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        walk_expr(self, expr);
+    }
+    fn visit_case_branch(&mut self, branch: &ast::CaseBranch) {
+        walk_case_branch(self, branch);
+    }
+    fn visit_over_clause(&mut self, over: &ast::OverClause) {
+        walk_over_clause(self, over);
+    }
+    fn visit_parameter(&mut self, _parameter: &ast::ExprParameter) {}
+    fn visit_expr_invalid(&mut self, _expr: &ast::ExprInvalid) {}
+    fn visit_literal_value(&mut self, _literal: &ast::LiteralValue) {}
+    fn visit_identifier(&mut self, _identifier: &ast::Identifier) {}
 }
 
-pub fn walk_stmt<V: Visitor>(visitor: &mut V, stmt: &Stmt) {
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
     match stmt {
-        Stmt::Alter(ast::StmtAlter { .. }) => {}
-        Stmt::Begin(ast::StmtBegin { .. }) => {}
-        Stmt::Commit(ast::StmtCommit { .. }) => {}
-        Stmt::Invalid(ast::StmtInvalid { .. }) => {}
-        Stmt::Savepoint(ast::StmtSavepoint { .. }) => {}
-        Stmt::Reindex(ast::StmtReindex { .. }) => {}
-        Stmt::Release(ast::StmtRelease { .. }) => {}
-        Stmt::Rollback(ast::StmtRollback { .. }) => {}
-        Stmt::Drop(ast::StmtDrop { .. }) => {}
+        Stmt::Alter(stmt) => visitor.visit_stmt_alter(stmt),
+        Stmt::Begin(stmt) => visitor.visit_stmt_begin(stmt),
+        Stmt::Commit(stmt) => visitor.visit_stmt_commit(stmt),
+        Stmt::Create(stmt) => visitor.visit_stmt_create(stmt),
+        Stmt::Delete(stmt) => visitor.visit_stmt_delete(stmt),
+        Stmt::Invalid(stmt) => visitor.visit_stmt_invalid(stmt),
+        Stmt::Savepoint(stmt) => visitor.visit_stmt_savepoint(stmt),
+        Stmt::Reindex(stmt) => visitor.visit_stmt_reindex(stmt),
+        Stmt::Release(stmt) => visitor.visit_stmt_release(stmt),
+        Stmt::Rollback(stmt) => visitor.visit_stmt_rollback(stmt),
+        Stmt::Drop(stmt) => visitor.visit_stmt_drop(stmt),
+        Stmt::Insert(stmt) => visitor.visit_stmt_insert(stmt),
+        Stmt::Select(stmt) => visitor.visit_stmt_select(stmt),
+        Stmt::Update(stmt) => visitor.visit_stmt_update(stmt),
+    }
+}
+
+pub fn walk_stmt_alter<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtAlter) {
+    visitor.visit_identifier(&stmt.id);
+    visitor.visit_alter_table_action(&stmt.action);
+}
+
+pub fn walk_stmt_create<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtCreate) {
+    visitor.visit_identifier(&stmt.id);
+    match &stmt.kind {
+        ast::CreateKind::Table(table) => visitor.visit_create_table(table),
+        ast::CreateKind::Index(index) => visitor.visit_create_index(index),
+    }
+}
+
+pub fn walk_stmt_delete<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtDelete) {
+    if let Some(with) = &stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_quantified_table_name(&stmt.table);
+    if let Some(expr) = &stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    if let Some(returning) = &stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_stmt_drop<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtDrop) {
+    visitor.visit_identifier(&stmt.id);
+}
+
+pub fn walk_stmt_insert<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtInsert) {
+    if let Some(with) = &stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_table_name(&stmt.table);
+    for column in &stmt.columns {
+        visitor.visit_identifier(column);
+    }
+    visitor.visit_insert_action(&stmt.action);
+    if let Some(returning) = &stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_stmt_reindex<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtReindex) {
+    if let Some(id) = &stmt.id {
+        visitor.visit_identifier(id);
+    }
+}
+
+pub fn walk_stmt_release<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtRelease) {
+    visitor.visit_identifier(&stmt.id);
+}
+
+pub fn walk_stmt_rollback<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtRollback) {
+    if let Some(id) = &stmt.id {
+        visitor.visit_identifier(id);
+    }
+}
+
+pub fn walk_stmt_savepoint<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtSavepoint) {
+    visitor.visit_identifier(&stmt.id);
+}
+
+pub fn walk_stmt_select<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtSelect) {
+    if let Some(with) = &stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    for column in &stmt.columns {
+        visitor.visit_result_column(column);
+    }
+    if let Some(from) = &stmt.from {
+        visitor.visit_from_clause(from);
+    }
+    if let Some(expr) = &stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    for expr in &stmt.group_by {
+        visitor.visit_expr(expr);
+    }
+    if let Some(expr) = &stmt.having {
+        visitor.visit_expr(expr);
+    }
+    for window in &stmt.window {
+        visitor.visit_named_window(window);
+    }
+    if let Some(compound) = &stmt.compound {
+        visitor.visit_compound_select(compound);
+    }
+    for term in &stmt.order_by {
+        visitor.visit_ordering_term(term);
+    }
+    if let Some(limit) = &stmt.limit {
+        visitor.visit_limit_clause(limit);
+    }
+}
+
+pub fn walk_stmt_update<V: Visitor + ?Sized>(visitor: &mut V, stmt: &ast::StmtUpdate) {
+    if let Some(with) = &stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_quantified_table_name(&stmt.table);
+    for assignment in &stmt.assignments {
+        visitor.visit_assignment(assignment);
+    }
+    if let Some(from) = &stmt.from {
+        visitor.visit_from_clause(from);
+    }
+    if let Some(expr) = &stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    if let Some(returning) = &stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_alter_table_action<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    action: &ast::AlterTableAction,
+) {
+    match &action.kind {
+        ast::AlterTableActionKind::Rename(rename) => match &rename.kind {
+            ast::AlterTableRenameKind::Table(id) => visitor.visit_identifier(id),
+            ast::AlterTableRenameKind::Column(from, to) => {
+                visitor.visit_identifier(from);
+                visitor.visit_identifier(to);
+            }
+        },
+        ast::AlterTableActionKind::Add(add) => visitor.visit_column_def(&add.column),
+        ast::AlterTableActionKind::Drop(drop) => visitor.visit_identifier(&drop.id),
+    }
+}
+
+pub fn walk_create_index<V: Visitor + ?Sized>(visitor: &mut V, index: &ast::CreateIndex) {
+    visitor.visit_identifier(&index.table);
+    for column in &index.columns {
+        visitor.visit_indexed_column(column);
+    }
+    if let Some(expr) = &index.where_clause {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_create_table<V: Visitor + ?Sized>(visitor: &mut V, table: &ast::CreateTable) {
+    match &table.source {
+        ast::CreateTableSource::Columns(columns) => {
+            for column in &columns.columns {
+                visitor.visit_column_def(column);
+            }
+            for constraint in &columns.constraints {
+                visitor.visit_table_constraint(constraint);
+            }
+        }
+        ast::CreateTableSource::Select(select) => visitor.visit_stmt_select(select),
+    }
+}
+
+pub fn walk_column_def<V: Visitor + ?Sized>(visitor: &mut V, column: &ast::ColumnDef) {
+    visitor.visit_identifier(&column.id);
+    visitor.visit_type_name(&column.type_name);
+    for constraint in &column.constraint_list.constraints {
+        visitor.visit_column_constraint(constraint);
+    }
+}
+
+pub fn walk_column_constraint<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    constraint: &ast::ColumnConstraint,
+) {
+    if let Some(name) = &constraint.name {
+        visitor.visit_identifier(name);
+    }
+    match &constraint.kind {
+        ast::ColumnConstraintKind::PrimaryKey(_)
+        | ast::ColumnConstraintKind::NotNull(_)
+        | ast::ColumnConstraintKind::Unique(_) => {}
+        ast::ColumnConstraintKind::Check(expr) => visitor.visit_expr(expr),
+        ast::ColumnConstraintKind::Default(default) => match default {
+            ast::ColumnConstraintDefault::ParenExpr(expr) => visitor.visit_expr(expr),
+            ast::ColumnConstraintDefault::LiteralValue(literal) => {
+                visitor.visit_literal_value(literal)
+            }
+            ast::ColumnConstraintDefault::SignedNumber(number) => {
+                visitor.visit_signed_number(number)
+            }
+        },
+        ast::ColumnConstraintKind::Collate(collation) => visitor.visit_identifier(collation),
+        ast::ColumnConstraintKind::Foreign(clause) => visitor.visit_foreign_key_clause(clause),
+    }
+}
+
+pub fn walk_table_constraint<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    constraint: &ast::TableConstraint,
+) {
+    if let Some(name) = &constraint.name {
+        visitor.visit_identifier(name);
+    }
+    match &constraint.kind {
+        ast::TableConstraintKind::PrimaryKey(key) | ast::TableConstraintKind::Unique(key) => {
+            for column in &key.columns {
+                visitor.visit_indexed_column(column);
+            }
+        }
+        ast::TableConstraintKind::Check(expr) => visitor.visit_expr(expr),
+        ast::TableConstraintKind::ForeignKey(foreign_key) => {
+            for column in &foreign_key.columns {
+                visitor.visit_identifier(column);
+            }
+            visitor.visit_foreign_key_clause(&foreign_key.clause);
+        }
+    }
+}
+
+pub fn walk_foreign_key_clause<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    clause: &ast::ForeignKeyClause,
+) {
+    visitor.visit_identifier(&clause.id);
+    for column in &clause.column_names {
+        visitor.visit_identifier(column);
+    }
+}
+
+pub fn walk_type_name<V: Visitor + ?Sized>(visitor: &mut V, type_name: &ast::TypeName) {
+    if let Some(field) = &type_name.number_field {
+        visitor.visit_signed_number(&field.first);
+        if let Some(second) = &field.second {
+            visitor.visit_signed_number(second);
+        }
+    }
+}
+
+pub fn walk_with_clause<V: Visitor + ?Sized>(visitor: &mut V, with: &ast::WithClause) {
+    for cte in &with.ctes {
+        visitor.visit_common_table_expr(cte);
+    }
+}
+
+pub fn walk_common_table_expr<V: Visitor + ?Sized>(visitor: &mut V, cte: &ast::CommonTableExpr) {
+    visitor.visit_identifier(&cte.id);
+    for column in &cte.columns {
+        visitor.visit_identifier(column);
+    }
+    visitor.visit_stmt_select(&cte.select);
+}
+
+pub fn walk_result_column<V: Visitor + ?Sized>(visitor: &mut V, column: &ast::ResultColumn) {
+    match &column.kind {
+        ast::ResultColumnKind::All => {}
+        ast::ResultColumnKind::TableAll(table) => visitor.visit_identifier(table),
+        ast::ResultColumnKind::Expr(expr, alias) => {
+            visitor.visit_expr(expr);
+            if let Some(alias) = alias {
+                visitor.visit_identifier(alias);
+            }
+        }
+    }
+}
+
+pub fn walk_from_clause<V: Visitor + ?Sized>(visitor: &mut V, from: &ast::FromClause) {
+    visitor.visit_from_item(&from.item);
+}
+
+pub fn walk_from_item<V: Visitor + ?Sized>(visitor: &mut V, item: &ast::FromItem) {
+    match &item.kind {
+        ast::FromItemKind::Table(table) => visitor.visit_quantified_table_name(table),
+        ast::FromItemKind::Subquery(subquery) => {
+            visitor.visit_stmt_select(&subquery.select);
+            if let Some(alias) = &subquery.alias {
+                visitor.visit_identifier(alias);
+            }
+        }
+        ast::FromItemKind::Join(join) => visitor.visit_join_clause(join),
+    }
+}
+
+pub fn walk_join_clause<V: Visitor + ?Sized>(visitor: &mut V, join: &ast::JoinClause) {
+    visitor.visit_from_item(&join.left);
+    visitor.visit_from_item(&join.right);
+    if let Some(constraint) = &join.constraint {
+        visitor.visit_join_constraint(constraint);
+    }
+}
+
+pub fn walk_join_constraint<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    constraint: &ast::JoinConstraint,
+) {
+    match constraint {
+        ast::JoinConstraint::On(expr) => visitor.visit_expr(expr),
+        ast::JoinConstraint::Using(columns) => {
+            for column in columns {
+                visitor.visit_identifier(column);
+            }
+        }
+    }
+}
+
+pub fn walk_table_name<V: Visitor + ?Sized>(visitor: &mut V, table: &ast::TableName) {
+    visitor.visit_identifier(&table.id);
+    if let Some(alias) = &table.alias {
+        visitor.visit_identifier(alias);
+    }
+}
+
+pub fn walk_quantified_table_name<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    table: &ast::QuantifiedTableName,
+) {
+    visitor.visit_identifier(&table.id);
+    if let Some(alias) = &table.alias {
+        visitor.visit_identifier(alias);
+    }
+    if let Some(ast::IndexedHint::IndexedBy(index)) = &table.indexed {
+        visitor.visit_identifier(index);
+    }
+}
+
+pub fn walk_compound_select<V: Visitor + ?Sized>(visitor: &mut V, compound: &ast::CompoundSelect) {
+    visitor.visit_stmt_select(&compound.select);
+}
+
+pub fn walk_ordering_term<V: Visitor + ?Sized>(visitor: &mut V, term: &ast::OrderingTerm) {
+    visitor.visit_expr(&term.expr);
+    if let Some(collation) = &term.collation {
+        visitor.visit_identifier(collation);
+    }
+}
+
+pub fn walk_named_window<V: Visitor + ?Sized>(visitor: &mut V, window: &ast::NamedWindow) {
+    visitor.visit_identifier(&window.id);
+    visitor.visit_window_spec(&window.spec);
+}
+
+pub fn walk_window_spec<V: Visitor + ?Sized>(visitor: &mut V, spec: &ast::WindowSpec) {
+    if let Some(base) = &spec.base {
+        visitor.visit_identifier(base);
+    }
+    for expr in &spec.partition_by {
+        visitor.visit_expr(expr);
+    }
+    for term in &spec.order_by {
+        visitor.visit_ordering_term(term);
+    }
+    if let Some(frame) = &spec.frame {
+        visitor.visit_window_frame(frame);
+    }
+}
+
+pub fn walk_window_frame<V: Visitor + ?Sized>(visitor: &mut V, frame: &ast::WindowFrame) {
+    visitor.visit_frame_bound(&frame.start);
+    if let Some(end) = &frame.end {
+        visitor.visit_frame_bound(end);
+    }
+}
+
+pub fn walk_frame_bound<V: Visitor + ?Sized>(visitor: &mut V, bound: &ast::FrameBound) {
+    match bound {
+        ast::FrameBound::Preceding(expr) | ast::FrameBound::Following(expr) => {
+            visitor.visit_expr(expr)
+        }
+        ast::FrameBound::UnboundedPreceding
+        | ast::FrameBound::CurrentRow
+        | ast::FrameBound::UnboundedFollowing => {}
+    }
+}
+
+pub fn walk_limit_clause<V: Visitor + ?Sized>(visitor: &mut V, limit: &ast::LimitClause) {
+    visitor.visit_expr(&limit.limit);
+    if let Some(offset) = &limit.offset {
+        visitor.visit_expr(offset);
+    }
+}
+
+pub fn walk_insert_action<V: Visitor + ?Sized>(visitor: &mut V, action: &ast::InsertAction) {
+    match &action.kind {
+        ast::InsertActionKind::Values(values) => {
+            for row in &values.rows {
+                for expr in row {
+                    visitor.visit_expr(expr);
+                }
+            }
+            if let Some(upsert) = &values.upsert {
+                visitor.visit_upsert_clause(upsert);
+            }
+        }
+        ast::InsertActionKind::Select(select) => {
+            visitor.visit_stmt_select(&select.select);
+            if let Some(upsert) = &select.upsert {
+                visitor.visit_upsert_clause(upsert);
+            }
+        }
+        ast::InsertActionKind::DefaultValues => {}
+    }
+}
+
+pub fn walk_upsert_clause<V: Visitor + ?Sized>(visitor: &mut V, upsert: &ast::UpsertClause) {
+    if let Some(target) = &upsert.target {
+        for column in &target.columns {
+            visitor.visit_indexed_column(column);
+        }
+        if let Some(expr) = &target.where_clause {
+            visitor.visit_expr(expr);
+        }
+    }
+    if let ast::UpsertAction::Update(update) = &upsert.action {
+        for assignment in &update.assignments {
+            visitor.visit_assignment(assignment);
+        }
+        if let Some(expr) = &update.where_clause {
+            visitor.visit_expr(expr);
+        }
+    }
+}
+
+pub fn walk_assignment<V: Visitor + ?Sized>(visitor: &mut V, assignment: &ast::Assignment) {
+    match &assignment.target {
+        ast::AssignmentTarget::Column(column) => visitor.visit_identifier(column),
+        ast::AssignmentTarget::ColumnList(columns) => {
+            for column in columns {
+                visitor.visit_identifier(column);
+            }
+        }
+    }
+    visitor.visit_expr(&assignment.expr);
+}
+
+pub fn walk_returning_clause<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    returning: &ast::ReturningClause,
+) {
+    for column in &returning.columns {
+        visitor.visit_result_column(column);
+    }
+}
+
+pub fn walk_indexed_column<V: Visitor + ?Sized>(visitor: &mut V, column: &ast::IndexedColumn) {
+    visitor.visit_expr(&column.expr);
+    if let Some(collation) = &column.collation {
+        visitor.visit_identifier(collation);
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &ast::Expr) {
+    match expr {
+        ast::Expr::BinOp(ast::ExprBinaryOp { left, right, .. })
+        | ast::Expr::Compare(ast::ExprCompare { left, right, .. })
+        | ast::Expr::BoolOp(ast::ExprBoolOp { left, right, .. })
+        | ast::Expr::Is(ast::ExprIs { left, right, .. }) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        ast::Expr::UnaryOp(unary) => visitor.visit_expr(&unary.operand),
+        ast::Expr::LiteralValue(literal) => visitor.visit_literal_value(literal),
+        ast::Expr::Column(column) => {
+            if let Some(table) = &column.table {
+                visitor.visit_identifier(table);
+            }
+            visitor.visit_identifier(&column.column);
+        }
+        ast::Expr::List(list) => {
+            for expr in &list.exprs {
+                visitor.visit_expr(expr);
+            }
+        }
+        ast::Expr::Subquery(subquery) => visitor.visit_stmt_select(&subquery.select),
+        ast::Expr::Collate(collate) => {
+            visitor.visit_expr(&collate.expr);
+            visitor.visit_identifier(&collate.collation);
+        }
+        ast::Expr::Like(like) => {
+            visitor.visit_expr(&like.expr);
+            visitor.visit_expr(&like.pattern);
+        }
+        ast::Expr::IsNull(is_null) => visitor.visit_expr(&is_null.expr),
+        ast::Expr::Between(between) => {
+            visitor.visit_expr(&between.expr);
+            visitor.visit_expr(&between.low);
+            visitor.visit_expr(&between.high);
+        }
+        ast::Expr::In(in_expr) => {
+            visitor.visit_expr(&in_expr.expr);
+            match &in_expr.kind {
+                ast::ExprInKind::List(exprs) => {
+                    for expr in exprs {
+                        visitor.visit_expr(expr);
+                    }
+                }
+                ast::ExprInKind::Select(select) => visitor.visit_stmt_select(select),
+                ast::ExprInKind::Table(table) => visitor.visit_identifier(table),
+            }
+        }
+        ast::Expr::Exists(exists) => visitor.visit_stmt_select(&exists.select),
+        ast::Expr::Function(function) => {
+            visitor.visit_identifier(&function.name);
+            if let ast::FunctionArgs::List(args) = &function.args {
+                for arg in args {
+                    visitor.visit_expr(arg);
+                }
+            }
+            if let Some(filter) = &function.filter {
+                visitor.visit_expr(filter);
+            }
+            if let Some(over) = &function.over {
+                visitor.visit_over_clause(over);
+            }
+        }
+        ast::Expr::Case(case) => {
+            if let Some(operand) = &case.operand {
+                visitor.visit_expr(operand);
+            }
+            for branch in &case.branches {
+                visitor.visit_case_branch(branch);
+            }
+            if let Some(else_expr) = &case.else_expr {
+                visitor.visit_expr(else_expr);
+            }
+        }
+        ast::Expr::Cast(cast) => {
+            visitor.visit_expr(&cast.expr);
+            visitor.visit_type_name(&cast.type_name);
+        }
+        ast::Expr::Parameter(parameter) => visitor.visit_parameter(parameter),
+        ast::Expr::Invalid(expr) => visitor.visit_expr_invalid(expr),
+    }
+}
+
+pub fn walk_case_branch<V: Visitor + ?Sized>(visitor: &mut V, branch: &ast::CaseBranch) {
+    visitor.visit_expr(&branch.condition);
+    visitor.visit_expr(&branch.result);
+}
+
+pub fn walk_over_clause<V: Visitor + ?Sized>(visitor: &mut V, over: &ast::OverClause) {
+    match over {
+        ast::OverClause::Window(spec) => visitor.visit_window_spec(spec),
+        ast::OverClause::Named(name) => visitor.visit_identifier(name),
     }
 }
@@ -0,0 +1,721 @@
+//! An in-place rewriting traversal of the syntax tree.
+//!
+//! Mirrors [`crate::visitor::Visitor`] with mutable references, so a pass can
+//! rewrite any node it is handed, including replacing an [`ast::Expr`] with
+//! a different variant. The traversal never touches spans: a rewritten node
+//! keeps whatever span it is given, so a replacement should carry the span
+//! of the node it replaces, e.g. a parameter replaced by a literal takes the
+//! span of the parameter.
+//!
+//! An override that replaces a node and then calls the `walk_*` function
+//! walks the children of the replacement, not of the original node.
+
+use crate::{self as ast, Stmt};
+
+pub trait VisitorMut {
+    fn visit_stmt(&mut self, stmt: &mut Stmt) {
+        walk_stmt(self, stmt);
+    }
+    fn visit_stmt_alter(&mut self, stmt: &mut ast::StmtAlter) {
+        walk_stmt_alter(self, stmt);
+    }
+    fn visit_stmt_begin(&mut self, _stmt: &mut ast::StmtBegin) {}
+    fn visit_stmt_commit(&mut self, _stmt: &mut ast::StmtCommit) {}
+    fn visit_stmt_create(&mut self, stmt: &mut ast::StmtCreate) {
+        walk_stmt_create(self, stmt);
+    }
+    fn visit_stmt_delete(&mut self, stmt: &mut ast::StmtDelete) {
+        walk_stmt_delete(self, stmt);
+    }
+    fn visit_stmt_drop(&mut self, stmt: &mut ast::StmtDrop) {
+        walk_stmt_drop(self, stmt);
+    }
+    fn visit_stmt_insert(&mut self, stmt: &mut ast::StmtInsert) {
+        walk_stmt_insert(self, stmt);
+    }
+    fn visit_stmt_invalid(&mut self, _stmt: &mut ast::StmtInvalid) {}
+    fn visit_stmt_reindex(&mut self, stmt: &mut ast::StmtReindex) {
+        walk_stmt_reindex(self, stmt);
+    }
+    fn visit_stmt_release(&mut self, stmt: &mut ast::StmtRelease) {
+        walk_stmt_release(self, stmt);
+    }
+    fn visit_stmt_rollback(&mut self, stmt: &mut ast::StmtRollback) {
+        walk_stmt_rollback(self, stmt);
+    }
+    fn visit_stmt_savepoint(&mut self, stmt: &mut ast::StmtSavepoint) {
+        walk_stmt_savepoint(self, stmt);
+    }
+    /// Called for top level selects as well as for every nested select
+    /// (subqueries, compound selects, CTE bodies, ...).
+    fn visit_stmt_select(&mut self, stmt: &mut ast::StmtSelect) {
+        walk_stmt_select(self, stmt);
+    }
+    fn visit_stmt_update(&mut self, stmt: &mut ast::StmtUpdate) {
+        walk_stmt_update(self, stmt);
+    }
+
+    fn visit_alter_table_action(&mut self, action: &mut ast::AlterTableAction) {
+        walk_alter_table_action(self, action);
+    }
+    fn visit_create_index(&mut self, index: &mut ast::CreateIndex) {
+        walk_create_index(self, index);
+    }
+    fn visit_create_table(&mut self, table: &mut ast::CreateTable) {
+        walk_create_table(self, table);
+    }
+    fn visit_column_def(&mut self, column: &mut ast::ColumnDef) {
+        walk_column_def(self, column);
+    }
+    fn visit_column_constraint(&mut self, constraint: &mut ast::ColumnConstraint) {
+        walk_column_constraint(self, constraint);
+    }
+    fn visit_table_constraint(&mut self, constraint: &mut ast::TableConstraint) {
+        walk_table_constraint(self, constraint);
+    }
+    fn visit_foreign_key_clause(&mut self, clause: &mut ast::ForeignKeyClause) {
+        walk_foreign_key_clause(self, clause);
+    }
+    fn visit_type_name(&mut self, type_name: &mut ast::TypeName) {
+        walk_type_name(self, type_name);
+    }
+    fn visit_signed_number(&mut self, _number: &mut ast::SignedNumber) {}
+
+    fn visit_with_clause(&mut self, with: &mut ast::WithClause) {
+        walk_with_clause(self, with);
+    }
+    fn visit_common_table_expr(&mut self, cte: &mut ast::CommonTableExpr) {
+        walk_common_table_expr(self, cte);
+    }
+    fn visit_result_column(&mut self, column: &mut ast::ResultColumn) {
+        walk_result_column(self, column);
+    }
+    fn visit_from_clause(&mut self, from: &mut ast::FromClause) {
+        walk_from_clause(self, from);
+    }
+    fn visit_from_item(&mut self, item: &mut ast::FromItem) {
+        walk_from_item(self, item);
+    }
+    fn visit_join_clause(&mut self, join: &mut ast::JoinClause) {
+        walk_join_clause(self, join);
+    }
+    fn visit_join_constraint(&mut self, constraint: &mut ast::JoinConstraint) {
+        walk_join_constraint(self, constraint);
+    }
+    fn visit_table_name(&mut self, table: &mut ast::TableName) {
+        walk_table_name(self, table);
+    }
+    fn visit_quantified_table_name(&mut self, table: &mut ast::QuantifiedTableName) {
+        walk_quantified_table_name(self, table);
+    }
+    fn visit_compound_select(&mut self, compound: &mut ast::CompoundSelect) {
+        walk_compound_select(self, compound);
+    }
+    fn visit_ordering_term(&mut self, term: &mut ast::OrderingTerm) {
+        walk_ordering_term(self, term);
+    }
+    fn visit_named_window(&mut self, window: &mut ast::NamedWindow) {
+        walk_named_window(self, window);
+    }
+    fn visit_window_spec(&mut self, spec: &mut ast::WindowSpec) {
+        walk_window_spec(self, spec);
+    }
+    fn visit_window_frame(&mut self, frame: &mut ast::WindowFrame) {
+        walk_window_frame(self, frame);
+    }
+    fn visit_frame_bound(&mut self, bound: &mut ast::FrameBound) {
+        walk_frame_bound(self, bound);
+    }
+    fn visit_limit_clause(&mut self, limit: &mut ast::LimitClause) {
+        walk_limit_clause(self, limit);
+    }
+
+    fn visit_insert_action(&mut self, action: &mut ast::InsertAction) {
+        walk_insert_action(self, action);
+    }
+    fn visit_upsert_clause(&mut self, upsert: &mut ast::UpsertClause) {
+        walk_upsert_clause(self, upsert);
+    }
+    fn visit_assignment(&mut self, assignment: &mut ast::Assignment) {
+        walk_assignment(self, assignment);
+    }
+    fn visit_returning_clause(&mut self, returning: &mut ast::ReturningClause) {
+        walk_returning_clause(self, returning);
+    }
+    fn visit_indexed_column(&mut self, column: &mut ast::IndexedColumn) {
+        walk_indexed_column(self, column);
+    }
+
+    fn visit_expr(&mut self, expr: &mut ast::Expr) {
+        walk_expr(self, expr);
+    }
+    fn visit_case_branch(&mut self, branch: &mut ast::CaseBranch) {
+        walk_case_branch(self, branch);
+    }
+    fn visit_over_clause(&mut self, over: &mut ast::OverClause) {
+        walk_over_clause(self, over);
+    }
+    fn visit_parameter(&mut self, _parameter: &mut ast::ExprParameter) {}
+    fn visit_expr_invalid(&mut self, _expr: &mut ast::ExprInvalid) {}
+    fn visit_literal_value(&mut self, _literal: &mut ast::LiteralValue) {}
+    fn visit_identifier(&mut self, _identifier: &mut ast::Identifier) {}
+}
+
+pub fn walk_stmt<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Alter(stmt) => visitor.visit_stmt_alter(stmt),
+        Stmt::Begin(stmt) => visitor.visit_stmt_begin(stmt),
+        Stmt::Commit(stmt) => visitor.visit_stmt_commit(stmt),
+        Stmt::Create(stmt) => visitor.visit_stmt_create(stmt),
+        Stmt::Delete(stmt) => visitor.visit_stmt_delete(stmt),
+        Stmt::Invalid(stmt) => visitor.visit_stmt_invalid(stmt),
+        Stmt::Savepoint(stmt) => visitor.visit_stmt_savepoint(stmt),
+        Stmt::Reindex(stmt) => visitor.visit_stmt_reindex(stmt),
+        Stmt::Release(stmt) => visitor.visit_stmt_release(stmt),
+        Stmt::Rollback(stmt) => visitor.visit_stmt_rollback(stmt),
+        Stmt::Drop(stmt) => visitor.visit_stmt_drop(stmt),
+        Stmt::Insert(stmt) => visitor.visit_stmt_insert(stmt),
+        Stmt::Select(stmt) => visitor.visit_stmt_select(stmt),
+        Stmt::Update(stmt) => visitor.visit_stmt_update(stmt),
+    }
+}
+
+pub fn walk_stmt_alter<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtAlter) {
+    visitor.visit_identifier(&mut stmt.id);
+    visitor.visit_alter_table_action(&mut stmt.action);
+}
+
+pub fn walk_stmt_create<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtCreate) {
+    visitor.visit_identifier(&mut stmt.id);
+    match &mut stmt.kind {
+        ast::CreateKind::Table(table) => visitor.visit_create_table(table),
+        ast::CreateKind::Index(index) => visitor.visit_create_index(index),
+    }
+}
+
+pub fn walk_stmt_delete<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtDelete) {
+    if let Some(with) = &mut stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_quantified_table_name(&mut stmt.table);
+    if let Some(expr) = &mut stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    if let Some(returning) = &mut stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_stmt_drop<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtDrop) {
+    visitor.visit_identifier(&mut stmt.id);
+}
+
+pub fn walk_stmt_insert<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtInsert) {
+    if let Some(with) = &mut stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_table_name(&mut stmt.table);
+    for column in &mut stmt.columns {
+        visitor.visit_identifier(column);
+    }
+    visitor.visit_insert_action(&mut stmt.action);
+    if let Some(returning) = &mut stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_stmt_reindex<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtReindex) {
+    if let Some(id) = &mut stmt.id {
+        visitor.visit_identifier(id);
+    }
+}
+
+pub fn walk_stmt_release<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtRelease) {
+    visitor.visit_identifier(&mut stmt.id);
+}
+
+pub fn walk_stmt_rollback<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtRollback) {
+    if let Some(id) = &mut stmt.id {
+        visitor.visit_identifier(id);
+    }
+}
+
+pub fn walk_stmt_savepoint<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtSavepoint) {
+    visitor.visit_identifier(&mut stmt.id);
+}
+
+pub fn walk_stmt_select<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtSelect) {
+    if let Some(with) = &mut stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    for column in &mut stmt.columns {
+        visitor.visit_result_column(column);
+    }
+    if let Some(from) = &mut stmt.from {
+        visitor.visit_from_clause(from);
+    }
+    if let Some(expr) = &mut stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    for expr in &mut stmt.group_by {
+        visitor.visit_expr(expr);
+    }
+    if let Some(expr) = &mut stmt.having {
+        visitor.visit_expr(expr);
+    }
+    for window in &mut stmt.window {
+        visitor.visit_named_window(window);
+    }
+    if let Some(compound) = &mut stmt.compound {
+        visitor.visit_compound_select(compound);
+    }
+    for term in &mut stmt.order_by {
+        visitor.visit_ordering_term(term);
+    }
+    if let Some(limit) = &mut stmt.limit {
+        visitor.visit_limit_clause(limit);
+    }
+}
+
+pub fn walk_stmt_update<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut ast::StmtUpdate) {
+    if let Some(with) = &mut stmt.with {
+        visitor.visit_with_clause(with);
+    }
+    visitor.visit_quantified_table_name(&mut stmt.table);
+    for assignment in &mut stmt.assignments {
+        visitor.visit_assignment(assignment);
+    }
+    if let Some(from) = &mut stmt.from {
+        visitor.visit_from_clause(from);
+    }
+    if let Some(expr) = &mut stmt.where_clause {
+        visitor.visit_expr(expr);
+    }
+    if let Some(returning) = &mut stmt.returning {
+        visitor.visit_returning_clause(returning);
+    }
+}
+
+pub fn walk_alter_table_action<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    action: &mut ast::AlterTableAction,
+) {
+    match &mut action.kind {
+        ast::AlterTableActionKind::Rename(rename) => match &mut rename.kind {
+            ast::AlterTableRenameKind::Table(id) => visitor.visit_identifier(id),
+            ast::AlterTableRenameKind::Column(from, to) => {
+                visitor.visit_identifier(from);
+                visitor.visit_identifier(to);
+            }
+        },
+        ast::AlterTableActionKind::Add(add) => visitor.visit_column_def(&mut add.column),
+        ast::AlterTableActionKind::Drop(drop) => visitor.visit_identifier(&mut drop.id),
+    }
+}
+
+pub fn walk_create_index<V: VisitorMut + ?Sized>(visitor: &mut V, index: &mut ast::CreateIndex) {
+    visitor.visit_identifier(&mut index.table);
+    for column in &mut index.columns {
+        visitor.visit_indexed_column(column);
+    }
+    if let Some(expr) = &mut index.where_clause {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_create_table<V: VisitorMut + ?Sized>(visitor: &mut V, table: &mut ast::CreateTable) {
+    match &mut table.source {
+        ast::CreateTableSource::Columns(columns) => {
+            for column in &mut columns.columns {
+                visitor.visit_column_def(column);
+            }
+            for constraint in &mut columns.constraints {
+                visitor.visit_table_constraint(constraint);
+            }
+        }
+        ast::CreateTableSource::Select(select) => visitor.visit_stmt_select(select),
+    }
+}
+
+pub fn walk_column_def<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut ast::ColumnDef) {
+    visitor.visit_identifier(&mut column.id);
+    visitor.visit_type_name(&mut column.type_name);
+    for constraint in &mut column.constraint_list.constraints {
+        visitor.visit_column_constraint(constraint);
+    }
+}
+
+pub fn walk_column_constraint<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut ast::ColumnConstraint,
+) {
+    if let Some(name) = &mut constraint.name {
+        visitor.visit_identifier(name);
+    }
+    match &mut constraint.kind {
+        ast::ColumnConstraintKind::PrimaryKey(_)
+        | ast::ColumnConstraintKind::NotNull(_)
+        | ast::ColumnConstraintKind::Unique(_) => {}
+        ast::ColumnConstraintKind::Check(expr) => visitor.visit_expr(expr),
+        ast::ColumnConstraintKind::Default(default) => match default {
+            ast::ColumnConstraintDefault::ParenExpr(expr) => visitor.visit_expr(expr),
+            ast::ColumnConstraintDefault::LiteralValue(literal) => {
+                visitor.visit_literal_value(literal)
+            }
+            ast::ColumnConstraintDefault::SignedNumber(number) => {
+                visitor.visit_signed_number(number)
+            }
+        },
+        ast::ColumnConstraintKind::Collate(collation) => visitor.visit_identifier(collation),
+        ast::ColumnConstraintKind::Foreign(clause) => visitor.visit_foreign_key_clause(clause),
+    }
+}
+
+pub fn walk_table_constraint<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut ast::TableConstraint,
+) {
+    if let Some(name) = &mut constraint.name {
+        visitor.visit_identifier(name);
+    }
+    match &mut constraint.kind {
+        ast::TableConstraintKind::PrimaryKey(key) | ast::TableConstraintKind::Unique(key) => {
+            for column in &mut key.columns {
+                visitor.visit_indexed_column(column);
+            }
+        }
+        ast::TableConstraintKind::Check(expr) => visitor.visit_expr(expr),
+        ast::TableConstraintKind::ForeignKey(foreign_key) => {
+            for column in &mut foreign_key.columns {
+                visitor.visit_identifier(column);
+            }
+            visitor.visit_foreign_key_clause(&mut foreign_key.clause);
+        }
+    }
+}
+
+pub fn walk_foreign_key_clause<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    clause: &mut ast::ForeignKeyClause,
+) {
+    visitor.visit_identifier(&mut clause.id);
+    for column in &mut clause.column_names {
+        visitor.visit_identifier(column);
+    }
+}
+
+pub fn walk_type_name<V: VisitorMut + ?Sized>(visitor: &mut V, type_name: &mut ast::TypeName) {
+    if let Some(field) = &mut type_name.number_field {
+        visitor.visit_signed_number(&mut field.first);
+        if let Some(second) = &mut field.second {
+            visitor.visit_signed_number(second);
+        }
+    }
+}
+
+pub fn walk_with_clause<V: VisitorMut + ?Sized>(visitor: &mut V, with: &mut ast::WithClause) {
+    for cte in &mut with.ctes {
+        visitor.visit_common_table_expr(cte);
+    }
+}
+
+pub fn walk_common_table_expr<V: VisitorMut + ?Sized>(visitor: &mut V, cte: &mut ast::CommonTableExpr) {
+    visitor.visit_identifier(&mut cte.id);
+    for column in &mut cte.columns {
+        visitor.visit_identifier(column);
+    }
+    visitor.visit_stmt_select(&mut cte.select);
+}
+
+pub fn walk_result_column<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut ast::ResultColumn) {
+    match &mut column.kind {
+        ast::ResultColumnKind::All => {}
+        ast::ResultColumnKind::TableAll(table) => visitor.visit_identifier(table),
+        ast::ResultColumnKind::Expr(expr, alias) => {
+            visitor.visit_expr(expr);
+            if let Some(alias) = alias {
+                visitor.visit_identifier(alias);
+            }
+        }
+    }
+}
+
+pub fn walk_from_clause<V: VisitorMut + ?Sized>(visitor: &mut V, from: &mut ast::FromClause) {
+    visitor.visit_from_item(&mut from.item);
+}
+
+pub fn walk_from_item<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut ast::FromItem) {
+    match &mut item.kind {
+        ast::FromItemKind::Table(table) => visitor.visit_quantified_table_name(table),
+        ast::FromItemKind::Subquery(subquery) => {
+            visitor.visit_stmt_select(&mut subquery.select);
+            if let Some(alias) = &mut subquery.alias {
+                visitor.visit_identifier(alias);
+            }
+        }
+        ast::FromItemKind::Join(join) => visitor.visit_join_clause(join),
+    }
+}
+
+pub fn walk_join_clause<V: VisitorMut + ?Sized>(visitor: &mut V, join: &mut ast::JoinClause) {
+    visitor.visit_from_item(&mut join.left);
+    visitor.visit_from_item(&mut join.right);
+    if let Some(constraint) = &mut join.constraint {
+        visitor.visit_join_constraint(constraint);
+    }
+}
+
+pub fn walk_join_constraint<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut ast::JoinConstraint,
+) {
+    match constraint {
+        ast::JoinConstraint::On(expr) => visitor.visit_expr(expr),
+        ast::JoinConstraint::Using(columns) => {
+            for column in columns {
+                visitor.visit_identifier(column);
+            }
+        }
+    }
+}
+
+pub fn walk_table_name<V: VisitorMut + ?Sized>(visitor: &mut V, table: &mut ast::TableName) {
+    visitor.visit_identifier(&mut table.id);
+    if let Some(alias) = &mut table.alias {
+        visitor.visit_identifier(alias);
+    }
+}
+
+pub fn walk_quantified_table_name<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    table: &mut ast::QuantifiedTableName,
+) {
+    visitor.visit_identifier(&mut table.id);
+    if let Some(alias) = &mut table.alias {
+        visitor.visit_identifier(alias);
+    }
+    if let Some(ast::IndexedHint::IndexedBy(index)) = &mut table.indexed {
+        visitor.visit_identifier(index);
+    }
+}
+
+pub fn walk_compound_select<V: VisitorMut + ?Sized>(visitor: &mut V, compound: &mut ast::CompoundSelect) {
+    visitor.visit_stmt_select(&mut compound.select);
+}
+
+pub fn walk_ordering_term<V: VisitorMut + ?Sized>(visitor: &mut V, term: &mut ast::OrderingTerm) {
+    visitor.visit_expr(&mut term.expr);
+    if let Some(collation) = &mut term.collation {
+        visitor.visit_identifier(collation);
+    }
+}
+
+pub fn walk_named_window<V: VisitorMut + ?Sized>(visitor: &mut V, window: &mut ast::NamedWindow) {
+    visitor.visit_identifier(&mut window.id);
+    visitor.visit_window_spec(&mut window.spec);
+}
+
+pub fn walk_window_spec<V: VisitorMut + ?Sized>(visitor: &mut V, spec: &mut ast::WindowSpec) {
+    if let Some(base) = &mut spec.base {
+        visitor.visit_identifier(base);
+    }
+    for expr in &mut spec.partition_by {
+        visitor.visit_expr(expr);
+    }
+    for term in &mut spec.order_by {
+        visitor.visit_ordering_term(term);
+    }
+    if let Some(frame) = &mut spec.frame {
+        visitor.visit_window_frame(frame);
+    }
+}
+
+pub fn walk_window_frame<V: VisitorMut + ?Sized>(visitor: &mut V, frame: &mut ast::WindowFrame) {
+    visitor.visit_frame_bound(&mut frame.start);
+    if let Some(end) = &mut frame.end {
+        visitor.visit_frame_bound(end);
+    }
+}
+
+pub fn walk_frame_bound<V: VisitorMut + ?Sized>(visitor: &mut V, bound: &mut ast::FrameBound) {
+    match bound {
+        ast::FrameBound::Preceding(expr) | ast::FrameBound::Following(expr) => {
+            visitor.visit_expr(expr)
+        }
+        ast::FrameBound::UnboundedPreceding
+        | ast::FrameBound::CurrentRow
+        | ast::FrameBound::UnboundedFollowing => {}
+    }
+}
+
+pub fn walk_limit_clause<V: VisitorMut + ?Sized>(visitor: &mut V, limit: &mut ast::LimitClause) {
+    visitor.visit_expr(&mut limit.limit);
+    if let Some(offset) = &mut limit.offset {
+        visitor.visit_expr(offset);
+    }
+}
+
+pub fn walk_insert_action<V: VisitorMut + ?Sized>(visitor: &mut V, action: &mut ast::InsertAction) {
+    match &mut action.kind {
+        ast::InsertActionKind::Values(values) => {
+            for row in &mut values.rows {
+                for expr in row {
+                    visitor.visit_expr(expr);
+                }
+            }
+            if let Some(upsert) = &mut values.upsert {
+                visitor.visit_upsert_clause(upsert);
+            }
+        }
+        ast::InsertActionKind::Select(select) => {
+            visitor.visit_stmt_select(&mut select.select);
+            if let Some(upsert) = &mut select.upsert {
+                visitor.visit_upsert_clause(upsert);
+            }
+        }
+        ast::InsertActionKind::DefaultValues => {}
+    }
+}
+
+pub fn walk_upsert_clause<V: VisitorMut + ?Sized>(visitor: &mut V, upsert: &mut ast::UpsertClause) {
+    if let Some(target) = &mut upsert.target {
+        for column in &mut target.columns {
+            visitor.visit_indexed_column(column);
+        }
+        if let Some(expr) = &mut target.where_clause {
+            visitor.visit_expr(expr);
+        }
+    }
+    if let ast::UpsertAction::Update(update) = &mut upsert.action {
+        for assignment in &mut update.assignments {
+            visitor.visit_assignment(assignment);
+        }
+        if let Some(expr) = &mut update.where_clause {
+            visitor.visit_expr(expr);
+        }
+    }
+}
+
+pub fn walk_assignment<V: VisitorMut + ?Sized>(visitor: &mut V, assignment: &mut ast::Assignment) {
+    match &mut assignment.target {
+        ast::AssignmentTarget::Column(column) => visitor.visit_identifier(column),
+        ast::AssignmentTarget::ColumnList(columns) => {
+            for column in columns {
+                visitor.visit_identifier(column);
+            }
+        }
+    }
+    visitor.visit_expr(&mut assignment.expr);
+}
+
+pub fn walk_returning_clause<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    returning: &mut ast::ReturningClause,
+) {
+    for column in &mut returning.columns {
+        visitor.visit_result_column(column);
+    }
+}
+
+pub fn walk_indexed_column<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut ast::IndexedColumn) {
+    visitor.visit_expr(&mut column.expr);
+    if let Some(collation) = &mut column.collation {
+        visitor.visit_identifier(collation);
+    }
+}
+
+pub fn walk_expr<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut ast::Expr) {
+    match expr {
+        ast::Expr::BinOp(ast::ExprBinaryOp { left, right, .. })
+        | ast::Expr::Compare(ast::ExprCompare { left, right, .. })
+        | ast::Expr::BoolOp(ast::ExprBoolOp { left, right, .. })
+        | ast::Expr::Is(ast::ExprIs { left, right, .. }) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        ast::Expr::UnaryOp(unary) => visitor.visit_expr(&mut unary.operand),
+        ast::Expr::LiteralValue(literal) => visitor.visit_literal_value(literal),
+        ast::Expr::Column(column) => {
+            if let Some(table) = &mut column.table {
+                visitor.visit_identifier(table);
+            }
+            visitor.visit_identifier(&mut column.column);
+        }
+        ast::Expr::List(list) => {
+            for expr in &mut list.exprs {
+                visitor.visit_expr(expr);
+            }
+        }
+        ast::Expr::Subquery(subquery) => visitor.visit_stmt_select(&mut subquery.select),
+        ast::Expr::Collate(collate) => {
+            visitor.visit_expr(&mut collate.expr);
+            visitor.visit_identifier(&mut collate.collation);
+        }
+        ast::Expr::Like(like) => {
+            visitor.visit_expr(&mut like.expr);
+            visitor.visit_expr(&mut like.pattern);
+        }
+        ast::Expr::IsNull(is_null) => visitor.visit_expr(&mut is_null.expr),
+        ast::Expr::Between(between) => {
+            visitor.visit_expr(&mut between.expr);
+            visitor.visit_expr(&mut between.low);
+            visitor.visit_expr(&mut between.high);
+        }
+        ast::Expr::In(in_expr) => {
+            visitor.visit_expr(&mut in_expr.expr);
+            match &mut in_expr.kind {
+                ast::ExprInKind::List(exprs) => {
+                    for expr in exprs {
+                        visitor.visit_expr(expr);
+                    }
+                }
+                ast::ExprInKind::Select(select) => visitor.visit_stmt_select(select),
+                ast::ExprInKind::Table(table) => visitor.visit_identifier(table),
+            }
+        }
+        ast::Expr::Exists(exists) => visitor.visit_stmt_select(&mut exists.select),
+        ast::Expr::Function(function) => {
+            visitor.visit_identifier(&mut function.name);
+            if let ast::FunctionArgs::List(args) = &mut function.args {
+                for arg in args {
+                    visitor.visit_expr(arg);
+                }
+            }
+            if let Some(filter) = &mut function.filter {
+                visitor.visit_expr(filter);
+            }
+            if let Some(over) = &mut function.over {
+                visitor.visit_over_clause(over);
+            }
+        }
+        ast::Expr::Case(case) => {
+            if let Some(operand) = &mut case.operand {
+                visitor.visit_expr(operand);
+            }
+            for branch in &mut case.branches {
+                visitor.visit_case_branch(branch);
+            }
+            if let Some(else_expr) = &mut case.else_expr {
+                visitor.visit_expr(else_expr);
+            }
+        }
+        ast::Expr::Cast(cast) => {
+            visitor.visit_expr(&mut cast.expr);
+            visitor.visit_type_name(&mut cast.type_name);
+        }
+        ast::Expr::Parameter(parameter) => visitor.visit_parameter(parameter),
+        ast::Expr::Invalid(expr) => visitor.visit_expr_invalid(expr),
+    }
+}
+
+pub fn walk_case_branch<V: VisitorMut + ?Sized>(visitor: &mut V, branch: &mut ast::CaseBranch) {
+    visitor.visit_expr(&mut branch.condition);
+    visitor.visit_expr(&mut branch.result);
+}
+
+pub fn walk_over_clause<V: VisitorMut + ?Sized>(visitor: &mut V, over: &mut ast::OverClause) {
+    match over {
+        ast::OverClause::Window(spec) => visitor.visit_window_spec(spec),
+        ast::OverClause::Named(name) => visitor.visit_identifier(name),
+    }
+}
@@ -0,0 +1,1158 @@
+use ast::name::Name;
+use source_index::span::Span;
+
+use crate::bound::*;
+use crate::catalog::{Catalog, ColumnId};
+use crate::errors::{BindError, BindErrorKind};
+
+pub(crate) struct Binder<'a> {
+    catalog: &'a dyn Catalog,
+
+    pub(crate) relations: Vec<Relation>,
+
+    pub(crate) errors: Vec<BindError>,
+
+    // FROM clauses being bound, innermost last
+    scopes: Vec<Scope>,
+
+    // common table expressions in scope, innermost last
+    ctes: Vec<CteEntry>,
+
+    next_cte: u32,
+
+    // WINDOW clauses of the selects being bound, innermost last
+    windows: Vec<Vec<ast::NamedWindow>>,
+}
+
+#[derive(Default)]
+struct Scope {
+    entries: Vec<ScopeEntry>,
+    // right hand columns of USING and NATURAL joins, which only qualified
+    // references can read
+    hidden: Vec<(RelationId, ColumnId)>,
+}
+
+struct ScopeEntry {
+    relation: RelationId,
+    // span of the alias or table name, to point at duplicates
+    name_span: Span,
+}
+
+struct CteEntry {
+    id: CteId,
+    name: Name,
+    columns: Vec<Name>,
+}
+
+fn same_name(a: &Name, b: &Name) -> bool {
+    a.as_str().eq_ignore_ascii_case(b.as_str())
+}
+
+impl<'a> Binder<'a> {
+    pub(crate) fn new(catalog: &'a dyn Catalog) -> Self {
+        Self {
+            catalog,
+            relations: Vec::new(),
+            errors: Vec::new(),
+            scopes: Vec::new(),
+            ctes: Vec::new(),
+            next_cte: 0,
+            windows: Vec::new(),
+        }
+    }
+
+    pub(crate) fn bind_stmt(&mut self, stmt: &ast::Stmt) -> BoundStmt {
+        match stmt {
+            ast::Stmt::Select(select) => BoundStmt::Select(Box::new(self.bind_select(select))),
+            ast::Stmt::Insert(insert) => BoundStmt::Insert(self.bind_insert(insert)),
+            ast::Stmt::Update(update) => BoundStmt::Update(self.bind_update(update)),
+            ast::Stmt::Delete(delete) => BoundStmt::Delete(self.bind_delete(delete)),
+            stmt => BoundStmt::Other(Box::new(stmt.clone())),
+        }
+    }
+
+    fn add_error(&mut self, kind: BindErrorKind, span: Span) {
+        self.errors.push(BindError::new(kind, span));
+    }
+
+    fn relation(&self, id: RelationId) -> &Relation {
+        &self.relations[id.0 as usize]
+    }
+
+    fn add_relation(&mut self, name: Name, kind: RelationKind, columns: Vec<RelationColumn>) -> RelationId {
+        let id = RelationId(self.relations.len() as u32);
+        self.relations.push(Relation {
+            id,
+            name,
+            kind,
+            columns,
+        });
+        id
+    }
+
+    // Add a relation to the innermost FROM clause, which may not already
+    // have one of the same name.
+    fn add_to_scope(&mut self, relation: RelationId, name_span: Span) {
+        let name = &self.relation(relation).name;
+        let scope = self.scopes.last().expect("a FROM clause is being bound");
+        let first = scope
+            .entries
+            .iter()
+            .find(|entry| name.is_valid() && same_name(&self.relation(entry.relation).name, name))
+            .map(|entry| entry.name_span);
+        if let Some(first) = first {
+            let name = name.clone();
+            self.add_error(BindErrorKind::DuplicateAlias { name, first }, name_span);
+        }
+        let scope = self.scopes.last_mut().expect("a FROM clause is being bound");
+        scope.entries.push(ScopeEntry { relation, name_span });
+    }
+
+    // Resolve a table name to a common table expression or a catalog table.
+    // Unknown tables are reported and get a relation without columns.
+    fn table_relation(&mut self, id: &ast::Identifier, alias: Option<&ast::Identifier>) -> RelationId {
+        let name = alias.unwrap_or(id).id.clone();
+        if !id.is_valid() {
+            return self.add_relation(name, RelationKind::Unknown, Vec::new());
+        }
+        if let Some(cte) = self.ctes.iter().rev().find(|cte| same_name(&cte.name, &id.id)) {
+            let columns = cte
+                .columns
+                .iter()
+                .map(|name| RelationColumn {
+                    name: name.clone(),
+                    data_type: None,
+                })
+                .collect();
+            let kind = RelationKind::Cte(cte.id);
+            return self.add_relation(name, kind, columns);
+        }
+        match self.catalog.table(id.id.as_str()) {
+            Some(table) => {
+                let columns = table
+                    .columns
+                    .iter()
+                    .map(|column| RelationColumn {
+                        name: column.name.clone(),
+                        data_type: Some(column.data_type.clone()),
+                    })
+                    .collect();
+                self.add_relation(name, RelationKind::Table(table.id), columns)
+            }
+            None => {
+                self.add_error(BindErrorKind::UnknownTable { name: id.id.clone() }, id.span);
+                self.add_relation(name, RelationKind::Unknown, Vec::new())
+            }
+        }
+    }
+
+    // The target of an INSERT, UPDATE or DELETE, which must be a catalog
+    // table, in a new scope of its own.
+    fn target_relation(&mut self, id: &ast::Identifier, alias: Option<&ast::Identifier>) -> RelationId {
+        let relation = self.table_relation(id, alias);
+        if let RelationKind::Cte(_) = self.relation(relation).kind {
+            self.add_error(BindErrorKind::UnknownTable { name: id.id.clone() }, id.span);
+            self.relations[relation.0 as usize].kind = RelationKind::Unknown;
+        }
+        self.scopes.push(Scope::default());
+        self.add_to_scope(relation, alias.unwrap_or(id).span);
+        relation
+    }
+
+    // Resolve the columns of an INSERT column list or UPDATE assignment.
+    fn target_columns(&mut self, relation: RelationId, ids: &[ast::Identifier]) -> Vec<ColumnId> {
+        let mut columns = Vec::new();
+        for id in ids {
+            if !id.is_valid() || self.relation(relation).kind == RelationKind::Unknown {
+                continue;
+            }
+            match self.column_of(relation, &id.id) {
+                Some(column) => columns.push(column),
+                None => {
+                    let table = Some(self.relation(relation).name.clone());
+                    self.add_error(BindErrorKind::UnknownColumn { name: id.id.clone(), table }, id.span);
+                }
+            }
+        }
+        columns
+    }
+
+    fn column_of(&self, relation: RelationId, name: &Name) -> Option<ColumnId> {
+        self.relation(relation)
+            .columns
+            .iter()
+            .position(|column| same_name(&column.name, name))
+            .map(|index| ColumnId(index as u32))
+    }
+
+    fn column_ref(&self, span: Span, relation: RelationId, column: ColumnId) -> BoundExpr {
+        let name = self.relation(relation).columns[column.0 as usize].name.clone();
+        BoundExpr::Column(ColumnRef {
+            span,
+            relation,
+            column,
+            name,
+        })
+    }
+
+    fn bind_with(&mut self, with: &ast::WithClause) -> Vec<BoundCte> {
+        let mut ctes = Vec::new();
+        for cte in &with.ctes {
+            let id = CteId(self.next_cte);
+            self.next_cte += 1;
+            let mut columns: Vec<Name> = cte.columns.iter().map(|column| column.id.clone()).collect();
+            if with.recursive {
+                // The select may read the table it defines, with the columns
+                // listed or named by its first select.
+                if columns.is_empty() {
+                    columns = cte.select.columns.iter().filter_map(result_column_name).collect();
+                }
+                self.ctes.push(CteEntry {
+                    id,
+                    name: cte.id.id.clone(),
+                    columns: columns.clone(),
+                });
+            }
+            let select = self.bind_select(&cte.select);
+            if cte.columns.is_empty() {
+                columns = self.relation(select.id).columns.iter().map(|column| column.name.clone()).collect();
+            }
+            if with.recursive {
+                self.ctes.pop();
+            }
+            self.ctes.push(CteEntry {
+                id,
+                name: cte.id.id.clone(),
+                columns: columns.clone(),
+            });
+            ctes.push(BoundCte {
+                span: cte.span,
+                id,
+                name: cte.id.id.clone(),
+                columns,
+                recursive: with.recursive,
+                materialized: cte.materialized.clone(),
+                select: Box::new(select),
+            });
+        }
+        ctes
+    }
+
+    pub(crate) fn bind_select(&mut self, select: &ast::StmtSelect) -> BoundSelect {
+        let cte_depth = self.ctes.len();
+        let ctes = match &select.with {
+            Some(with) => self.bind_with(with),
+            None => Vec::new(),
+        };
+        self.scopes.push(Scope::default());
+        self.windows.push(select.window.clone());
+
+        let from = select.from.as_ref().map(|from| self.bind_from(&from.item));
+        let columns = self.bind_result_columns(&select.columns);
+        let where_clause = select.where_clause.as_ref().map(|expr| self.bind_expr(expr));
+        let group_by = select.group_by.iter().map(|expr| self.bind_expr(expr)).collect();
+        let having = select.having.as_ref().map(|expr| self.bind_expr(expr));
+        let output = columns
+            .iter()
+            .map(|column| RelationColumn {
+                name: column.name.clone(),
+                data_type: None,
+            })
+            .collect();
+        let id = self.add_relation(Name::empty(), RelationKind::Select, output);
+
+        let mut order_by = Vec::new();
+        if select.compound.is_none() {
+            order_by = select
+                .order_by
+                .iter()
+                .map(|term| self.bind_ordering_term(term, Some(&columns)))
+                .collect();
+        }
+        self.windows.pop();
+        self.scopes.pop();
+
+        let compound = select.compound.as_ref().map(|compound| BoundCompound {
+            span: compound.span,
+            op: compound.op.clone(),
+            select: Box::new(self.bind_select(&compound.select)),
+        });
+        if compound.is_some() {
+            // The ORDER BY of a compound select sorts its result.
+            let mut scope = Scope::default();
+            scope.entries.push(ScopeEntry {
+                relation: id,
+                name_span: select.span,
+            });
+            self.scopes.push(scope);
+            order_by = select
+                .order_by
+                .iter()
+                .map(|term| self.bind_ordering_term(term, None))
+                .collect();
+            self.scopes.pop();
+        }
+        let limit = select.limit.as_ref().map(|limit| BoundLimit {
+            span: limit.span,
+            limit: self.bind_expr(&limit.limit),
+            offset: limit.offset.as_ref().map(|expr| self.bind_expr(expr)),
+        });
+        self.ctes.truncate(cte_depth);
+
+        BoundSelect {
+            span: select.span,
+            id,
+            ctes,
+            distinct: select.distinct,
+            columns,
+            from,
+            where_clause,
+            group_by,
+            having,
+            compound,
+            order_by,
+            limit,
+        }
+    }
+
+    fn bind_result_columns(&mut self, columns: &[ast::ResultColumn]) -> Vec<BoundResultColumn> {
+        let mut bound = Vec::new();
+        for column in columns {
+            match &column.kind {
+                ast::ResultColumnKind::All => {
+                    let scope = self.scopes.last().expect("a select is being bound");
+                    let mut refs = Vec::new();
+                    for entry in &scope.entries {
+                        let relation = self.relation(entry.relation);
+                        for index in 0..relation.columns.len() {
+                            let id = ColumnId(index as u32);
+                            if !scope.hidden.contains(&(entry.relation, id)) {
+                                refs.push((entry.relation, id));
+                            }
+                        }
+                    }
+                    for (relation, id) in refs {
+                        bound.push(self.expanded_column(column.span, relation, id));
+                    }
+                }
+                ast::ResultColumnKind::TableAll(table) => {
+                    let scope = self.scopes.last().expect("a select is being bound");
+                    let relation = scope
+                        .entries
+                        .iter()
+                        .map(|entry| entry.relation)
+                        .find(|relation| same_name(&self.relation(*relation).name, &table.id));
+                    let Some(relation) = relation else {
+                        self.add_error(BindErrorKind::UnknownTable { name: table.id.clone() }, table.span);
+                        continue;
+                    };
+                    for index in 0..self.relation(relation).columns.len() {
+                        bound.push(self.expanded_column(column.span, relation, ColumnId(index as u32)));
+                    }
+                }
+                ast::ResultColumnKind::Expr(expr, alias) => {
+                    let name = match alias {
+                        Some(alias) => alias.id.clone(),
+                        None => match expr {
+                            ast::Expr::Column(column) => column.column.id.clone(),
+                            expr => Name::new(expr.to_string()),
+                        },
+                    };
+                    bound.push(BoundResultColumn {
+                        span: column.span,
+                        name,
+                        expr: self.bind_expr(expr),
+                    });
+                }
+            }
+        }
+        bound
+    }
+
+    fn expanded_column(&self, span: Span, relation: RelationId, column: ColumnId) -> BoundResultColumn {
+        let expr = self.column_ref(span, relation, column);
+        let name = self.relation(relation).columns[column.0 as usize].name.clone();
+        BoundResultColumn { span, name, expr }
+    }
+
+    // Bind an ORDER BY term. With the result columns of a simple select, a
+    // bare name matching an alias or a column number is replaced by the
+    // expression of that result column.
+    fn bind_ordering_term(
+        &mut self,
+        term: &ast::OrderingTerm,
+        columns: Option<&[BoundResultColumn]>,
+    ) -> BoundOrderingTerm {
+        let output = match &term.expr {
+            ast::Expr::Column(ast::ExprColumn { table: None, column, .. }) => columns
+                .and_then(|columns| columns.iter().find(|result| same_name(&result.name, &column.id)))
+                .map(|result| result.expr.clone()),
+            ast::Expr::LiteralValue(ast::LiteralValue::IntLiteral(number)) => {
+                let index = usize::try_from(number.value - 1).ok();
+                match columns {
+                    Some(columns) => index.and_then(|index| columns.get(index)).map(|result| result.expr.clone()),
+                    None => {
+                        let relation = self.scopes.last().expect("a select is being bound").entries[0].relation;
+                        index
+                            .filter(|index| *index < self.relation(relation).columns.len())
+                            .map(|index| self.column_ref(number.span, relation, ColumnId(index as u32)))
+                    }
+                }
+            }
+            _ => None,
+        };
+        BoundOrderingTerm {
+            span: term.span,
+            expr: output.unwrap_or_else(|| self.bind_expr(&term.expr)),
+            collation: term.collation.as_ref().map(|collation| collation.id.clone()),
+            order: term.order.clone(),
+            nulls: term.nulls.clone(),
+        }
+    }
+
+    fn bind_from(&mut self, item: &ast::FromItem) -> BoundFrom {
+        match &item.kind {
+            ast::FromItemKind::Table(table) => {
+                let relation = self.table_relation(&table.id, table.alias.as_ref());
+                self.add_to_scope(relation, table.alias.as_ref().unwrap_or(&table.id).span);
+                if let RelationKind::Cte(cte) = self.relation(relation).kind {
+                    return BoundFrom::Cte(BoundCteScan {
+                        span: item.span,
+                        relation,
+                        cte,
+                    });
+                }
+                BoundFrom::Table(BoundTable {
+                    span: item.span,
+                    relation,
+                    indexed: table.indexed.clone(),
+                })
+            }
+            ast::FromItemKind::Subquery(subquery) => {
+                // A derived table cannot read the tables before it in the
+                // same FROM clause.
+                let scope = self.scopes.pop().expect("a FROM clause is being bound");
+                let select = self.bind_select(&subquery.select);
+                self.scopes.push(scope);
+                if let Some(alias) = &subquery.alias {
+                    self.relations[select.id.0 as usize].name = alias.id.clone();
+                }
+                let name_span = subquery.alias.as_ref().map_or(subquery.span, |alias| alias.span);
+                self.add_to_scope(select.id, name_span);
+                BoundFrom::Subquery(BoundSubquery {
+                    span: item.span,
+                    select: Box::new(select),
+                })
+            }
+            ast::FromItemKind::Join(join) => {
+                let start = self.scopes.last().expect("a FROM clause is being bound").entries.len();
+                let left = self.bind_from(&join.left);
+                let middle = self.scopes.last().expect("a FROM clause is being bound").entries.len();
+                let right = self.bind_from(&join.right);
+                let condition = match &join.constraint {
+                    Some(ast::JoinConstraint::On(expr)) => Some(self.bind_expr(expr)),
+                    Some(ast::JoinConstraint::Using(columns)) => {
+                        let names: Vec<(Name, Span)> =
+                            columns.iter().map(|column| (column.id.clone(), column.span)).collect();
+                        self.join_equalities(start, middle, &names, true)
+                    }
+                    None if join.op.natural => {
+                        let names = self.natural_columns(start, middle);
+                        self.join_equalities(start, middle, &names, false)
+                    }
+                    None => None,
+                };
+                BoundFrom::Join(BoundJoin {
+                    span: item.span,
+                    left: Box::new(left),
+                    kind: join.op.kind.clone(),
+                    right: Box::new(right),
+                    condition,
+                })
+            }
+        }
+    }
+
+    // Names of the visible columns on both sides of a NATURAL join, where
+    // the left side is the scope entries from `start` to `middle`.
+    fn natural_columns(&self, start: usize, middle: usize) -> Vec<(Name, Span)> {
+        let scope = self.scopes.last().expect("a FROM clause is being bound");
+        let mut names = Vec::new();
+        for entry in &scope.entries[middle..] {
+            for column in &self.relation(entry.relation).columns {
+                let on_left = !self.visible_columns(&scope.entries[start..middle], &column.name).is_empty();
+                if on_left && !names.iter().any(|(name, _)| same_name(name, &column.name)) {
+                    names.push((column.name.clone(), entry.name_span));
+                }
+            }
+        }
+        names
+    }
+
+    // Equate the columns called `names` of the two sides of a join and hide
+    // the ones on the right from unqualified references.
+    fn join_equalities(
+        &mut self,
+        start: usize,
+        middle: usize,
+        names: &[(Name, Span)],
+        report: bool,
+    ) -> Option<BoundExpr> {
+        let mut condition: Option<BoundExpr> = None;
+        for (name, span) in names {
+            let scope = self.scopes.last().expect("a FROM clause is being bound");
+            let left = self.visible_columns(&scope.entries[start..middle], name);
+            let right = self.visible_columns(&scope.entries[middle..], name);
+            let (Some(left), Some(right)) = (left.first(), right.first()) else {
+                if report {
+                    self.add_error(BindErrorKind::UnknownColumn { name: name.clone(), table: None }, *span);
+                }
+                continue;
+            };
+            let (left, right) = (*left, *right);
+            self.scopes.last_mut().expect("a FROM clause is being bound").hidden.push(right);
+            let equality = BoundExpr::Compare(BoundCompare {
+                span: *span,
+                left: Box::new(self.column_ref(*span, left.0, left.1)),
+                op: ast::CmpOperator::Eq,
+                right: Box::new(self.column_ref(*span, right.0, right.1)),
+            });
+            condition = Some(match condition {
+                Some(condition) => BoundExpr::BoolOp(BoundBoolOp {
+                    span: *span,
+                    left: Box::new(condition),
+                    op: ast::BooleanOperator::And,
+                    right: Box::new(equality),
+                }),
+                None => equality,
+            });
+        }
+        condition
+    }
+
+    // The columns called `name` of `entries` of the innermost scope that an
+    // unqualified reference can read.
+    fn visible_columns(&self, entries: &[ScopeEntry], name: &Name) -> Vec<(RelationId, ColumnId)> {
+        let scope = self.scopes.last().expect("a FROM clause is being bound");
+        self.matching_columns(scope, entries, name)
+    }
+
+    fn matching_columns(&self, scope: &Scope, entries: &[ScopeEntry], name: &Name) -> Vec<(RelationId, ColumnId)> {
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let column = self.column_of(entry.relation, name)?;
+                let id = (entry.relation, column);
+                (!scope.hidden.contains(&id)).then_some(id)
+            })
+            .collect()
+    }
+
+    fn bind_column(&mut self, column: &ast::ExprColumn) -> BoundExpr {
+        let invalid = BoundExpr::Invalid(ast::ExprInvalid { span: column.span });
+        if !column.column.is_valid() {
+            return invalid;
+        }
+        let name = &column.column.id;
+        if let Some(table) = &column.table {
+            let relation = self.scopes.iter().rev().find_map(|scope| {
+                scope
+                    .entries
+                    .iter()
+                    .map(|entry| entry.relation)
+                    .find(|relation| same_name(&self.relation(*relation).name, &table.id))
+            });
+            let Some(relation) = relation else {
+                self.add_error(BindErrorKind::UnknownTable { name: table.id.clone() }, table.span);
+                return invalid;
+            };
+            if self.relation(relation).kind == RelationKind::Unknown {
+                return invalid;
+            }
+            return match self.column_of(relation, name) {
+                Some(id) => self.column_ref(column.span, relation, id),
+                None => {
+                    let kind = BindErrorKind::UnknownColumn {
+                        name: name.clone(),
+                        table: Some(table.id.clone()),
+                    };
+                    self.add_error(kind, column.column.span);
+                    invalid
+                }
+            };
+        }
+
+        for scope in self.scopes.iter().rev() {
+            let matches = self.matching_columns(scope, &scope.entries, name);
+            if let [(relation, id), rest @ ..] = matches.as_slice() {
+                if !rest.is_empty() {
+                    let tables = matches
+                        .iter()
+                        .map(|(relation, _)| self.relation(*relation).name.clone())
+                        .collect();
+                    let kind = BindErrorKind::AmbiguousColumn { name: name.clone(), tables };
+                    self.add_error(kind, column.span);
+                }
+                return self.column_ref(column.span, *relation, *id);
+            }
+            // The column may belong to a table missing from the catalog.
+            let unknown = scope
+                .entries
+                .iter()
+                .any(|entry| self.relation(entry.relation).kind == RelationKind::Unknown);
+            if unknown {
+                return invalid;
+            }
+        }
+        self.add_error(BindErrorKind::UnknownColumn { name: name.clone(), table: None }, column.span);
+        invalid
+    }
+
+    fn bind_box(&mut self, expr: &ast::Expr) -> Box<BoundExpr> {
+        Box::new(self.bind_expr(expr))
+    }
+
+    pub(crate) fn bind_expr(&mut self, expr: &ast::Expr) -> BoundExpr {
+        match expr {
+            ast::Expr::LiteralValue(literal) => BoundExpr::Literal(literal.clone()),
+            ast::Expr::Column(column) => self.bind_column(column),
+            ast::Expr::BinOp(expr) => BoundExpr::BinaryOp(BoundBinaryOp {
+                span: expr.span,
+                left: self.bind_box(&expr.left),
+                op: expr.op.clone(),
+                right: self.bind_box(&expr.right),
+            }),
+            ast::Expr::UnaryOp(expr) => BoundExpr::UnaryOp(BoundUnaryOp {
+                span: expr.span,
+                op: expr.op.clone(),
+                operand: self.bind_box(&expr.operand),
+            }),
+            ast::Expr::Compare(expr) => BoundExpr::Compare(BoundCompare {
+                span: expr.span,
+                left: self.bind_box(&expr.left),
+                op: expr.op.clone(),
+                right: self.bind_box(&expr.right),
+            }),
+            ast::Expr::BoolOp(expr) => BoundExpr::BoolOp(BoundBoolOp {
+                span: expr.span,
+                left: self.bind_box(&expr.left),
+                op: expr.op.clone(),
+                right: self.bind_box(&expr.right),
+            }),
+            ast::Expr::List(list) => match list.exprs.as_slice() {
+                [expr] => self.bind_expr(expr),
+                exprs => BoundExpr::List(BoundList {
+                    span: list.span,
+                    exprs: exprs.iter().map(|expr| self.bind_expr(expr)).collect(),
+                }),
+            },
+            ast::Expr::Subquery(subquery) => BoundExpr::Subquery(BoundSubquery {
+                span: subquery.span,
+                select: Box::new(self.bind_select(&subquery.select)),
+            }),
+            ast::Expr::Collate(expr) => BoundExpr::Collate(BoundCollate {
+                span: expr.span,
+                expr: self.bind_box(&expr.expr),
+                collation: expr.collation.id.clone(),
+            }),
+            ast::Expr::Like(expr) => BoundExpr::Like(BoundLike {
+                span: expr.span,
+                expr: self.bind_box(&expr.expr),
+                negated: expr.negated,
+                pattern: self.bind_box(&expr.pattern),
+            }),
+            ast::Expr::IsNull(expr) => BoundExpr::IsNull(BoundIsNull {
+                span: expr.span,
+                expr: self.bind_box(&expr.expr),
+                negated: expr.negated,
+            }),
+            ast::Expr::Is(expr) => BoundExpr::Is(BoundIs {
+                span: expr.span,
+                left: self.bind_box(&expr.left),
+                negated: expr.negated,
+                distinct_from: expr.distinct_from,
+                right: self.bind_box(&expr.right),
+            }),
+            ast::Expr::Between(expr) => BoundExpr::Between(BoundBetween {
+                span: expr.span,
+                expr: self.bind_box(&expr.expr),
+                negated: expr.negated,
+                low: self.bind_box(&expr.low),
+                high: self.bind_box(&expr.high),
+            }),
+            ast::Expr::In(expr) => {
+                let kind = match &expr.kind {
+                    ast::ExprInKind::List(exprs) => {
+                        BoundInKind::List(exprs.iter().map(|expr| self.bind_expr(expr)).collect())
+                    }
+                    ast::ExprInKind::Select(select) => BoundInKind::Select(Box::new(self.bind_select(select))),
+                    ast::ExprInKind::Table(table) => BoundInKind::Table(self.table_relation(table, None)),
+                };
+                BoundExpr::In(BoundIn {
+                    span: expr.span,
+                    expr: self.bind_box(&expr.expr),
+                    negated: expr.negated,
+                    kind,
+                })
+            }
+            ast::Expr::Exists(expr) => BoundExpr::Exists(BoundExists {
+                span: expr.span,
+                negated: expr.negated,
+                select: Box::new(self.bind_select(&expr.select)),
+            }),
+            ast::Expr::Function(function) => BoundExpr::Function(BoundFunction {
+                span: function.span,
+                name: function.name.id.clone(),
+                distinct: function.distinct,
+                args: match &function.args {
+                    ast::FunctionArgs::Star => None,
+                    ast::FunctionArgs::List(args) => Some(args.iter().map(|arg| self.bind_expr(arg)).collect()),
+                },
+                filter: function.filter.as_ref().map(|filter| self.bind_box(filter)),
+                over: function.over.as_ref().and_then(|over| self.bind_over(over)),
+            }),
+            ast::Expr::Case(case) => BoundExpr::Case(BoundCase {
+                span: case.span,
+                operand: case.operand.as_ref().map(|operand| self.bind_box(operand)),
+                branches: case
+                    .branches
+                    .iter()
+                    .map(|branch| BoundCaseBranch {
+                        span: branch.span,
+                        condition: self.bind_expr(&branch.condition),
+                        result: self.bind_expr(&branch.result),
+                    })
+                    .collect(),
+                else_expr: case.else_expr.as_ref().map(|expr| self.bind_box(expr)),
+            }),
+            ast::Expr::Cast(cast) => BoundExpr::Cast(BoundCast {
+                span: cast.span,
+                expr: self.bind_box(&cast.expr),
+                type_name: cast.type_name.clone(),
+            }),
+            ast::Expr::Parameter(parameter) => BoundExpr::Parameter(parameter.clone()),
+            ast::Expr::Invalid(invalid) => BoundExpr::Invalid(invalid.clone()),
+        }
+    }
+
+    fn bind_over(&mut self, over: &ast::OverClause) -> Option<BoundWindow> {
+        let spec = match over {
+            ast::OverClause::Window(spec) => (**spec).clone(),
+            ast::OverClause::Named(name) => self.named_window(name)?,
+        };
+        let spec = self.resolve_window_base(spec);
+        Some(BoundWindow {
+            span: spec.span,
+            partition_by: spec.partition_by.iter().map(|expr| self.bind_expr(expr)).collect(),
+            order_by: spec.order_by.iter().map(|term| self.bind_ordering_term(term, None)).collect(),
+            frame: spec.frame,
+        })
+    }
+
+    fn named_window(&mut self, name: &ast::Identifier) -> Option<ast::WindowSpec> {
+        let window = self
+            .windows
+            .last()
+            .and_then(|windows| windows.iter().find(|window| same_name(&window.id.id, &name.id)));
+        match window {
+            Some(window) => Some(window.spec.clone()),
+            None => {
+                self.add_error(BindErrorKind::UnknownWindow { name: name.id.clone() }, name.span);
+                None
+            }
+        }
+    }
+
+    // Fill in the partitioning, ordering and frame a window takes from its
+    // base window.
+    fn resolve_window_base(&mut self, mut spec: ast::WindowSpec) -> ast::WindowSpec {
+        // each step takes a window from the WINDOW clause, which stops cycles
+        let limit = self.windows.last().map_or(0, Vec::len);
+        for _ in 0..=limit {
+            let Some(base) = spec.base.take() else {
+                break;
+            };
+            let Some(base) = self.named_window(&base) else {
+                break;
+            };
+            let mut partition_by = base.partition_by;
+            partition_by.append(&mut spec.partition_by);
+            spec.partition_by = partition_by;
+            if spec.order_by.is_empty() {
+                spec.order_by = base.order_by;
+            }
+            if spec.frame.is_none() {
+                spec.frame = base.frame;
+            }
+            spec.base = base.base;
+        }
+        spec
+    }
+
+    fn bind_returning(&mut self, returning: &Option<ast::ReturningClause>) -> Vec<BoundResultColumn> {
+        match returning {
+            Some(returning) => self.bind_result_columns(&returning.columns),
+            None => Vec::new(),
+        }
+    }
+
+    fn bind_assignments(&mut self, relation: RelationId, assignments: &[ast::Assignment]) -> Vec<BoundAssignment> {
+        assignments
+            .iter()
+            .map(|assignment| {
+                let targets = match &assignment.target {
+                    ast::AssignmentTarget::Column(id) => std::slice::from_ref(id),
+                    ast::AssignmentTarget::ColumnList(ids) => ids.as_slice(),
+                };
+                BoundAssignment {
+                    span: assignment.span,
+                    columns: self.target_columns(relation, targets),
+                    expr: self.bind_expr(&assignment.expr),
+                }
+            })
+            .collect()
+    }
+
+    fn bind_insert(&mut self, insert: &ast::StmtInsert) -> BoundInsert {
+        let cte_depth = self.ctes.len();
+        let ctes = match &insert.with {
+            Some(with) => self.bind_with(with),
+            None => Vec::new(),
+        };
+        let table = self.table_relation(&insert.table.id, insert.table.alias.as_ref());
+        if let RelationKind::Cte(_) = self.relation(table).kind {
+            let name = insert.table.id.id.clone();
+            self.add_error(BindErrorKind::UnknownTable { name }, insert.table.id.span);
+            self.relations[table.0 as usize].kind = RelationKind::Unknown;
+        }
+        let columns: Vec<ColumnId> = if insert.columns.is_empty() {
+            (0..self.relation(table).columns.len())
+                .map(|index| ColumnId(index as u32))
+                .collect()
+        } else {
+            self.target_columns(table, &insert.columns)
+        };
+        let value_count = if !insert.columns.is_empty() {
+            Some(insert.columns.len())
+        } else if self.relation(table).kind == RelationKind::Unknown {
+            None
+        } else {
+            Some(columns.len())
+        };
+
+        // The values are read before the target table is in scope.
+        let (source, upsert) = match &insert.action.kind {
+            ast::InsertActionKind::Values(values) => {
+                self.scopes.push(Scope::default());
+                let rows = values
+                    .rows
+                    .iter()
+                    .map(|row| row.iter().map(|expr| self.bind_expr(expr)).collect())
+                    .collect();
+                self.scopes.pop();
+                (BoundInsertSource::Values(rows), values.upsert.as_ref())
+            }
+            ast::InsertActionKind::Select(select) => (
+                BoundInsertSource::Select(Box::new(self.bind_select(&select.select))),
+                select.upsert.as_ref(),
+            ),
+            ast::InsertActionKind::DefaultValues => (BoundInsertSource::DefaultValues, None),
+        };
+
+        self.scopes.push(Scope::default());
+        let name_span = insert.table.alias.as_ref().unwrap_or(&insert.table.id).span;
+        self.add_to_scope(table, name_span);
+        let upsert = upsert.map(|upsert| self.bind_upsert(table, upsert));
+        let returning = self.bind_returning(&insert.returning);
+        self.scopes.pop();
+        self.ctes.truncate(cte_depth);
+
+        BoundInsert {
+            span: insert.span,
+            ctes,
+            table,
+            columns,
+            value_count,
+            source,
+            upsert,
+            returning,
+        }
+    }
+
+    fn bind_upsert(&mut self, table: RelationId, upsert: &ast::UpsertClause) -> BoundUpsert {
+        let target = upsert.target.as_ref().map(|target| BoundUpsertTarget {
+            span: target.span,
+            columns: target.columns.iter().map(|column| self.bind_expr(&column.expr)).collect(),
+            where_clause: target.where_clause.as_ref().map(|expr| self.bind_expr(expr)),
+        });
+        let action = match &upsert.action {
+            ast::UpsertAction::Nothing => BoundUpsertAction::Nothing,
+            ast::UpsertAction::Update(update) => {
+                let kind = match self.relation(table).kind {
+                    RelationKind::Table(id) => RelationKind::Excluded(id),
+                    _ => RelationKind::Unknown,
+                };
+                let columns = self.relation(table).columns.clone();
+                let excluded = self.add_relation(Name::new("excluded".to_string()), kind, columns);
+                self.add_to_scope(excluded, update.span);
+                BoundUpsertAction::Update {
+                    excluded,
+                    assignments: self.bind_assignments(table, &update.assignments),
+                    where_clause: update.where_clause.as_ref().map(|expr| Box::new(self.bind_expr(expr))),
+                }
+            }
+        };
+        BoundUpsert {
+            span: upsert.span,
+            target,
+            action,
+        }
+    }
+
+    fn bind_update(&mut self, update: &ast::StmtUpdate) -> BoundUpdate {
+        let cte_depth = self.ctes.len();
+        let ctes = match &update.with {
+            Some(with) => self.bind_with(with),
+            None => Vec::new(),
+        };
+        let table = self.target_relation(&update.table.id, update.table.alias.as_ref());
+        let from = update.from.as_ref().map(|from| self.bind_from(&from.item));
+        let assignments = self.bind_assignments(table, &update.assignments);
+        let where_clause = update.where_clause.as_ref().map(|expr| self.bind_expr(expr));
+        let returning = self.bind_returning(&update.returning);
+        self.scopes.pop();
+        self.ctes.truncate(cte_depth);
+        BoundUpdate {
+            span: update.span,
+            ctes,
+            conflict_action: update.conflict_action.clone(),
+            table,
+            indexed: update.table.indexed.clone(),
+            assignments,
+            from,
+            where_clause,
+            returning,
+        }
+    }
+
+    fn bind_delete(&mut self, delete: &ast::StmtDelete) -> BoundDelete {
+        let cte_depth = self.ctes.len();
+        let ctes = match &delete.with {
+            Some(with) => self.bind_with(with),
+            None => Vec::new(),
+        };
+        let table = self.target_relation(&delete.table.id, delete.table.alias.as_ref());
+        let where_clause = delete.where_clause.as_ref().map(|expr| self.bind_expr(expr));
+        let returning = self.bind_returning(&delete.returning);
+        self.scopes.pop();
+        self.ctes.truncate(cte_depth);
+        BoundDelete {
+            span: delete.span,
+            ctes,
+            table,
+            indexed: delete.table.indexed.clone(),
+            where_clause,
+            returning,
+        }
+    }
+}
+
+// The name of a result column known without binding it, for the columns of
+// a recursive common table expression.
+fn result_column_name(column: &ast::ResultColumn) -> Option<Name> {
+    match &column.kind {
+        ast::ResultColumnKind::Expr(_, Some(alias)) => Some(alias.id.clone()),
+        ast::ResultColumnKind::Expr(ast::Expr::Column(column), None) => Some(column.column.id.clone()),
+        ast::ResultColumnKind::Expr(expr, None) => Some(Name::new(expr.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use source_index::diagnostic::{Renderer, Style};
+
+    use crate::catalog::TableId;
+    use crate::test_util::bind_source;
+    use crate::Bound;
+
+    use super::*;
+
+    fn select(bound: &Bound) -> &BoundSelect {
+        match &bound.stmts[0] {
+            BoundStmt::Select(select) => select,
+            stmt => panic!("expected a select, found {stmt:?}"),
+        }
+    }
+
+    fn column(bound: &Bound, expr: &BoundExpr) -> (String, String) {
+        match expr {
+            BoundExpr::Column(column) => (
+                bound.relation(column.relation).name.to_string(),
+                column.name.to_string(),
+            ),
+            expr => panic!("expected a column, found {expr:?}"),
+        }
+    }
+
+    fn error_kinds(bound: &Bound) -> Vec<BindErrorKind> {
+        bound.errors().iter().map(|error| error.kind.clone()).collect()
+    }
+
+    #[test]
+    fn resolve_columns() {
+        let bound = bind_source("SELECT u.name, total FROM users AS u JOIN orders ON u.id = user_id WHERE id > 1;");
+        let select = select(&bound);
+        assert_eq!(
+            error_kinds(&bound),
+            vec![BindErrorKind::AmbiguousColumn {
+                name: Name::new("id".to_string()),
+                tables: vec![Name::new("u".to_string()), Name::new("orders".to_string())],
+            }]
+        );
+        assert_eq!(column(&bound, &select.columns[0].expr), ("u".to_string(), "name".to_string()));
+        assert_eq!(column(&bound, &select.columns[1].expr), ("orders".to_string(), "total".to_string()));
+        let BoundExpr::Column(total) = &select.columns[1].expr else {
+            unreachable!()
+        };
+        assert_eq!(total.column, ColumnId(2));
+        assert_eq!(bound.relation(total.relation).kind, RelationKind::Table(TableId(2)));
+    }
+
+    #[test]
+    fn unknown_names() {
+        let bound = bind_source(
+            "SELECT nope, missing.id, x.a FROM users; SELECT b FROM x; SELECT users.age FROM users;",
+        );
+        // Columns of a table missing from the catalog are not reported again.
+        assert_eq!(
+            error_kinds(&bound),
+            vec![
+                BindErrorKind::UnknownColumn {
+                    name: Name::new("nope".to_string()),
+                    table: None,
+                },
+                BindErrorKind::UnknownTable {
+                    name: Name::new("missing".to_string()),
+                },
+                BindErrorKind::UnknownTable {
+                    name: Name::new("x".to_string()),
+                },
+                BindErrorKind::UnknownTable {
+                    name: Name::new("x".to_string()),
+                },
+                BindErrorKind::UnknownColumn {
+                    name: Name::new("age".to_string()),
+                    table: Some(Name::new("users".to_string())),
+                },
+            ]
+        );
+        assert!(matches!(select(&bound).columns[0].expr, BoundExpr::Invalid(_)));
+    }
+
+    #[test]
+    fn duplicate_alias_diagnostic() {
+        let source = "SELECT 1 FROM users AS t, orders AS t;";
+        let bound = bind_source(source);
+        let rendered = Renderer::new("query.sql", source, Style::Plain).render(&bound.errors()[0].to_diagnostic());
+        assert_eq!(
+            rendered,
+            "\
+error[B0004]: Table name `t` is used more than once.
+ --> query.sql:1:37
+  |
+1 | SELECT 1 FROM users AS t, orders AS t;
+  |                        - first used here
+  |                                     ^ used again here
+  |
+  = help: give one of the tables a different name with `AS`
+"
+        );
+    }
+
+    #[test]
+    fn using_and_star() {
+        let bound = bind_source("SELECT *, id FROM users JOIN orders USING (id);");
+        assert!(bound.is_valid(), "{:?}", bound.errors());
+        let select = select(&bound);
+        let names: Vec<String> = select.columns.iter().map(|column| column.name.to_string()).collect();
+        assert_eq!(names, vec!["id", "name", "user_id", "total", "id"]);
+        let Some(BoundFrom::Join(join)) = &select.from else {
+            panic!("expected a join")
+        };
+        let Some(BoundExpr::Compare(condition)) = &join.condition else {
+            panic!("expected an equality")
+        };
+        assert_eq!(column(&bound, &condition.left), ("users".to_string(), "id".to_string()));
+        assert_eq!(column(&bound, &condition.right), ("orders".to_string(), "id".to_string()));
+    }
+
+    #[test]
+    fn correlated_subquery_and_ctes() {
+        let bound = bind_source(
+            "WITH big AS (SELECT user_id AS uid FROM orders WHERE total > 100) \
+             SELECT name FROM users WHERE EXISTS (SELECT 1 FROM big WHERE uid = users.id) ORDER BY 1;",
+        );
+        assert!(bound.is_valid(), "{:?}", bound.errors());
+        let select = select(&bound);
+        let Some(BoundExpr::Exists(exists)) = &select.where_clause else {
+            panic!("expected EXISTS")
+        };
+        let Some(BoundExpr::Compare(condition)) = &exists.select.where_clause else {
+            panic!("expected a comparison")
+        };
+        assert_eq!(column(&bound, &condition.left), ("big".to_string(), "uid".to_string()));
+        assert_eq!(column(&bound, &condition.right), ("users".to_string(), "id".to_string()));
+        assert!(matches!(bound.relation(exists.select.id).kind, RelationKind::Select));
+        assert_eq!(column(&bound, &select.order_by[0].expr), ("users".to_string(), "name".to_string()));
+    }
+
+    #[test]
+    fn dml_targets() {
+        let bound = bind_source(
+            "INSERT INTO users (name, age) VALUES ('a') ON CONFLICT (id) DO UPDATE SET name = excluded.name; \
+             UPDATE orders SET total = total + 1 WHERE user_id = 2 RETURNING id; \
+             DELETE FROM users WHERE name = 'x';",
+        );
+        assert_eq!(
+            error_kinds(&bound),
+            vec![
+                BindErrorKind::UnknownColumn {
+                    name: Name::new("age".to_string()),
+                    table: Some(Name::new("users".to_string())),
+                },
+                BindErrorKind::ValueCount { expected: 2, found: 1 },
+            ]
+        );
+        let BoundStmt::Insert(insert) = &bound.stmts[0] else {
+            panic!("expected an insert")
+        };
+        assert_eq!(insert.columns, vec![ColumnId(1)]);
+        let Some(BoundUpsert {
+            action: BoundUpsertAction::Update { assignments, .. },
+            ..
+        }) = &insert.upsert
+        else {
+            panic!("expected DO UPDATE")
+        };
+        assert_eq!(column(&bound, &assignments[0].expr), ("excluded".to_string(), "name".to_string()));
+        let BoundStmt::Update(update) = &bound.stmts[1] else {
+            panic!("expected an update")
+        };
+        assert_eq!(update.assignments[0].columns, vec![ColumnId(2)]);
+        assert_eq!(column(&bound, &update.returning[0].expr), ("orders".to_string(), "id".to_string()));
+    }
+
+    #[test]
+    fn ctes_of_a_statement_end_with_it() {
+        let bound = bind_source(
+            "WITH c AS (SELECT name AS z FROM users) INSERT INTO users (name) SELECT z FROM c; SELECT z FROM c;",
+        );
+        assert_eq!(
+            error_kinds(&bound),
+            vec![BindErrorKind::UnknownTable {
+                name: Name::new("c".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! The bound tree.
+//!
+//! Mirrors the parts of the AST that reference tables and columns, with every
+//! name resolved. Tables, subqueries and common table expressions in scope
+//! are [`Relation`]s and a column reference points at a column of one of
+//! them. Parentheses around a single expression, `NATURAL` and `USING` are
+//! gone: the latter two become the condition of their join.
+
+use ast::name::Name;
+use source_index::span::{Span, Spanned};
+
+use crate::catalog::{ColumnId, TableId};
+
+/// Identifies a [`Relation`] of a [`crate::Bound`] result.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RelationId(pub u32);
+
+/// Identifies a [`BoundCte`] of a [`crate::Bound`] result.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CteId(pub u32);
+
+/// A set of rows a column can be read from. Every reference to a table or
+/// common table expression is a separate relation, so a self join has two
+/// relations for the same table. Every select is the relation of its result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub id: RelationId,
+    /// The alias, or the name of the table. Empty for a select without alias.
+    pub name: Name,
+    pub kind: RelationKind,
+    pub columns: Vec<RelationColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RelationKind {
+    Table(TableId),
+    /// The row proposed for insertion in the DO UPDATE of an upsert.
+    Excluded(TableId),
+    Cte(CteId),
+    Select,
+    /// A table missing from the catalog. Columns of it are not resolved.
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelationColumn {
+    pub name: Name,
+    /// Known for the columns of tables, `None` for computed columns.
+    pub data_type: Option<ast::ExternalType>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundStmt {
+    Select(Box<BoundSelect>),
+    Insert(BoundInsert),
+    Update(BoundUpdate),
+    Delete(BoundDelete),
+    /// Statements without column references, as parsed.
+    Other(Box<ast::Stmt>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundSelect {
+    pub span: Span,
+    /// The relation of the result.
+    pub id: RelationId,
+    pub ctes: Vec<BoundCte>,
+    pub distinct: bool,
+    /// Result columns with `*` and `table.*` expanded.
+    pub columns: Vec<BoundResultColumn>,
+    pub from: Option<BoundFrom>,
+    pub where_clause: Option<BoundExpr>,
+    pub group_by: Vec<BoundExpr>,
+    pub having: Option<BoundExpr>,
+    pub compound: Option<BoundCompound>,
+    /// Terms naming a result column are replaced by its expression, except
+    /// in compound selects where they read the result relation.
+    pub order_by: Vec<BoundOrderingTerm>,
+    pub limit: Option<BoundLimit>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCompound {
+    pub span: Span,
+    pub op: ast::CompoundOperator,
+    pub select: Box<BoundSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCte {
+    pub span: Span,
+    pub id: CteId,
+    pub name: Name,
+    pub columns: Vec<Name>,
+    pub recursive: bool,
+    pub materialized: Option<ast::MaterializeHint>,
+    pub select: Box<BoundSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundResultColumn {
+    pub span: Span,
+    /// The alias, the column name of a column reference, or the text of the
+    /// expression.
+    pub name: Name,
+    pub expr: BoundExpr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundFrom {
+    Table(BoundTable),
+    Subquery(BoundSubquery),
+    Cte(BoundCteScan),
+    Join(BoundJoin),
+}
+
+impl Spanned for BoundFrom {
+    fn span(&self) -> Span {
+        match self {
+            BoundFrom::Table(table) => table.span,
+            BoundFrom::Subquery(subquery) => subquery.span,
+            BoundFrom::Cte(cte) => cte.span,
+            BoundFrom::Join(join) => join.span,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundTable {
+    pub span: Span,
+    pub relation: RelationId,
+    pub indexed: Option<ast::IndexedHint>,
+}
+
+/// A select in a FROM clause or an expression. Its relation is the one of
+/// the select.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundSubquery {
+    pub span: Span,
+    pub select: Box<BoundSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCteScan {
+    pub span: Span,
+    pub relation: RelationId,
+    pub cte: CteId,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundJoin {
+    pub span: Span,
+    pub left: Box<BoundFrom>,
+    pub kind: ast::JoinKind,
+    pub right: Box<BoundFrom>,
+    /// The ON expression, or the column equalities of NATURAL and USING.
+    pub condition: Option<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundOrderingTerm {
+    pub span: Span,
+    pub expr: BoundExpr,
+    pub collation: Option<Name>,
+    pub order: Option<ast::Order>,
+    pub nulls: Option<ast::NullsOrder>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundLimit {
+    pub span: Span,
+    pub limit: BoundExpr,
+    pub offset: Option<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundInsert {
+    pub span: Span,
+    pub ctes: Vec<BoundCte>,
+    pub table: RelationId,
+    /// The columns given a value, all of them in order if none are listed.
+    pub columns: Vec<ColumnId>,
+    /// The number of values each row must have, unknown when no columns are
+    /// listed for a table missing from the catalog.
+    pub value_count: Option<usize>,
+    pub source: BoundInsertSource,
+    pub upsert: Option<BoundUpsert>,
+    pub returning: Vec<BoundResultColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundInsertSource {
+    Values(Vec<Vec<BoundExpr>>),
+    Select(Box<BoundSelect>),
+    DefaultValues,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundUpsert {
+    pub span: Span,
+    pub target: Option<BoundUpsertTarget>,
+    pub action: BoundUpsertAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundUpsertTarget {
+    pub span: Span,
+    pub columns: Vec<BoundExpr>,
+    pub where_clause: Option<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundUpsertAction {
+    Nothing,
+    /// Assignments to the target table, which may read the `excluded` relation.
+    Update {
+        excluded: RelationId,
+        assignments: Vec<BoundAssignment>,
+        where_clause: Option<Box<BoundExpr>>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundAssignment {
+    pub span: Span,
+    /// A single column, or the columns of a `(a, b) = ...` list.
+    pub columns: Vec<ColumnId>,
+    pub expr: BoundExpr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundUpdate {
+    pub span: Span,
+    pub ctes: Vec<BoundCte>,
+    pub conflict_action: Option<ast::ConflictAction>,
+    pub table: RelationId,
+    pub indexed: Option<ast::IndexedHint>,
+    pub assignments: Vec<BoundAssignment>,
+    pub from: Option<BoundFrom>,
+    pub where_clause: Option<BoundExpr>,
+    pub returning: Vec<BoundResultColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundDelete {
+    pub span: Span,
+    pub ctes: Vec<BoundCte>,
+    pub table: RelationId,
+    pub indexed: Option<ast::IndexedHint>,
+    pub where_clause: Option<BoundExpr>,
+    pub returning: Vec<BoundResultColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundExpr {
+    Literal(ast::LiteralValue),
+    Column(ColumnRef),
+    BinaryOp(BoundBinaryOp),
+    UnaryOp(BoundUnaryOp),
+    Compare(BoundCompare),
+    BoolOp(BoundBoolOp),
+    /// A row value, a parenthesised list of two or more expressions.
+    List(BoundList),
+    Subquery(BoundSubquery),
+    Collate(BoundCollate),
+    Like(BoundLike),
+    IsNull(BoundIsNull),
+    Is(BoundIs),
+    Between(BoundBetween),
+    In(BoundIn),
+    Exists(BoundExists),
+    Function(BoundFunction),
+    Case(BoundCase),
+    Cast(BoundCast),
+    Parameter(ast::ExprParameter),
+    /// An expression that failed to parse or to bind.
+    Invalid(ast::ExprInvalid),
+}
+
+impl Spanned for BoundExpr {
+    fn span(&self) -> Span {
+        match self {
+            BoundExpr::Literal(literal) => literal.span(),
+            BoundExpr::Column(column) => column.span,
+            BoundExpr::BinaryOp(expr) => expr.span,
+            BoundExpr::UnaryOp(expr) => expr.span,
+            BoundExpr::Compare(expr) => expr.span,
+            BoundExpr::BoolOp(expr) => expr.span,
+            BoundExpr::List(expr) => expr.span,
+            BoundExpr::Subquery(expr) => expr.span,
+            BoundExpr::Collate(expr) => expr.span,
+            BoundExpr::Like(expr) => expr.span,
+            BoundExpr::IsNull(expr) => expr.span,
+            BoundExpr::Is(expr) => expr.span,
+            BoundExpr::Between(expr) => expr.span,
+            BoundExpr::In(expr) => expr.span,
+            BoundExpr::Exists(expr) => expr.span,
+            BoundExpr::Function(expr) => expr.span,
+            BoundExpr::Case(expr) => expr.span,
+            BoundExpr::Cast(expr) => expr.span,
+            BoundExpr::Parameter(expr) => expr.span,
+            BoundExpr::Invalid(expr) => expr.span,
+        }
+    }
+}
+
+/// A resolved column reference. Columns of a relation of an enclosing
+/// select make a correlated subquery.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnRef {
+    pub span: Span,
+    pub relation: RelationId,
+    pub column: ColumnId,
+    /// The name of the column in its relation.
+    pub name: Name,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundBinaryOp {
+    pub span: Span,
+    pub left: Box<BoundExpr>,
+    pub op: ast::BinaryOperator,
+    pub right: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundUnaryOp {
+    pub span: Span,
+    pub op: ast::UnaryOperator,
+    pub operand: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCompare {
+    pub span: Span,
+    pub left: Box<BoundExpr>,
+    pub op: ast::CmpOperator,
+    pub right: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundBoolOp {
+    pub span: Span,
+    pub left: Box<BoundExpr>,
+    pub op: ast::BooleanOperator,
+    pub right: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundList {
+    pub span: Span,
+    pub exprs: Vec<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCollate {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub collation: Name,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundLike {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+    pub pattern: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundIsNull {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundIs {
+    pub span: Span,
+    pub left: Box<BoundExpr>,
+    pub negated: bool,
+    pub distinct_from: bool,
+    pub right: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundBetween {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+    pub low: Box<BoundExpr>,
+    pub high: Box<BoundExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundIn {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+    pub kind: BoundInKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundInKind {
+    List(Vec<BoundExpr>),
+    Select(Box<BoundSelect>),
+    /// `IN table-name`, a relation of the named table.
+    Table(RelationId),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundExists {
+    pub span: Span,
+    pub negated: bool,
+    pub select: Box<BoundSelect>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundFunction {
+    pub span: Span,
+    pub name: Name,
+    pub distinct: bool,
+    /// `None` for `*`, as in `COUNT(*)`.
+    pub args: Option<Vec<BoundExpr>>,
+    pub filter: Option<Box<BoundExpr>>,
+    pub over: Option<BoundWindow>,
+}
+
+/// A window specification, with named windows and base windows resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundWindow {
+    pub span: Span,
+    pub partition_by: Vec<BoundExpr>,
+    pub order_by: Vec<BoundOrderingTerm>,
+    pub frame: Option<ast::WindowFrame>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCase {
+    pub span: Span,
+    pub operand: Option<Box<BoundExpr>>,
+    pub branches: Vec<BoundCaseBranch>,
+    pub else_expr: Option<Box<BoundExpr>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCaseBranch {
+    pub span: Span,
+    pub condition: BoundExpr,
+    pub result: BoundExpr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundCast {
+    pub span: Span,
+    pub expr: Box<BoundExpr>,
+    pub type_name: ast::TypeName,
+}
@@ -0,0 +1,47 @@
+use ast::{name::Name, ExternalType};
+
+/// Identifies a table of a [`Catalog`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TableId(pub u32);
+
+/// Position of a column in its table or relation, starting at 0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ColumnId(pub u32);
+
+/// The tables known to the binder.
+pub trait Catalog {
+    /// The table called `name`. Names are compared ignoring ASCII case.
+    fn table(&self, name: &str) -> Option<TableSchema>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSchema {
+    pub id: TableId,
+    pub name: Name,
+    pub columns: Vec<ColumnSchema>,
+}
+
+impl TableSchema {
+    /// The column called `name`, ignoring ASCII case.
+    pub fn column(&self, name: &str) -> Option<(ColumnId, &ColumnSchema)> {
+        self.columns
+            .iter()
+            .position(|column| column.name.as_str().eq_ignore_ascii_case(name))
+            .map(|index| (ColumnId(index as u32), &self.columns[index]))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSchema {
+    pub name: Name,
+    pub data_type: ExternalType,
+}
+
+/// A list of tables is the simplest catalog, mostly useful in tests.
+impl Catalog for Vec<TableSchema> {
+    fn table(&self, name: &str) -> Option<TableSchema> {
+        self.iter()
+            .find(|table| table.name.as_str().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+}
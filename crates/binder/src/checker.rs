@@ -0,0 +1,680 @@
+//! Type checking of bound statements.
+//!
+//! Every expression gets a [`DataType`] from its operands, bottom up. An
+//! operand of the wrong type is reported where it is used and the expression
+//! takes [`DataType::Unknown`], which fits anywhere, so one mistake is
+//! reported once.
+
+use std::collections::HashMap;
+
+use ast::name::Name;
+use source_index::span::{Span, Spanned};
+
+use crate::bound::*;
+use crate::catalog::ColumnId;
+use crate::errors::{BindError, BindErrorKind};
+use crate::types::DataType;
+
+pub(crate) struct Checker<'a> {
+    relations: &'a [Relation],
+
+    // column types of the selects checked so far
+    outputs: HashMap<RelationId, Vec<DataType>>,
+
+    ctes: HashMap<CteId, Vec<DataType>>,
+
+    pub(crate) errors: Vec<BindError>,
+}
+
+impl<'a> Checker<'a> {
+    pub(crate) fn new(relations: &'a [Relation]) -> Self {
+        Self {
+            relations,
+            outputs: HashMap::new(),
+            ctes: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn add_error(&mut self, kind: BindErrorKind, span: Span) {
+        self.errors.push(BindError::new(kind, span));
+    }
+
+    pub(crate) fn check_stmt(&mut self, stmt: &BoundStmt) {
+        match stmt {
+            BoundStmt::Select(select) => {
+                self.check_select(select);
+            }
+            BoundStmt::Insert(insert) => self.check_insert(insert),
+            BoundStmt::Update(update) => self.check_update(update),
+            BoundStmt::Delete(delete) => self.check_delete(delete),
+            BoundStmt::Other(_) => {}
+        }
+    }
+
+    fn column_type(&self, relation: RelationId, column: ColumnId) -> DataType {
+        let relation = &self.relations[relation.0 as usize];
+        let index = column.0 as usize;
+        let computed = match relation.kind {
+            RelationKind::Select => self.outputs.get(&relation.id),
+            RelationKind::Cte(cte) => self.ctes.get(&cte),
+            _ => None,
+        };
+        match computed {
+            Some(types) => types.get(index).copied().unwrap_or(DataType::Unknown),
+            None => relation
+                .columns
+                .get(index)
+                .and_then(|column| column.data_type.as_ref())
+                .map_or(DataType::Unknown, DataType::from),
+        }
+    }
+
+    /// Report `expr` unless its type converts to `expected`.
+    fn expect(&mut self, expr: &BoundExpr, expected: DataType) {
+        let found = self.infer(expr);
+        if !found.coerces_to(expected) {
+            self.add_error(BindErrorKind::Mismatch { expected, found }, expr.span());
+        }
+    }
+
+    // Report a value of `expr` stored in a column that cannot hold it.
+    fn expect_column(&mut self, expr: &BoundExpr, relation: RelationId, column: ColumnId) {
+        let expected = self.column_type(relation, column);
+        let found = self.infer(expr);
+        if !found.coerces_to(expected) {
+            let column = self.relations[relation.0 as usize].columns[column.0 as usize].name.clone();
+            self.add_error(BindErrorKind::Assignment { column, expected, found }, expr.span());
+        }
+    }
+
+    fn check_ctes(&mut self, ctes: &[BoundCte]) {
+        for cte in ctes {
+            let types = self.check_select(&cte.select);
+            self.ctes.insert(cte.id, types);
+        }
+    }
+
+    /// Check `select` and return the types of its result columns.
+    fn check_select(&mut self, select: &BoundSelect) -> Vec<DataType> {
+        self.check_ctes(&select.ctes);
+        if let Some(from) = &select.from {
+            self.check_from(from);
+        }
+        let mut types: Vec<DataType> = select.columns.iter().map(|column| self.infer(&column.expr)).collect();
+        if let Some(expr) = &select.where_clause {
+            self.expect(expr, DataType::Boolean);
+        }
+        for expr in &select.group_by {
+            self.infer(expr);
+        }
+        if let Some(expr) = &select.having {
+            self.expect(expr, DataType::Boolean);
+        }
+        if let Some(compound) = &select.compound {
+            let other = self.check_select(&compound.select);
+            if other.len() != types.len() {
+                let kind = BindErrorKind::ColumnCount {
+                    expected: types.len(),
+                    found: other.len(),
+                };
+                self.add_error(kind, compound.select.span);
+            } else {
+                for (index, (ty, other_ty)) in types.iter_mut().zip(other).enumerate() {
+                    match ty.unify(other_ty) {
+                        Some(unified) => *ty = unified,
+                        None => {
+                            let span = compound.select.columns[index].span;
+                            let kind = BindErrorKind::Mismatch {
+                                expected: *ty,
+                                found: other_ty,
+                            };
+                            self.add_error(kind, span);
+                            *ty = DataType::Unknown;
+                        }
+                    }
+                }
+            }
+        }
+        self.outputs.insert(select.id, types.clone());
+        for term in &select.order_by {
+            self.infer(&term.expr);
+        }
+        if let Some(limit) = &select.limit {
+            self.expect(&limit.limit, DataType::Integer);
+            if let Some(offset) = &limit.offset {
+                self.expect(offset, DataType::Integer);
+            }
+        }
+        types
+    }
+
+    fn check_from(&mut self, from: &BoundFrom) {
+        match from {
+            BoundFrom::Table(_) | BoundFrom::Cte(_) => {}
+            BoundFrom::Subquery(subquery) => {
+                self.check_select(&subquery.select);
+            }
+            BoundFrom::Join(join) => {
+                self.check_from(&join.left);
+                self.check_from(&join.right);
+                if let Some(condition) = &join.condition {
+                    self.expect(condition, DataType::Boolean);
+                }
+            }
+        }
+    }
+
+    fn check_returning(&mut self, returning: &[BoundResultColumn]) {
+        for column in returning {
+            self.infer(&column.expr);
+        }
+    }
+
+    fn check_assignments(&mut self, table: RelationId, assignments: &[BoundAssignment]) {
+        for assignment in assignments {
+            match assignment.columns.as_slice() {
+                [column] => self.expect_column(&assignment.expr, table, *column),
+                _ => {
+                    self.infer(&assignment.expr);
+                }
+            }
+        }
+    }
+
+    fn check_insert(&mut self, insert: &BoundInsert) {
+        self.check_ctes(&insert.ctes);
+        // Values are only matched to columns when there is one per column,
+        // which is not the case after an unknown column.
+        match &insert.source {
+            BoundInsertSource::Values(rows) => {
+                for row in rows {
+                    self.check_value_count(insert, row.len(), row_span(row, insert.span));
+                    if row.len() != insert.columns.len() {
+                        for expr in row {
+                            self.infer(expr);
+                        }
+                        continue;
+                    }
+                    for (expr, column) in row.iter().zip(&insert.columns) {
+                        self.expect_column(expr, insert.table, *column);
+                    }
+                }
+            }
+            BoundInsertSource::Select(select) => {
+                let types = self.check_select(select);
+                self.check_value_count(insert, types.len(), select.span);
+                if types.len() != insert.columns.len() {
+                    return self.check_upsert_and_returning(insert);
+                }
+                for ((found, column), result) in types.into_iter().zip(&insert.columns).zip(&select.columns) {
+                    let expected = self.column_type(insert.table, *column);
+                    if !found.coerces_to(expected) {
+                        let relation = &self.relations[insert.table.0 as usize];
+                        let column = relation.columns[column.0 as usize].name.clone();
+                        self.add_error(BindErrorKind::Assignment { column, expected, found }, result.span);
+                    }
+                }
+            }
+            BoundInsertSource::DefaultValues => {}
+        }
+        self.check_upsert_and_returning(insert);
+    }
+
+    fn check_value_count(&mut self, insert: &BoundInsert, found: usize, span: Span) {
+        match insert.value_count {
+            Some(expected) if expected != found => {
+                self.add_error(BindErrorKind::ValueCount { expected, found }, span);
+            }
+            _ => {}
+        }
+    }
+
+    fn check_upsert_and_returning(&mut self, insert: &BoundInsert) {
+        if let Some(upsert) = &insert.upsert {
+            if let Some(target) = &upsert.target {
+                for column in &target.columns {
+                    self.infer(column);
+                }
+                if let Some(expr) = &target.where_clause {
+                    self.expect(expr, DataType::Boolean);
+                }
+            }
+            if let BoundUpsertAction::Update {
+                assignments,
+                where_clause,
+                ..
+            } = &upsert.action
+            {
+                self.check_assignments(insert.table, assignments);
+                if let Some(expr) = where_clause {
+                    self.expect(expr, DataType::Boolean);
+                }
+            }
+        }
+        self.check_returning(&insert.returning);
+    }
+
+    fn check_update(&mut self, update: &BoundUpdate) {
+        self.check_ctes(&update.ctes);
+        if let Some(from) = &update.from {
+            self.check_from(from);
+        }
+        self.check_assignments(update.table, &update.assignments);
+        if let Some(expr) = &update.where_clause {
+            self.expect(expr, DataType::Boolean);
+        }
+        self.check_returning(&update.returning);
+    }
+
+    fn check_delete(&mut self, delete: &BoundDelete) {
+        self.check_ctes(&delete.ctes);
+        if let Some(expr) = &delete.where_clause {
+            self.expect(expr, DataType::Boolean);
+        }
+        self.check_returning(&delete.returning);
+    }
+
+    // The type of a select used as a value, the type of its only column.
+    fn scalar_select(&mut self, select: &BoundSelect) -> DataType {
+        match self.check_select(select).as_slice() {
+            [ty] => *ty,
+            _ => DataType::Unknown,
+        }
+    }
+
+    // Report a comparison of `left` with `right` if they have no common type.
+    fn compare(&mut self, left: DataType, right: DataType, span: Span) {
+        if left.unify(right).is_none() {
+            self.add_error(BindErrorKind::Incomparable { left, right }, span);
+        }
+    }
+
+    /// The type of `expr`, reporting the type errors in it.
+    pub(crate) fn infer(&mut self, expr: &BoundExpr) -> DataType {
+        match expr {
+            BoundExpr::Literal(literal) => match literal {
+                ast::LiteralValue::StringLiteral(_) => DataType::Varchar,
+                ast::LiteralValue::IntLiteral(_) => DataType::Integer,
+                ast::LiteralValue::FloatLiteral(_) => DataType::Numeric,
+                ast::LiteralValue::NullLiteral(_) => DataType::Null,
+                ast::LiteralValue::BoolLiteral(_) => DataType::Boolean,
+                ast::LiteralValue::Blob(_) => DataType::Blob,
+            },
+            BoundExpr::Column(column) => self.column_type(column.relation, column.column),
+            BoundExpr::BinaryOp(expr) => {
+                let left = self.infer(&expr.left);
+                let right = self.infer(&expr.right);
+                let numeric = |ty: DataType| ty.is_numeric() || ty.is_unconstrained();
+                match left.unify(right) {
+                    Some(ty) if numeric(left) && numeric(right) => ty,
+                    _ => {
+                        let op = expr.op.clone();
+                        self.add_error(BindErrorKind::InvalidOperands { op, left, right }, expr.span);
+                        DataType::Unknown
+                    }
+                }
+            }
+            BoundExpr::UnaryOp(expr) => {
+                if expr.op == ast::UnaryOperator::Not {
+                    self.expect(&expr.operand, DataType::Boolean);
+                    return DataType::Boolean;
+                }
+                let operand = self.infer(&expr.operand);
+                if operand.is_numeric() || operand.is_unconstrained() {
+                    return operand;
+                }
+                let op = expr.op.clone();
+                self.add_error(BindErrorKind::InvalidOperand { op, operand }, expr.operand.span());
+                DataType::Unknown
+            }
+            BoundExpr::Compare(expr) => {
+                if let (BoundExpr::List(left), BoundExpr::List(right)) = (&*expr.left, &*expr.right) {
+                    for (left, right) in left.exprs.iter().zip(&right.exprs) {
+                        let (left_ty, right_ty) = (self.infer(left), self.infer(right));
+                        self.compare(left_ty, right_ty, Span::new(left.span().start(), right.span().end()));
+                    }
+                    return DataType::Boolean;
+                }
+                let left = self.infer(&expr.left);
+                let right = self.infer(&expr.right);
+                self.compare(left, right, expr.span);
+                DataType::Boolean
+            }
+            BoundExpr::BoolOp(expr) => {
+                self.expect(&expr.left, DataType::Boolean);
+                self.expect(&expr.right, DataType::Boolean);
+                DataType::Boolean
+            }
+            BoundExpr::List(list) => {
+                for expr in &list.exprs {
+                    self.infer(expr);
+                }
+                DataType::Unknown
+            }
+            BoundExpr::Subquery(subquery) => self.scalar_select(&subquery.select),
+            BoundExpr::Collate(expr) => {
+                let ty = self.infer(&expr.expr);
+                if !ty.coerces_to(DataType::Varchar) {
+                    let kind = BindErrorKind::Mismatch {
+                        expected: DataType::Varchar,
+                        found: ty,
+                    };
+                    self.add_error(kind, expr.expr.span());
+                }
+                ty
+            }
+            BoundExpr::Like(expr) => {
+                self.expect(&expr.expr, DataType::Varchar);
+                self.expect(&expr.pattern, DataType::Varchar);
+                DataType::Boolean
+            }
+            BoundExpr::IsNull(expr) => {
+                self.infer(&expr.expr);
+                DataType::Boolean
+            }
+            BoundExpr::Is(expr) => {
+                let left = self.infer(&expr.left);
+                let right = self.infer(&expr.right);
+                self.compare(left, right, expr.span);
+                DataType::Boolean
+            }
+            BoundExpr::Between(expr) => {
+                let ty = self.infer(&expr.expr);
+                for bound in [&expr.low, &expr.high] {
+                    let bound_ty = self.infer(bound);
+                    self.compare(ty, bound_ty, bound.span());
+                }
+                DataType::Boolean
+            }
+            BoundExpr::In(expr) => {
+                let ty = self.infer(&expr.expr);
+                match &expr.kind {
+                    BoundInKind::List(items) => {
+                        for item in items {
+                            let item_ty = self.infer(item);
+                            self.compare(ty, item_ty, item.span());
+                        }
+                    }
+                    BoundInKind::Select(select) => {
+                        let select_ty = self.scalar_select(select);
+                        self.compare(ty, select_ty, select.span);
+                    }
+                    BoundInKind::Table(relation) => {
+                        let table_ty = self.column_type(*relation, ColumnId(0));
+                        self.compare(ty, table_ty, expr.span);
+                    }
+                }
+                DataType::Boolean
+            }
+            BoundExpr::Exists(expr) => {
+                self.check_select(&expr.select);
+                DataType::Boolean
+            }
+            BoundExpr::Function(function) => self.infer_function(function),
+            BoundExpr::Case(case) => {
+                let operand = case.operand.as_ref().map(|operand| self.infer(operand));
+                let mut ty = DataType::Null;
+                for branch in &case.branches {
+                    match operand {
+                        Some(operand) => {
+                            let condition = self.infer(&branch.condition);
+                            self.compare(operand, condition, branch.condition.span());
+                        }
+                        None => self.expect(&branch.condition, DataType::Boolean),
+                    }
+                    ty = self.unify_result(ty, &branch.result);
+                }
+                if let Some(expr) = &case.else_expr {
+                    ty = self.unify_result(ty, expr);
+                }
+                ty
+            }
+            BoundExpr::Cast(cast) => {
+                self.infer(&cast.expr);
+                DataType::from(&cast.type_name.external_type)
+            }
+            BoundExpr::Parameter(_) | BoundExpr::Invalid(_) => DataType::Unknown,
+        }
+    }
+
+    // Add the result `expr` of a CASE branch to the type `ty` of the others.
+    fn unify_result(&mut self, ty: DataType, expr: &BoundExpr) -> DataType {
+        let found = self.infer(expr);
+        match ty.unify(found) {
+            Some(ty) => ty,
+            None => {
+                self.add_error(BindErrorKind::Mismatch { expected: ty, found }, expr.span());
+                DataType::Unknown
+            }
+        }
+    }
+
+    fn infer_function(&mut self, function: &BoundFunction) -> DataType {
+        let args: Vec<DataType> = function
+            .args
+            .iter()
+            .flatten()
+            .map(|arg| self.infer(arg))
+            .collect();
+        if let Some(filter) = &function.filter {
+            self.expect(filter, DataType::Boolean);
+        }
+        if let Some(window) = &function.over {
+            for expr in &window.partition_by {
+                self.infer(expr);
+            }
+            for term in &window.order_by {
+                self.infer(&term.expr);
+            }
+        }
+        function_type(&function.name, &args)
+    }
+}
+
+// The values of a `VALUES` row without its parentheses, or `default` for an
+// empty row.
+fn row_span(row: &[BoundExpr], default: Span) -> Span {
+    match (row.first(), row.last()) {
+        (Some(first), Some(last)) => Span::new(first.span().start(), last.span().end()),
+        _ => default,
+    }
+}
+
+// The result type of the built-in functions whose type is known before
+// execution. Arguments are not checked.
+fn function_type(name: &Name, args: &[DataType]) -> DataType {
+    let first = args.first().copied().unwrap_or(DataType::Unknown);
+    match name.as_str().to_ascii_lowercase().as_str() {
+        "count" | "length" | "row_number" | "rank" | "dense_rank" | "ntile" => DataType::Integer,
+        "avg" | "percent_rank" | "cume_dist" => DataType::Numeric,
+        "min" | "max" => first,
+        "sum" | "abs" | "round" if first.is_numeric() => first,
+        "lower" | "upper" | "trim" | "ltrim" | "rtrim" | "substr" | "replace" | "group_concat" => {
+            DataType::Varchar
+        }
+        "coalesce" | "ifnull" => args
+            .iter()
+            .try_fold(DataType::Null, |ty, arg| ty.unify(*arg))
+            .unwrap_or(DataType::Unknown),
+        _ => DataType::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use source_index::diagnostic::{Renderer, Style};
+
+    use crate::test_util::bind_source;
+
+    use super::*;
+
+    fn error_kinds(source: &str) -> Vec<BindErrorKind> {
+        bind_source(source).errors().iter().map(|error| error.kind.clone()).collect()
+    }
+
+    // The result column types of the select `source`.
+    fn result_types(source: &str) -> Vec<DataType> {
+        let bound = bind_source(source);
+        assert!(bound.is_valid(), "{:?}", bound.errors());
+        let BoundStmt::Select(select) = &bound.stmts[0] else {
+            panic!("expected a select")
+        };
+        Checker::new(bound.relations()).check_select(select)
+    }
+
+    #[test]
+    fn cannot_add_varchar_and_integer() {
+        let source = "SELECT name + 1 FROM users;";
+        let bound = bind_source(source);
+        let rendered = Renderer::new("query.sql", source, Style::Plain).render(&bound.errors()[0].to_diagnostic());
+        assert_eq!(
+            rendered,
+            "\
+error[B0006]: Cannot add VARCHAR and INTEGER.
+ --> query.sql:1:8
+  |
+1 | SELECT name + 1 FROM users;
+  |        ^^^^^^^^ VARCHAR + INTEGER
+  |
+  = help: convert the value with `CAST(<expr> AS <type>)`
+"
+        );
+    }
+
+    #[test]
+    fn infer_and_coerce() {
+        assert_eq!(
+            result_types(
+                "SELECT id + 1, id * total, -id, NULL + 1, ? + 1, id > 1.5, \
+                 CASE WHEN id = 1 THEN id ELSE total END, count(*), max(name), CAST(id AS VARCHAR) \
+                 FROM users JOIN orders USING (id);"
+            ),
+            vec![
+                DataType::Integer,
+                DataType::Numeric,
+                DataType::Integer,
+                DataType::Integer,
+                DataType::Unknown,
+                DataType::Boolean,
+                DataType::Numeric,
+                DataType::Integer,
+                DataType::Varchar,
+                DataType::Varchar,
+            ]
+        );
+        assert_eq!(
+            result_types("SELECT x FROM (SELECT id AS x FROM orders UNION SELECT total FROM orders);"),
+            vec![DataType::Numeric]
+        );
+    }
+
+    #[test]
+    fn compound_column_counts() {
+        let source = "SELECT 1, 2 UNION SELECT 1; SELECT id FROM orders EXCEPT SELECT id, total FROM orders;";
+        assert_eq!(
+            error_kinds(source),
+            vec![
+                BindErrorKind::ColumnCount { expected: 2, found: 1 },
+                BindErrorKind::ColumnCount { expected: 1, found: 2 },
+            ]
+        );
+        assert_eq!(&source[bind_source(source).errors()[0].span], "SELECT 1");
+    }
+
+    #[test]
+    fn insert_value_counts() {
+        let source = "INSERT INTO users (id, name) VALUES (1, 'a'), (1); \
+                      INSERT INTO users (id) SELECT id, name FROM users;";
+        assert_eq!(
+            error_kinds(source),
+            vec![
+                BindErrorKind::ValueCount { expected: 2, found: 1 },
+                BindErrorKind::ValueCount { expected: 1, found: 2 },
+            ]
+        );
+        let errors = bind_source(source).errors().to_vec();
+        assert_eq!(&source[errors[0].span], "1");
+        assert_eq!(&source[errors[1].span], "SELECT id, name FROM users");
+
+        // An unknown column is reported on its own.
+        assert_eq!(
+            error_kinds("INSERT INTO users (id, nope) VALUES (1, 2);"),
+            vec![BindErrorKind::UnknownColumn {
+                name: Name::new("nope".to_string()),
+                table: Some(Name::new("users".to_string())),
+            }]
+        );
+    }
+
+    #[test]
+    fn boolean_contexts() {
+        assert_eq!(
+            error_kinds("SELECT NOT name FROM users WHERE id AND TRUE; SELECT 1 FROM orders LIMIT 'a';"),
+            vec![
+                BindErrorKind::Mismatch {
+                    expected: DataType::Boolean,
+                    found: DataType::Varchar,
+                },
+                BindErrorKind::Mismatch {
+                    expected: DataType::Boolean,
+                    found: DataType::Integer,
+                },
+                BindErrorKind::Mismatch {
+                    expected: DataType::Integer,
+                    found: DataType::Varchar,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(
+            error_kinds(
+                "SELECT 1 FROM users JOIN orders USING (id) WHERE name = 1 OR id BETWEEN 1 AND 'x' OR total IN (1, 2.5, NULL) \
+                 OR (id, name) = (1, 'a') OR -name = 1;"
+            ),
+            vec![
+                BindErrorKind::Incomparable {
+                    left: DataType::Varchar,
+                    right: DataType::Integer,
+                },
+                BindErrorKind::Incomparable {
+                    left: DataType::Integer,
+                    right: DataType::Varchar,
+                },
+                BindErrorKind::InvalidOperand {
+                    op: ast::UnaryOperator::Negative,
+                    operand: DataType::Varchar,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn assignments() {
+        assert_eq!(
+            error_kinds(
+                "INSERT INTO users VALUES (1, 2); INSERT INTO orders (total, user_id) VALUES (1, NULL); \
+                 UPDATE orders SET total = 'x' WHERE id = 1; INSERT INTO orders (id, nope) VALUES (1, 2);"
+            ),
+            vec![
+                BindErrorKind::Assignment {
+                    column: Name::new("name".to_string()),
+                    expected: DataType::Varchar,
+                    found: DataType::Integer,
+                },
+                BindErrorKind::Assignment {
+                    column: Name::new("total".to_string()),
+                    expected: DataType::Numeric,
+                    found: DataType::Varchar,
+                },
+                BindErrorKind::UnknownColumn {
+                    name: Name::new("nope".to_string()),
+                    table: Some(Name::new("orders".to_string())),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt::Display;
+
+use ast::name::Name;
+use ast::{BinaryOperator, UnaryOperator};
+use source_index::diagnostic::{Diagnostic, Label};
+use source_index::span::Span;
+
+use crate::types::DataType;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BindError {
+    pub kind: BindErrorKind,
+
+    pub span: Span,
+}
+
+impl BindError {
+    pub fn new(kind: BindErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let label = match &self.kind {
+            BindErrorKind::UnknownTable { .. } => "no such table".to_string(),
+            BindErrorKind::UnknownColumn { .. } => "no such column".to_string(),
+            BindErrorKind::AmbiguousColumn { .. } => "ambiguous column".to_string(),
+            BindErrorKind::DuplicateAlias { .. } => "used again here".to_string(),
+            BindErrorKind::UnknownWindow { .. } => "no such window".to_string(),
+            BindErrorKind::InvalidOperands { op, left, right } => {
+                format!("{left} {op} {right}")
+            }
+            BindErrorKind::InvalidOperand { operand, .. } => format!("has type {operand}"),
+            BindErrorKind::Incomparable { left, right } => format!("{left} compared with {right}"),
+            BindErrorKind::Mismatch { found, .. } => format!("has type {found}"),
+            BindErrorKind::Assignment { found, .. } => format!("has type {found}"),
+            BindErrorKind::ColumnCount { found, .. } => format!("has {found} columns"),
+            BindErrorKind::ValueCount { found, .. } => format!("has {found} values"),
+        };
+        let mut diagnostic = Diagnostic::error(self.kind.to_string())
+            .with_code(self.kind.code())
+            .with_label(Label::primary(self.span, label));
+        if let BindErrorKind::DuplicateAlias { first, .. } = &self.kind {
+            diagnostic = diagnostic.with_label(Label::secondary(*first, "first used here"));
+        }
+        if let Some(hint) = self.kind.hint() {
+            diagnostic = diagnostic.with_note(format!("help: {hint}"));
+        }
+        diagnostic
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BindErrorKind {
+    UnknownTable {
+        name: Name,
+    },
+    /// `table` is the qualifier of the column, if it had one.
+    UnknownColumn {
+        name: Name,
+        table: Option<Name>,
+    },
+    /// `tables` are the relations having a column of that name.
+    AmbiguousColumn {
+        name: Name,
+        tables: Vec<Name>,
+    },
+    /// Two tables of a FROM clause with the same name. `first` is the span
+    /// of the first one.
+    DuplicateAlias {
+        name: Name,
+        first: Span,
+    },
+    UnknownWindow {
+        name: Name,
+    },
+    /// Arithmetic on operands that are not both numbers.
+    InvalidOperands {
+        op: BinaryOperator,
+        left: DataType,
+        right: DataType,
+    },
+    /// A sign in front of an operand that is not a number.
+    InvalidOperand {
+        op: UnaryOperator,
+        operand: DataType,
+    },
+    /// A comparison of values without a common type.
+    Incomparable {
+        left: DataType,
+        right: DataType,
+    },
+    /// An expression of the wrong type, like a WHERE clause that is not a
+    /// BOOLEAN.
+    Mismatch {
+        expected: DataType,
+        found: DataType,
+    },
+    /// A value of the wrong type inserted in or assigned to `column`.
+    Assignment {
+        column: Name,
+        expected: DataType,
+        found: DataType,
+    },
+    /// A select of a UNION, INTERSECT or EXCEPT with a different number of
+    /// columns than the first one.
+    ColumnCount {
+        expected: usize,
+        found: usize,
+    },
+    /// A row or select inserting a different number of values than there
+    /// are columns to insert into.
+    ValueCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl BindErrorKind {
+    /// A stable code identifying the kind of error, like the `P` codes of
+    /// parse errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BindErrorKind::UnknownTable { .. } => "B0001",
+            BindErrorKind::UnknownColumn { .. } => "B0002",
+            BindErrorKind::AmbiguousColumn { .. } => "B0003",
+            BindErrorKind::DuplicateAlias { .. } => "B0004",
+            BindErrorKind::UnknownWindow { .. } => "B0005",
+            BindErrorKind::InvalidOperands { .. } => "B0006",
+            BindErrorKind::InvalidOperand { .. } => "B0007",
+            BindErrorKind::Incomparable { .. } => "B0008",
+            BindErrorKind::Mismatch { .. } => "B0009",
+            BindErrorKind::Assignment { .. } => "B0010",
+            BindErrorKind::ColumnCount { .. } => "B0011",
+            BindErrorKind::ValueCount { .. } => "B0012",
+        }
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            BindErrorKind::AmbiguousColumn { name, tables } => {
+                format!("qualify the column with its table, e.g. `{}.{name}`", tables[0])
+            }
+            BindErrorKind::DuplicateAlias { .. } => {
+                "give one of the tables a different name with `AS`".to_string()
+            }
+            BindErrorKind::InvalidOperands { .. }
+            | BindErrorKind::Incomparable { .. }
+            | BindErrorKind::Assignment { .. } => "convert the value with `CAST(<expr> AS <type>)`".to_string(),
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+impl Display for BindErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindErrorKind::UnknownTable { name } => write!(f, "Unknown table `{name}`."),
+            BindErrorKind::UnknownColumn { name, table: Some(table) } => {
+                write!(f, "Unknown column `{name}` in `{table}`.")
+            }
+            BindErrorKind::UnknownColumn { name, table: None } => {
+                write!(f, "Unknown column `{name}`.")
+            }
+            BindErrorKind::AmbiguousColumn { name, tables } => {
+                let tables: Vec<String> = tables.iter().map(|table| format!("`{table}`")).collect();
+                write!(f, "Column `{name}` is ambiguous, it is in {}.", tables.join(" and "))
+            }
+            BindErrorKind::DuplicateAlias { name, .. } => {
+                write!(f, "Table name `{name}` is used more than once.")
+            }
+            BindErrorKind::UnknownWindow { name } => write!(f, "Unknown window `{name}`."),
+            BindErrorKind::InvalidOperands { op, left, right } => {
+                let verb = match op {
+                    BinaryOperator::Add => "add",
+                    BinaryOperator::Sub => "subtract",
+                    BinaryOperator::Mult => "multiply",
+                    BinaryOperator::Div => "divide",
+                    BinaryOperator::Mod => "take the remainder of",
+                };
+                write!(f, "Cannot {verb} {left} and {right}.")
+            }
+            BindErrorKind::InvalidOperand { op, operand } => match op {
+                UnaryOperator::Negative => write!(f, "Cannot negate {operand}."),
+                _ => write!(f, "Cannot apply unary `+` to {operand}."),
+            },
+            BindErrorKind::Incomparable { left, right } => write!(f, "Cannot compare {left} and {right}."),
+            BindErrorKind::Mismatch { expected, found } => write!(f, "Expected {expected}, found {found}."),
+            BindErrorKind::Assignment { column, expected, found } => {
+                write!(f, "Cannot assign {found} to column `{column}` of type {expected}.")
+            }
+            BindErrorKind::ColumnCount { expected, found } => {
+                write!(f, "Expected {expected} columns, found {found}.")
+            }
+            BindErrorKind::ValueCount { expected, found } => {
+                write!(f, "Expected {expected} values, one per inserted column, found {found}.")
+            }
+        }
+    }
+}
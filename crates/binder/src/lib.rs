@@ -0,0 +1,81 @@
+//! Name resolution for parsed statements.
+//!
+//! [`bind`] resolves every table and column name of a statement against a
+//! [`Catalog`] and returns a [`bound`] tree in which a column reference is a
+//! [`bound::ColumnRef`] to a column of a [`bound::Relation`]. Names that do
+//! not resolve are reported as [`BindError`]s, which render like parse
+//! errors, and are left in the tree as [`bound::BoundExpr::Invalid`].
+//! The bound expressions are then type checked, reporting operands of the
+//! wrong [`DataType`] as errors of the same kind.
+
+use ast::Stmts;
+
+use crate::binder::Binder;
+use crate::bound::{BoundStmt, Relation, RelationId};
+use crate::checker::Checker;
+
+mod binder;
+pub mod bound;
+mod catalog;
+mod checker;
+mod errors;
+#[cfg(test)]
+mod test_util;
+mod types;
+
+pub use crate::catalog::{Catalog, ColumnId, ColumnSchema, TableId, TableSchema};
+pub use crate::errors::{BindError, BindErrorKind};
+pub use crate::types::DataType;
+
+/// Bind and type check every statement of `stmts`. Binding carries on past
+/// errors, so the result covers the whole input even when it is not valid.
+pub fn bind(stmts: &Stmts, catalog: &dyn Catalog) -> Bound {
+    let mut binder = Binder::new(catalog);
+    let stmts: Vec<BoundStmt> = stmts.body.iter().map(|stmt| binder.bind_stmt(stmt)).collect();
+    let mut checker = Checker::new(&binder.relations);
+    for stmt in &stmts {
+        checker.check_stmt(stmt);
+    }
+    let mut errors = binder.errors;
+    errors.append(&mut checker.errors);
+    errors.sort_by_key(|error| error.span.start());
+    Bound {
+        stmts,
+        relations: binder.relations,
+        errors,
+    }
+}
+
+/// The bound statements and the relations they read.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Bound {
+    pub stmts: Vec<BoundStmt>,
+    relations: Vec<Relation>,
+    errors: Vec<BindError>,
+}
+
+impl Bound {
+    pub fn relation(&self, id: RelationId) -> &Relation {
+        &self.relations[id.0 as usize]
+    }
+
+    /// Returns the relations of all statements, indexed by [`RelationId`].
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    /// Returns the names that failed to resolve and the type errors, in
+    /// source order.
+    pub fn errors(&self) -> &[BindError] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<BindError> {
+        self.errors
+    }
+
+    /// Returns `true` if every name resolved and every expression type checks.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
@@ -0,0 +1,43 @@
+//! The catalog the tests of the binder and the checker bind against.
+
+use ast::name::Name;
+use ast::ExternalType;
+
+use crate::catalog::{ColumnSchema, TableId, TableSchema};
+use crate::{bind, Bound};
+
+fn table(id: u32, name: &str, columns: &[(&str, ExternalType)]) -> TableSchema {
+    TableSchema {
+        id: TableId(id),
+        name: Name::new(name.to_string()),
+        columns: columns
+            .iter()
+            .map(|(name, data_type)| ColumnSchema {
+                name: Name::new(name.to_string()),
+                data_type: data_type.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// `users (id INTEGER, name VARCHAR)` and
+/// `orders (id INTEGER, user_id INTEGER, total NUMERIC)`.
+pub(crate) fn catalog() -> Vec<TableSchema> {
+    vec![
+        table(1, "users", &[("id", ExternalType::Integer), ("name", ExternalType::Varchar)]),
+        table(
+            2,
+            "orders",
+            &[
+                ("id", ExternalType::Integer),
+                ("user_id", ExternalType::Integer),
+                ("total", ExternalType::Numeric),
+            ],
+        ),
+    ]
+}
+
+pub(crate) fn bind_source(source: &str) -> Bound {
+    let parsed = parser::parse_stmts(source).expect("source should parse");
+    bind(&parsed.stmts, &catalog())
+}
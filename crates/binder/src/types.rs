@@ -0,0 +1,85 @@
+use std::fmt::Display;
+
+use ast::ExternalType;
+
+/// The type of an expression.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DataType {
+    /// The type of the `NULL` literal, which converts to any other type.
+    Null,
+    Boolean,
+    Integer,
+    Numeric,
+    Char,
+    Varchar,
+    Blob,
+    /// Not known before execution, like the type of a bind parameter, or
+    /// not known because of an earlier error. Converts to and from any type.
+    Unknown,
+}
+
+impl DataType {
+    pub fn is_numeric(self) -> bool {
+        matches!(self, DataType::Integer | DataType::Numeric)
+    }
+
+    pub fn is_text(self) -> bool {
+        matches!(self, DataType::Char | DataType::Varchar)
+    }
+
+    /// `true` for the types that convert to any other.
+    pub fn is_unconstrained(self) -> bool {
+        matches!(self, DataType::Null | DataType::Unknown)
+    }
+
+    /// Whether a value of this type can be used where `target` is expected.
+    /// Integers widen to numerics and text converts between fixed and
+    /// variable length.
+    pub fn coerces_to(self, target: DataType) -> bool {
+        self == target
+            || self.is_unconstrained()
+            || target == DataType::Unknown
+            || (self == DataType::Integer && target == DataType::Numeric)
+            || (self.is_text() && target.is_text())
+    }
+
+    /// The type both `self` and `other` convert to, if any. This is the type
+    /// of a comparison operand, arithmetic result or CASE result.
+    pub fn unify(self, other: DataType) -> Option<DataType> {
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (DataType::Unknown, _) | (_, DataType::Unknown) => Some(DataType::Unknown),
+            (DataType::Null, other) | (other, DataType::Null) => Some(other),
+            (a, b) if a.is_numeric() && b.is_numeric() => Some(DataType::Numeric),
+            (a, b) if a.is_text() && b.is_text() => Some(DataType::Varchar),
+            _ => None,
+        }
+    }
+}
+
+impl From<&ExternalType> for DataType {
+    fn from(value: &ExternalType) -> Self {
+        match value {
+            ExternalType::Char => DataType::Char,
+            ExternalType::Integer | ExternalType::Serial => DataType::Integer,
+            ExternalType::Numeric => DataType::Numeric,
+            ExternalType::Varchar => DataType::Varchar,
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DataType::Null => "NULL",
+            DataType::Boolean => "BOOLEAN",
+            DataType::Integer => "INTEGER",
+            DataType::Numeric => "NUMERIC",
+            DataType::Char => "CHAR",
+            DataType::Varchar => "VARCHAR",
+            DataType::Blob => "BLOB",
+            DataType::Unknown => "UNKNOWN",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -0,0 +1,424 @@
+//! Schema changes made by `CREATE`, `DROP` and `ALTER TABLE`.
+//!
+//! A statement is checked in full before anything changes, so a failed
+//! statement leaves the catalog as it was. A successful one takes the next
+//! schema version and stamps it on every object it touched.
+
+use ast::name::Name;
+use ast::visitor::{self, Visitor};
+use ast::visitor_mut::{walk_expr, VisitorMut};
+use source_index::span::Spanned;
+
+use crate::errors::{CatalogError, CatalogErrorKind};
+use crate::objects::*;
+use crate::SystemCatalog;
+
+type Result<T> = std::result::Result<T, CatalogError>;
+
+impl SystemCatalog {
+    /// Apply the schema change of `stmt`. Statements other than `CREATE`,
+    /// `DROP` and `ALTER TABLE` change nothing.
+    pub fn apply(&mut self, stmt: &ast::Stmt) -> Result<()> {
+        match stmt {
+            ast::Stmt::Create(create) => self.create(create),
+            ast::Stmt::Drop(drop) => self.drop(drop),
+            ast::Stmt::Alter(alter) => self.alter(alter),
+            _ => Ok(()),
+        }
+    }
+
+    // Bump the schema version for a change and return it.
+    fn next_version(&mut self) -> u64 {
+        self.version += 1;
+        self.version
+    }
+
+    fn next_id(&mut self) -> ObjectId {
+        let id = ObjectId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    // Report `name` if a table or index already has it.
+    fn check_name_free(&self, id: &ast::Identifier) -> Result<()> {
+        let kind = if self.table(&id.id).is_some() {
+            CatalogErrorKind::TableExists { name: id.id.clone() }
+        } else if self.index(&id.id).is_some() {
+            CatalogErrorKind::IndexExists { name: id.id.clone() }
+        } else {
+            return Ok(());
+        };
+        Err(CatalogError::new(kind, id.span))
+    }
+
+    fn create(&mut self, create: &ast::StmtCreate) -> Result<()> {
+        if let Err(error) = self.check_name_free(&create.id) {
+            return if create.exist_check { Ok(()) } else { Err(error) };
+        }
+        match &create.kind {
+            ast::CreateKind::Table(table) => {
+                let table = self.build_table(&create.id.id, table)?;
+                let id = self.next_id();
+                let version = self.next_version();
+                self.insert_table(Table { id, version, ..table });
+            }
+            ast::CreateKind::Index(index) => {
+                let index = self.build_index(&create.id.id, index)?;
+                let id = self.next_id();
+                let version = self.next_version();
+                self.insert_index(Index { id, version, ..index });
+            }
+        }
+        Ok(())
+    }
+
+    /// The table defined by `create`, with a placeholder id and version.
+    pub(crate) fn build_table(&self, name: &Name, create: &ast::CreateTable) -> Result<Table> {
+        let columns = match &create.source {
+            ast::CreateTableSource::Columns(columns) => columns,
+            ast::CreateTableSource::Select(_) => {
+                return Err(CatalogError::new(CatalogErrorKind::CreateAsSelect, create.span));
+            }
+        };
+        let mut table = Table {
+            id: ObjectId(0),
+            version: 0,
+            name: name.clone(),
+            columns: Vec::new(),
+            primary_key: None,
+            unique: Vec::new(),
+            checks: Vec::new(),
+            foreign_keys: Vec::new(),
+        };
+        for def in &columns.columns {
+            add_column(&mut table, def, true)?;
+        }
+        for constraint in &columns.constraints {
+            let name = constraint.name.as_ref().map(|name| name.id.clone());
+            match &constraint.kind {
+                ast::TableConstraintKind::PrimaryKey(key) => {
+                    let key = Key {
+                        name,
+                        columns: key_columns(&table, &key.columns)?,
+                        conflict_action: key.conflict_action.clone(),
+                    };
+                    set_primary_key(&mut table, key, constraint.span)?;
+                }
+                ast::TableConstraintKind::Unique(key) => table.unique.push(Key {
+                    name,
+                    columns: key_columns(&table, &key.columns)?,
+                    conflict_action: key.conflict_action.clone(),
+                }),
+                ast::TableConstraintKind::Check(expr) => table.checks.push(Check {
+                    name,
+                    expr: expr.clone(),
+                }),
+                ast::TableConstraintKind::ForeignKey(foreign_key) => {
+                    for column in &foreign_key.columns {
+                        check_column(&table, column)?;
+                    }
+                    let columns = foreign_key.columns.iter().map(|column| column.id.clone()).collect();
+                    table.foreign_keys.push(ForeignKey::new(name, columns, &foreign_key.clause));
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// The index defined by `create`, with a placeholder id and version.
+    pub(crate) fn build_index(&self, name: &Name, create: &ast::CreateIndex) -> Result<Index> {
+        let Some(table) = self.table(&create.table.id) else {
+            let kind = CatalogErrorKind::UnknownTable {
+                name: create.table.id.clone(),
+            };
+            return Err(CatalogError::new(kind, create.table.span));
+        };
+        for indexed in &create.columns {
+            check_expr_columns(table, &indexed.expr)?;
+        }
+        if let Some(expr) = &create.where_clause {
+            check_expr_columns(table, expr)?;
+        }
+        Ok(Index {
+            id: ObjectId(0),
+            version: 0,
+            name: name.clone(),
+            table: table.id,
+            unique: create.unique,
+            columns: create.columns.clone(),
+            where_clause: create.where_clause.clone(),
+        })
+    }
+
+    fn drop(&mut self, drop: &ast::StmtDrop) -> Result<()> {
+        let name = &drop.id.id;
+        let found = match drop.kind {
+            ast::DdlTargetKind::Table => self.table(name).map(|table| table.id),
+            ast::DdlTargetKind::Index => self.index(name).map(|index| index.id),
+        };
+        let Some(id) = found else {
+            if drop.exist_check {
+                return Ok(());
+            }
+            let kind = match drop.kind {
+                ast::DdlTargetKind::Table => CatalogErrorKind::UnknownTable { name: name.clone() },
+                ast::DdlTargetKind::Index => CatalogErrorKind::UnknownIndex { name: name.clone() },
+            };
+            return Err(CatalogError::new(kind, drop.id.span));
+        };
+        self.next_version();
+        match drop.kind {
+            ast::DdlTargetKind::Table => {
+                self.tables.remove(&id);
+                self.indexes.retain(|_, index| index.table != id);
+            }
+            ast::DdlTargetKind::Index => {
+                self.indexes.remove(&id);
+            }
+        }
+        Ok(())
+    }
+
+    fn alter(&mut self, alter: &ast::StmtAlter) -> Result<()> {
+        let Some(table) = self.table(&alter.id.id) else {
+            let kind = CatalogErrorKind::UnknownTable {
+                name: alter.id.id.clone(),
+            };
+            return Err(CatalogError::new(kind, alter.id.span));
+        };
+        let mut table = table.clone();
+        match &alter.action.kind {
+            ast::AlterTableActionKind::Rename(rename) => match &rename.kind {
+                ast::AlterTableRenameKind::Table(to) => {
+                    self.check_name_free(to)?;
+                    let version = self.next_version();
+                    // Foreign keys name the table they reference, and one of
+                    // them may be in the working copy.
+                    for other in self.tables.values_mut() {
+                        let references = |key: &ForeignKey| same_name(&key.table, &table.name);
+                        if other.foreign_keys.iter().any(references) {
+                            rename_referenced_table(&mut other.foreign_keys, &table.name, &to.id);
+                            other.version = version;
+                        }
+                    }
+                    rename_referenced_table(&mut table.foreign_keys, &table.name.clone(), &to.id);
+                    table.name = to.id.clone();
+                    table.version = version;
+                }
+                ast::AlterTableRenameKind::Column(from, to) => {
+                    check_column(&table, from)?;
+                    if table.column(&to.id).is_some() {
+                        let kind = CatalogErrorKind::DuplicateColumn { name: to.id.clone() };
+                        return Err(CatalogError::new(kind, to.span));
+                    }
+                    let version = self.next_version();
+                    self.rename_column(&mut table, &from.id, &to.id, version);
+                }
+            },
+            ast::AlterTableActionKind::Add(add) => {
+                add_column(&mut table, &add.column, false)?;
+                table.version = self.next_version();
+            }
+            ast::AlterTableActionKind::Drop(drop) => {
+                check_column(&table, &drop.id)?;
+                let in_index = self.indexes_of(table.id).any(|index| index.reads(&drop.id.id));
+                let referenced = self.tables.values().any(|other| {
+                    other.foreign_keys.iter().any(|key| key.references(&table.name, &drop.id.id))
+                });
+                let kind = if table.columns.len() == 1 {
+                    CatalogErrorKind::LastColumn {
+                        table: table.name.clone(),
+                    }
+                } else if in_index || referenced || table.is_constrained(&drop.id.id) {
+                    CatalogErrorKind::ColumnInUse {
+                        name: drop.id.id.clone(),
+                    }
+                } else {
+                    table.columns.retain(|column| !same_name(&column.name, &drop.id.id));
+                    table.version = self.next_version();
+                    self.insert_table(table);
+                    return Ok(());
+                };
+                return Err(CatalogError::new(kind, drop.id.span));
+            }
+        }
+        self.insert_table(table);
+        Ok(())
+    }
+
+    // Rename a column of `table` everywhere it is named: in the table, its
+    // indexes and the foreign keys referencing it.
+    fn rename_column(&mut self, table: &mut Table, from: &Name, to: &Name, version: u64) {
+        let mut renamer = RenameColumn { from, to };
+        if let Some(column) = table.column_mut(from) {
+            column.name = to.clone();
+        }
+        for key in table.primary_key.iter_mut().chain(&mut table.unique) {
+            rename_in(&mut key.columns, from, to);
+        }
+        for foreign_key in &mut table.foreign_keys {
+            rename_in(&mut foreign_key.columns, from, to);
+        }
+        for check in &mut table.checks {
+            renamer.visit_expr(&mut check.expr);
+        }
+        table.version = version;
+
+        for index in self.indexes.values_mut().filter(|index| index.table == table.id) {
+            for indexed in &mut index.columns {
+                renamer.visit_expr(&mut indexed.expr);
+            }
+            if let Some(expr) = &mut index.where_clause {
+                renamer.visit_expr(expr);
+            }
+            index.version = version;
+        }
+        for other in self.tables.values_mut() {
+            for foreign_key in &mut other.foreign_keys {
+                if same_name(&foreign_key.table, &table.name) {
+                    rename_in(&mut foreign_key.referenced_columns, from, to);
+                    other.version = version;
+                }
+            }
+        }
+        // A self-referencing foreign key is in the working copy.
+        for foreign_key in &mut table.foreign_keys {
+            if same_name(&foreign_key.table, &table.name) {
+                rename_in(&mut foreign_key.referenced_columns, from, to);
+            }
+        }
+    }
+}
+
+// Add the column `def` and its constraints to `table`. Columns added by
+// ALTER TABLE cannot take part in a key.
+fn add_column(table: &mut Table, def: &ast::ColumnDef, allow_keys: bool) -> Result<()> {
+    let name = &def.id.id;
+    if table.column(name).is_some() {
+        let kind = CatalogErrorKind::DuplicateColumn { name: name.clone() };
+        return Err(CatalogError::new(kind, def.id.span));
+    }
+    let mut column = Column {
+        name: name.clone(),
+        type_name: def.type_name.clone(),
+        not_null: false,
+        default: None,
+        collation: None,
+    };
+    for constraint in &def.constraint_list.constraints {
+        let constraint_name = constraint.name.as_ref().map(|name| name.id.clone());
+        match &constraint.kind {
+            ast::ColumnConstraintKind::PrimaryKey(_) | ast::ColumnConstraintKind::Unique(_) if !allow_keys => {
+                return Err(CatalogError::new(CatalogErrorKind::KeyColumnAdded, constraint.span));
+            }
+            ast::ColumnConstraintKind::PrimaryKey(primary_key) => {
+                let key = Key {
+                    name: constraint_name,
+                    columns: vec![name.clone()],
+                    conflict_action: primary_key.conflict_action.clone(),
+                };
+                set_primary_key(table, key, constraint.span)?;
+            }
+            ast::ColumnConstraintKind::NotNull(_) => column.not_null = true,
+            ast::ColumnConstraintKind::Unique(action) => table.unique.push(Key {
+                name: constraint_name,
+                columns: vec![name.clone()],
+                conflict_action: action.clone(),
+            }),
+            ast::ColumnConstraintKind::Check(expr) => table.checks.push(Check {
+                name: constraint_name,
+                expr: expr.clone(),
+            }),
+            ast::ColumnConstraintKind::Default(default) => column.default = Some(default.clone()),
+            ast::ColumnConstraintKind::Collate(collation) => column.collation = Some(collation.id.clone()),
+            ast::ColumnConstraintKind::Foreign(clause) => {
+                let foreign_key = ForeignKey::new(constraint_name, vec![name.clone()], clause);
+                table.foreign_keys.push(foreign_key);
+            }
+        }
+    }
+    table.columns.push(column);
+    Ok(())
+}
+
+fn set_primary_key(table: &mut Table, key: Key, span: source_index::span::Span) -> Result<()> {
+    if table.primary_key.is_some() {
+        return Err(CatalogError::new(CatalogErrorKind::MultiplePrimaryKeys, span));
+    }
+    table.primary_key = Some(key);
+    Ok(())
+}
+
+fn check_column(table: &Table, id: &ast::Identifier) -> Result<()> {
+    if table.column(&id.id).is_some() {
+        return Ok(());
+    }
+    let kind = CatalogErrorKind::UnknownColumn {
+        name: id.id.clone(),
+        table: table.name.clone(),
+    };
+    Err(CatalogError::new(kind, id.span))
+}
+
+// Every column read by `expr` must be a column of the table.
+fn check_expr_columns(table: &Table, expr: &ast::Expr) -> Result<()> {
+    let mut columns = ColumnRefs { ids: Vec::new() };
+    columns.visit_expr(expr);
+    columns.ids.iter().try_for_each(|id| check_column(table, id))
+}
+
+// The columns of a PRIMARY KEY or UNIQUE table constraint, which must be
+// columns of the table.
+fn key_columns(table: &Table, columns: &[ast::IndexedColumn]) -> Result<Vec<Name>> {
+    columns
+        .iter()
+        .map(|indexed| match &indexed.expr {
+            ast::Expr::Column(column) if column.table.is_none() => {
+                check_column(table, &column.column)?;
+                Ok(column.column.id.clone())
+            }
+            expr => Err(CatalogError::new(CatalogErrorKind::ExpressionKey, expr.span())),
+        })
+        .collect()
+}
+
+fn rename_referenced_table(foreign_keys: &mut [ForeignKey], from: &Name, to: &Name) {
+    for foreign_key in foreign_keys.iter_mut().filter(|foreign_key| same_name(&foreign_key.table, from)) {
+        foreign_key.table = to.clone();
+    }
+}
+
+fn rename_in(names: &mut [Name], from: &Name, to: &Name) {
+    for name in names.iter_mut().filter(|name| same_name(name, from)) {
+        *name = to.clone();
+    }
+}
+
+struct ColumnRefs {
+    ids: Vec<ast::Identifier>,
+}
+
+impl Visitor for ColumnRefs {
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        if let ast::Expr::Column(column) = expr {
+            self.ids.push(column.column.clone());
+        }
+        visitor::walk_expr(self, expr);
+    }
+}
+
+struct RenameColumn<'a> {
+    from: &'a Name,
+    to: &'a Name,
+}
+
+impl VisitorMut for RenameColumn<'_> {
+    fn visit_expr(&mut self, expr: &mut ast::Expr) {
+        if let ast::Expr::Column(column) = expr {
+            if same_name(&column.column.id, self.from) {
+                column.column.id = self.to.clone();
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
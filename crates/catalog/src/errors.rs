@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use ast::name::Name;
+use source_index::diagnostic::{Diagnostic, Label};
+use source_index::span::Span;
+
+/// A schema change that cannot be made. The catalog is left as it was.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatalogError {
+    pub kind: CatalogErrorKind,
+
+    pub span: Span,
+}
+
+impl CatalogError {
+    pub fn new(kind: CatalogErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let label = match &self.kind {
+            CatalogErrorKind::TableExists { .. } | CatalogErrorKind::IndexExists { .. } => "name already taken",
+            CatalogErrorKind::UnknownTable { .. } => "no such table",
+            CatalogErrorKind::UnknownIndex { .. } => "no such index",
+            CatalogErrorKind::UnknownColumn { .. } => "no such column",
+            CatalogErrorKind::DuplicateColumn { .. } => "defined again here",
+            CatalogErrorKind::MultiplePrimaryKeys => "second primary key",
+            CatalogErrorKind::ColumnInUse { .. } => "still in use",
+            CatalogErrorKind::LastColumn { .. } => "only column",
+            CatalogErrorKind::KeyColumnAdded => "not allowed here",
+            CatalogErrorKind::CreateAsSelect => "not supported",
+            CatalogErrorKind::ExpressionKey => "not a column",
+        };
+        let mut diagnostic = Diagnostic::error(self.kind.to_string())
+            .with_code(self.kind.code())
+            .with_label(Label::primary(self.span, label));
+        if let Some(hint) = self.kind.hint() {
+            diagnostic = diagnostic.with_note(format!("help: {hint}"));
+        }
+        diagnostic
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CatalogErrorKind {
+    /// A table of that name exists, which tables and indexes cannot share.
+    TableExists {
+        name: Name,
+    },
+    IndexExists {
+        name: Name,
+    },
+    UnknownTable {
+        name: Name,
+    },
+    UnknownIndex {
+        name: Name,
+    },
+    UnknownColumn {
+        name: Name,
+        table: Name,
+    },
+    DuplicateColumn {
+        name: Name,
+    },
+    MultiplePrimaryKeys,
+    /// A dropped column is part of a key, foreign key or index.
+    ColumnInUse {
+        name: Name,
+    },
+    LastColumn {
+        table: Name,
+    },
+    /// `ALTER TABLE ... ADD COLUMN` with a PRIMARY KEY or UNIQUE constraint.
+    KeyColumnAdded,
+    /// `CREATE TABLE ... AS SELECT`, whose columns are only known by running
+    /// the select.
+    CreateAsSelect,
+    /// An expression in a PRIMARY KEY or UNIQUE table constraint, which only
+    /// takes columns.
+    ExpressionKey,
+}
+
+impl CatalogErrorKind {
+    /// A stable code identifying the kind of error, like the `P` codes of
+    /// parse errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CatalogErrorKind::TableExists { .. } => "C0001",
+            CatalogErrorKind::IndexExists { .. } => "C0002",
+            CatalogErrorKind::UnknownTable { .. } => "C0003",
+            CatalogErrorKind::UnknownIndex { .. } => "C0004",
+            CatalogErrorKind::UnknownColumn { .. } => "C0005",
+            CatalogErrorKind::DuplicateColumn { .. } => "C0006",
+            CatalogErrorKind::MultiplePrimaryKeys => "C0007",
+            CatalogErrorKind::ColumnInUse { .. } => "C0008",
+            CatalogErrorKind::LastColumn { .. } => "C0009",
+            CatalogErrorKind::KeyColumnAdded => "C0010",
+            CatalogErrorKind::CreateAsSelect => "C0011",
+            CatalogErrorKind::ExpressionKey => "C0012",
+        }
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            CatalogErrorKind::TableExists { .. } | CatalogErrorKind::IndexExists { .. } => {
+                "add `IF NOT EXISTS` to keep the existing one".to_string()
+            }
+            CatalogErrorKind::UnknownTable { .. } | CatalogErrorKind::UnknownIndex { .. } => {
+                "add `IF EXISTS` to ignore a missing object".to_string()
+            }
+            CatalogErrorKind::ColumnInUse { .. } => "drop the index or constraint first".to_string(),
+            CatalogErrorKind::LastColumn { .. } => "drop the table instead".to_string(),
+            CatalogErrorKind::ExpressionKey => "use a unique index on the expression instead".to_string(),
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+impl Display for CatalogErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogErrorKind::TableExists { name } => write!(f, "Table `{name}` already exists."),
+            CatalogErrorKind::IndexExists { name } => write!(f, "Index `{name}` already exists."),
+            CatalogErrorKind::UnknownTable { name } => write!(f, "Unknown table `{name}`."),
+            CatalogErrorKind::UnknownIndex { name } => write!(f, "Unknown index `{name}`."),
+            CatalogErrorKind::UnknownColumn { name, table } => {
+                write!(f, "Unknown column `{name}` in `{table}`.")
+            }
+            CatalogErrorKind::DuplicateColumn { name } => write!(f, "Column `{name}` is defined more than once."),
+            CatalogErrorKind::MultiplePrimaryKeys => f.write_str("A table can only have one primary key."),
+            CatalogErrorKind::ColumnInUse { name } => {
+                write!(f, "Column `{name}` is used by a key or an index and cannot be dropped.")
+            }
+            CatalogErrorKind::LastColumn { table } => write!(f, "Cannot drop the only column of `{table}`."),
+            CatalogErrorKind::KeyColumnAdded => {
+                f.write_str("A column added to a table cannot be a PRIMARY KEY or UNIQUE.")
+            }
+            CatalogErrorKind::CreateAsSelect => f.write_str("CREATE TABLE ... AS SELECT is not supported."),
+            CatalogErrorKind::ExpressionKey => {
+                f.write_str("A PRIMARY KEY or UNIQUE constraint can only list columns, not expressions.")
+            }
+        }
+    }
+}
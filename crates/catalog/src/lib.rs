@@ -0,0 +1,284 @@
+//! The system catalog: the tables, columns, constraints and indexes of a
+//! database.
+//!
+//! A [`SystemCatalog`] is changed by applying `CREATE`, `DROP` and
+//! `ALTER TABLE` statements to it and looked up by name. It is the
+//! [`binder::Catalog`] names are resolved against. A [`CatalogFile`] keeps
+//! one in a database file and writes every change through, so the schema
+//! survives a restart.
+
+use std::collections::BTreeMap;
+
+use ast::name::Name;
+use binder::{ColumnSchema, TableId, TableSchema};
+
+use crate::objects::same_name;
+
+mod ddl;
+mod errors;
+mod objects;
+mod storage;
+
+pub use crate::errors::{CatalogError, CatalogErrorKind};
+pub use crate::objects::{Check, Column, ForeignKey, Index, Key, ObjectId, Table};
+pub use crate::storage::{CatalogFile, Error, StorageError};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SystemCatalog {
+    /// Incremented by every schema change.
+    version: u64,
+    next_id: u32,
+    tables: BTreeMap<ObjectId, Table>,
+    indexes: BTreeMap<ObjectId, Index>,
+}
+
+impl SystemCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of schema changes made so far. Objects record the version
+    /// of their last change.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The table called `name`, ignoring ASCII case.
+    pub fn table(&self, name: &Name) -> Option<&Table> {
+        self.tables.values().find(|table| same_name(&table.name, name))
+    }
+
+    pub fn table_by_id(&self, id: ObjectId) -> Option<&Table> {
+        self.tables.get(&id)
+    }
+
+    /// The index called `name`, ignoring ASCII case.
+    pub fn index(&self, name: &Name) -> Option<&Index> {
+        self.indexes.values().find(|index| same_name(&index.name, name))
+    }
+
+    pub fn index_by_id(&self, id: ObjectId) -> Option<&Index> {
+        self.indexes.get(&id)
+    }
+
+    /// Returns the tables in the order they were created.
+    pub fn tables(&self) -> impl Iterator<Item = &Table> {
+        self.tables.values()
+    }
+
+    /// Returns the indexes in the order they were created.
+    pub fn indexes(&self) -> impl Iterator<Item = &Index> {
+        self.indexes.values()
+    }
+
+    /// Returns the indexes on the table `table`.
+    pub fn indexes_of(&self, table: ObjectId) -> impl Iterator<Item = &Index> {
+        self.indexes.values().filter(move |index| index.table == table)
+    }
+
+    fn insert_table(&mut self, table: Table) {
+        self.next_id = self.next_id.max(table.id.0 + 1);
+        self.tables.insert(table.id, table);
+    }
+
+    fn insert_index(&mut self, index: Index) {
+        self.next_id = self.next_id.max(index.id.0 + 1);
+        self.indexes.insert(index.id, index);
+    }
+}
+
+impl binder::Catalog for SystemCatalog {
+    fn table(&self, name: &str) -> Option<TableSchema> {
+        let table = self
+            .tables
+            .values()
+            .find(|table| table.name.as_str().eq_ignore_ascii_case(name))?;
+        Some(TableSchema {
+            id: TableId(table.id.0),
+            name: table.name.clone(),
+            columns: table
+                .columns
+                .iter()
+                .map(|column| ColumnSchema {
+                    name: column.name.clone(),
+                    data_type: column.data_type().clone(),
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply(catalog: &mut SystemCatalog, source: &str) -> Result<(), CatalogError> {
+        let parsed = parser::parse_stmts(source).expect("source should parse");
+        parsed.stmts.body.iter().try_for_each(|stmt| catalog.apply(stmt))
+    }
+
+    fn name(name: &str) -> Name {
+        Name::new(name.to_string())
+    }
+
+    fn catalog(source: &str) -> SystemCatalog {
+        let mut catalog = SystemCatalog::new();
+        apply(&mut catalog, source).expect("schema should apply");
+        catalog
+    }
+
+    fn error_kind(catalog: &mut SystemCatalog, source: &str) -> CatalogErrorKind {
+        apply(catalog, source).expect_err("change should fail").kind
+    }
+
+    #[test]
+    fn create_from_column_definitions() {
+        let catalog = catalog(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL DEFAULT 'x' COLLATE nocase, \
+             parent INTEGER REFERENCES users (id) ON DELETE CASCADE, CHECK (id > 0), UNIQUE (name)); \
+             CREATE UNIQUE INDEX users_name ON users (name);",
+        );
+        let users = catalog.table(&name("USERS")).expect("table should exist");
+        assert_eq!(users.id, ObjectId(0));
+        assert_eq!(users.version, 1);
+        assert_eq!(users.primary_key.as_ref().map(|key| key.columns.clone()), Some(vec![name("id")]));
+        let column = users.column(&name("name")).expect("column should exist");
+        assert!(column.not_null);
+        assert_eq!(column.collation, Some(name("nocase")));
+        assert_eq!(users.foreign_keys[0].table, name("users"));
+        assert_eq!(
+            users.to_string(),
+            "CREATE TABLE users (id INTEGER, name VARCHAR(20) NOT NULL DEFAULT 'x' COLLATE nocase, \
+             parent INTEGER, PRIMARY KEY (id), UNIQUE (name), CHECK (id > 0), \
+             FOREIGN KEY (parent) REFERENCES users (id) ON DELETE CASCADE)"
+        );
+        let index = catalog.index(&name("users_name")).expect("index should exist");
+        assert_eq!((index.id, index.version, index.table), (ObjectId(1), 2, users.id));
+        assert_eq!(catalog.version(), 2);
+    }
+
+    #[test]
+    fn create_and_drop_exist_checks() {
+        let mut catalog = catalog("CREATE TABLE t (a INTEGER); CREATE INDEX i ON t (a);");
+        assert_eq!(
+            error_kind(&mut catalog, "CREATE TABLE i (b INTEGER);"),
+            CatalogErrorKind::IndexExists { name: name("i") }
+        );
+        apply(&mut catalog, "CREATE TABLE IF NOT EXISTS t (b INTEGER); DROP TABLE IF EXISTS nope;").unwrap();
+        assert_eq!(catalog.version(), 2);
+        assert!(catalog.table(&name("t")).unwrap().column(&name("a")).is_some());
+        assert_eq!(
+            error_kind(&mut catalog, "DROP INDEX nope;"),
+            CatalogErrorKind::UnknownIndex { name: name("nope") }
+        );
+        // Dropping a table drops its indexes and never reuses its id.
+        apply(&mut catalog, "DROP TABLE t; CREATE TABLE t (a INTEGER);").unwrap();
+        assert!(catalog.index(&name("i")).is_none());
+        assert_eq!(catalog.table(&name("t")).unwrap().id, ObjectId(2));
+        assert_eq!(catalog.version(), 4);
+    }
+
+    #[test]
+    fn invalid_definitions() {
+        let mut catalog = SystemCatalog::new();
+        assert_eq!(
+            error_kind(&mut catalog, "CREATE TABLE t (a INTEGER, A VARCHAR);"),
+            CatalogErrorKind::DuplicateColumn { name: name("A") }
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "CREATE TABLE t (a INTEGER PRIMARY KEY, PRIMARY KEY (a));"),
+            CatalogErrorKind::MultiplePrimaryKeys
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "CREATE TABLE t (a INTEGER, UNIQUE (b));"),
+            CatalogErrorKind::UnknownColumn {
+                name: name("b"),
+                table: name("t"),
+            }
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "CREATE TABLE t (a INTEGER, UNIQUE (a + 1));"),
+            CatalogErrorKind::ExpressionKey
+        );
+        assert_eq!(catalog, SystemCatalog::new());
+
+        // Every column read by an index must be in its table.
+        apply(&mut catalog, "CREATE TABLE t (a INTEGER);").unwrap();
+        for source in [
+            "CREATE INDEX i ON t (lower(nope));",
+            "CREATE INDEX i ON t (a) WHERE nope > 0;",
+        ] {
+            assert_eq!(
+                error_kind(&mut catalog, source),
+                CatalogErrorKind::UnknownColumn {
+                    name: name("nope"),
+                    table: name("t"),
+                },
+                "{source}"
+            );
+        }
+        assert!(catalog.index(&name("i")).is_none());
+    }
+
+    #[test]
+    fn alter_table() {
+        // Columns read by expressions or referenced from another table.
+        let mut in_use = catalog(
+            "CREATE TABLE t (a INTEGER, b INTEGER CHECK (b > 0), c INTEGER, d INTEGER, e INTEGER); \
+             CREATE INDEX t_c ON t (a + c); CREATE INDEX t_d ON t (a) WHERE d IS NOT NULL; \
+             CREATE TABLE r (t_e INTEGER REFERENCES t (e));",
+        );
+        for column in ["b", "c", "d", "e"] {
+            assert_eq!(
+                error_kind(&mut in_use, &format!("ALTER TABLE t DROP COLUMN {column};")),
+                CatalogErrorKind::ColumnInUse { name: name(column) }
+            );
+        }
+
+        let mut catalog = catalog(
+            "CREATE TABLE a (id INTEGER PRIMARY KEY, x INTEGER CHECK (x > 0)); \
+             CREATE TABLE b (a_id INTEGER REFERENCES a (id), y INTEGER); CREATE INDEX a_x ON a (x);",
+        );
+        apply(
+            &mut catalog,
+            "ALTER TABLE a RENAME COLUMN id TO pk; ALTER TABLE a RENAME TO parent; \
+             ALTER TABLE b ADD COLUMN z VARCHAR DEFAULT 'z'; ALTER TABLE b DROP COLUMN y;",
+        )
+        .unwrap();
+        let parent = catalog.table(&name("parent")).unwrap();
+        assert_eq!(parent.primary_key.as_ref().unwrap().columns, vec![name("pk")]);
+        let b = catalog.table(&name("b")).unwrap();
+        assert_eq!((b.foreign_keys[0].table.as_str(), b.version), ("parent", 7));
+        assert_eq!(b.foreign_keys[0].referenced_columns, vec![name("pk")]);
+        assert_eq!(
+            b.columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>(),
+            vec!["a_id", "z"]
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "ALTER TABLE parent DROP COLUMN x;"),
+            CatalogErrorKind::ColumnInUse { name: name("x") }
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "ALTER TABLE b ADD COLUMN w INTEGER UNIQUE;"),
+            CatalogErrorKind::KeyColumnAdded
+        );
+        assert_eq!(
+            error_kind(&mut catalog, "ALTER TABLE b RENAME TO a_x;"),
+            CatalogErrorKind::IndexExists { name: name("a_x") }
+        );
+    }
+
+    #[test]
+    fn binds_against_the_catalog() {
+        let catalog = catalog("CREATE TABLE t (a INTEGER, b VARCHAR);");
+        let parsed = parser::parse_stmts("SELECT b FROM t WHERE a = 1; SELECT c FROM t;").unwrap();
+        let bound = binder::bind(&parsed.stmts, &catalog);
+        assert_eq!(
+            bound.errors().iter().map(|error| error.kind.clone()).collect::<Vec<_>>(),
+            vec![binder::BindErrorKind::UnknownColumn {
+                name: name("c"),
+                table: None,
+            }]
+        );
+    }
+}
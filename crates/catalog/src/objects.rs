@@ -0,0 +1,269 @@
+//! The schema objects of a [`crate::SystemCatalog`].
+//!
+//! Objects are built from the AST of the statement that created them.
+//! Column constraints are kept on the column when they only concern its
+//! values and moved to the table otherwise, so a `PRIMARY KEY` on a column
+//! and a `PRIMARY KEY (...)` table constraint end up the same. Each object
+//! renders back to a `CREATE` statement, which is how it is stored.
+
+use std::fmt::{Display, Formatter, Result};
+
+use ast::name::Name;
+use ast::visitor::{walk_expr, Visitor};
+use source_index::location::Location;
+use source_index::span::Span;
+
+/// Identifies a table or index. Identifiers are never reused, not even
+/// after the object is dropped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(pub u32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub id: ObjectId,
+    /// The schema version of the last change to the table.
+    pub version: u64,
+    pub name: Name,
+    pub columns: Vec<Column>,
+    pub primary_key: Option<Key>,
+    pub unique: Vec<Key>,
+    pub checks: Vec<Check>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl Table {
+    /// The column called `name`, ignoring ASCII case.
+    pub fn column(&self, name: &Name) -> Option<&Column> {
+        self.columns.iter().find(|column| same_name(&column.name, name))
+    }
+
+    pub fn column_mut(&mut self, name: &Name) -> Option<&mut Column> {
+        self.columns.iter_mut().find(|column| same_name(&column.name, name))
+    }
+
+    /// A key, foreign key or CHECK constraint of the table uses `column`.
+    pub fn is_constrained(&self, column: &Name) -> bool {
+        self.primary_key.iter().chain(&self.unique).any(|key| key.contains(column))
+            || self
+                .foreign_keys
+                .iter()
+                .any(|foreign_key| foreign_key.columns.iter().any(|name| same_name(name, column)))
+            || self.checks.iter().any(|check| reads_column(&check.expr, column))
+    }
+}
+
+/// The `CREATE TABLE` statement defining the table.
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "CREATE TABLE {} (", identifier(&self.name))?;
+        let mut separator = "";
+        for column in &self.columns {
+            write!(f, "{separator}{column}")?;
+            separator = ", ";
+        }
+        if let Some(key) = &self.primary_key {
+            write!(f, ", {}PRIMARY KEY {key}", constraint_name(&key.name))?;
+        }
+        for key in &self.unique {
+            write!(f, ", {}UNIQUE {key}", constraint_name(&key.name))?;
+        }
+        for check in &self.checks {
+            write!(f, ", {}CHECK ({})", constraint_name(&check.name), check.expr)?;
+        }
+        for foreign_key in &self.foreign_keys {
+            write!(f, ", {foreign_key}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: Name,
+    pub type_name: ast::TypeName,
+    pub not_null: bool,
+    pub default: Option<ast::ColumnConstraintDefault>,
+    pub collation: Option<Name>,
+}
+
+impl Column {
+    pub fn data_type(&self) -> &ast::ExternalType {
+        &self.type_name.external_type
+    }
+}
+
+/// The column definition, as in `CREATE TABLE`.
+impl Display for Column {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} {}", identifier(&self.name), self.type_name)?;
+        if self.not_null {
+            f.write_str(" NOT NULL")?;
+        }
+        if let Some(default) = &self.default {
+            write!(f, " DEFAULT {default}")?;
+        }
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {}", identifier(collation))?;
+        }
+        Ok(())
+    }
+}
+
+/// The columns of a primary key or unique constraint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Key {
+    pub name: Option<Name>,
+    pub columns: Vec<Name>,
+    pub conflict_action: Option<ast::ConflictAction>,
+}
+
+impl Key {
+    pub fn contains(&self, column: &Name) -> bool {
+        self.columns.iter().any(|name| same_name(name, column))
+    }
+}
+
+/// The column list and conflict clause of the key.
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({})", identifiers(&self.columns))?;
+        if let Some(action) = &self.conflict_action {
+            write!(f, " ON CONFLICT {action}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub name: Option<Name>,
+    pub expr: ast::Expr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForeignKey {
+    pub name: Option<Name>,
+    /// The referencing columns of the table.
+    pub columns: Vec<Name>,
+    /// The referenced table, which need not exist yet.
+    pub table: Name,
+    /// The referenced columns, the primary key of `table` if empty.
+    pub referenced_columns: Vec<Name>,
+    pub on: Option<ast::ForeignKeyClauseOn>,
+}
+
+impl ForeignKey {
+    /// The foreign key of `columns` described by `clause`.
+    pub fn new(name: Option<Name>, columns: Vec<Name>, clause: &ast::ForeignKeyClause) -> Self {
+        Self {
+            name,
+            columns,
+            table: clause.id.id.clone(),
+            referenced_columns: clause.column_names.iter().map(|column| column.id.clone()).collect(),
+            on: clause.foreign_key_clause_on.clone(),
+        }
+    }
+
+    /// The key references `column` of `table`.
+    pub fn references(&self, table: &Name, column: &Name) -> bool {
+        same_name(&self.table, table) && self.referenced_columns.iter().any(|name| same_name(name, column))
+    }
+}
+
+/// The table constraint, as in `CREATE TABLE`.
+impl Display for ForeignKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{}FOREIGN KEY ({}) REFERENCES {}",
+            constraint_name(&self.name),
+            identifiers(&self.columns),
+            identifier(&self.table)
+        )?;
+        if !self.referenced_columns.is_empty() {
+            write!(f, " ({})", identifiers(&self.referenced_columns))?;
+        }
+        if let Some(on) = &self.on {
+            write!(f, " {on}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    pub id: ObjectId,
+    /// The schema version of the last change to the index.
+    pub version: u64,
+    pub name: Name,
+    pub table: ObjectId,
+    pub unique: bool,
+    pub columns: Vec<ast::IndexedColumn>,
+    pub where_clause: Option<ast::Expr>,
+}
+
+impl Index {
+    /// An indexed expression or the WHERE clause of the index reads
+    /// `column`.
+    pub fn reads(&self, column: &Name) -> bool {
+        self.columns.iter().any(|indexed| reads_column(&indexed.expr, column))
+            || self.where_clause.iter().any(|expr| reads_column(expr, column))
+    }
+
+    /// The `CREATE INDEX` statement defining the index on `table`.
+    pub fn to_sql(&self, table: &Name) -> String {
+        let unique = if self.unique { "UNIQUE " } else { "" };
+        let columns: Vec<String> = self.columns.iter().map(ToString::to_string).collect();
+        let mut sql = format!(
+            "CREATE {unique}INDEX {} ON {} ({})",
+            identifier(&self.name),
+            identifier(table),
+            columns.join(", ")
+        );
+        if let Some(expr) = &self.where_clause {
+            sql.push_str(&format!(" WHERE {expr}"));
+        }
+        sql
+    }
+}
+
+/// `expr` reads a column called `column`, of any table.
+fn reads_column(expr: &ast::Expr, column: &Name) -> bool {
+    let mut reads = ReadsColumn { column, found: false };
+    reads.visit_expr(expr);
+    reads.found
+}
+
+struct ReadsColumn<'a> {
+    column: &'a Name,
+    found: bool,
+}
+
+impl Visitor for ReadsColumn<'_> {
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        if let ast::Expr::Column(expr) = expr {
+            self.found |= same_name(&expr.column.id, self.column);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+pub(crate) fn same_name(a: &Name, b: &Name) -> bool {
+    a.as_str().eq_ignore_ascii_case(b.as_str())
+}
+
+// Quote `name` where the parser needs it, as the AST does for identifiers.
+fn identifier(name: &Name) -> String {
+    ast::Identifier::new(name.clone(), Span::empty(Location::new(0))).to_string()
+}
+
+fn identifiers(names: &[Name]) -> String {
+    names.iter().map(identifier).collect::<Vec<_>>().join(", ")
+}
+
+fn constraint_name(name: &Option<Name>) -> String {
+    match name {
+        Some(name) => format!("CONSTRAINT {} ", identifier(name)),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,304 @@
+//! The catalog on disk.
+//!
+//! A catalog is stored as the `CREATE` statement of every object, tables
+//! before indexes, next to the id and version of the object. Loading parses
+//! the statements back, so the file stays readable as long as the parser
+//! accepts what the AST renders. All integers are little endian:
+//!
+//! ```text
+//! magic    b"MDBCATLG"
+//! format   u32         FORMAT
+//! version  u64         schema version
+//! next_id  u32         next object id
+//! count    u32         number of objects, each
+//!   kind     u8        TABLE or INDEX
+//!   id       u32
+//!   version  u64
+//!   len      u32
+//!   sql      [u8; len] UTF-8 `CREATE` statement
+//! ```
+
+use std::fmt::Display;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::errors::CatalogError;
+use crate::objects::{Index, ObjectId, Table};
+use crate::SystemCatalog;
+
+const MAGIC: &[u8; 8] = b"MDBCATLG";
+const FORMAT: u32 = 1;
+const TABLE: u8 = 0;
+const INDEX: u8 = 1;
+
+/// A catalog file that cannot be read or written.
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    /// The file is not a catalog or is damaged.
+    Corrupt(String),
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(error) => write!(f, "Cannot access the catalog file: {error}."),
+            StorageError::Corrupt(reason) => write!(f, "The catalog file is corrupt: {reason}."),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<std::io::Error> for StorageError {
+    fn from(error: std::io::Error) -> Self {
+        StorageError::Io(error)
+    }
+}
+
+/// The error of a change to a [`CatalogFile`].
+#[derive(Debug)]
+pub enum Error {
+    Catalog(CatalogError),
+    Storage(StorageError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Catalog(error) => write!(f, "{}", error.kind),
+            Error::Storage(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<CatalogError> for Error {
+    fn from(error: CatalogError) -> Self {
+        Error::Catalog(error)
+    }
+}
+
+impl From<StorageError> for Error {
+    fn from(error: StorageError) -> Self {
+        Error::Storage(error)
+    }
+}
+
+impl SystemCatalog {
+    /// The catalog in the file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT.to_le_bytes());
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&self.next_id.to_le_bytes());
+        let count = (self.tables.len() + self.indexes.len()) as u32;
+        bytes.extend_from_slice(&count.to_le_bytes());
+        let tables = self.tables.values().map(|table| (TABLE, table.id, table.version, table.to_string()));
+        let indexes = self.indexes.values().map(|index| {
+            let table = &self.tables[&index.table].name;
+            (INDEX, index.id, index.version, index.to_sql(table))
+        });
+        for (kind, id, version, sql) in tables.chain(indexes) {
+            bytes.push(kind);
+            bytes.extend_from_slice(&id.0.to_le_bytes());
+            bytes.extend_from_slice(&version.to_le_bytes());
+            bytes.extend_from_slice(&(sql.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(sql.as_bytes());
+        }
+        bytes
+    }
+
+    /// Read back a catalog written by [`SystemCatalog::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(StorageError::Corrupt("not a catalog file".to_string()));
+        }
+        let format = reader.u32()?;
+        if format != FORMAT {
+            return Err(StorageError::Corrupt(format!("unknown format {format}")));
+        }
+        let mut catalog = SystemCatalog {
+            version: reader.u64()?,
+            next_id: reader.u32()?,
+            ..SystemCatalog::default()
+        };
+        for _ in 0..reader.u32()? {
+            let kind = reader.u8()?;
+            let id = ObjectId(reader.u32()?);
+            let version = reader.u64()?;
+            let len = reader.u32()? as usize;
+            let sql = std::str::from_utf8(reader.take(len)?)
+                .map_err(|_| StorageError::Corrupt(format!("object {} is not UTF-8", id.0)))?;
+            catalog.load_object(kind, id, version, sql)?;
+        }
+        if !reader.bytes.is_empty() {
+            return Err(StorageError::Corrupt("trailing bytes".to_string()));
+        }
+        Ok(catalog)
+    }
+
+    fn load_object(&mut self, kind: u8, id: ObjectId, version: u64, sql: &str) -> Result<(), StorageError> {
+        let corrupt = || StorageError::Corrupt(format!("object {} has an invalid definition", id.0));
+        let parsed = parser::parse_stmts(&format!("{sql};")).map_err(|_| corrupt())?;
+        let [ast::Stmt::Create(create)] = parsed.stmts.body.as_slice() else {
+            return Err(corrupt());
+        };
+        match (kind, &create.kind) {
+            (TABLE, ast::CreateKind::Table(table)) => {
+                let table = self.build_table(&create.id.id, table).map_err(|_| corrupt())?;
+                self.insert_table(Table { id, version, ..table });
+            }
+            (INDEX, ast::CreateKind::Index(index)) => {
+                let index = self.build_index(&create.id.id, index).map_err(|_| corrupt())?;
+                self.insert_index(Index { id, version, ..index });
+            }
+            _ => return Err(corrupt()),
+        }
+        Ok(())
+    }
+
+    /// Read the catalog stored in the file at `path`.
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Write the catalog to the file at `path`. The file is replaced in one
+    /// step, so it holds either the old or the new catalog after a crash.
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(&self.to_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StorageError> {
+        if self.bytes.len() < len {
+            return Err(StorageError::Corrupt("unexpected end of file".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, StorageError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, StorageError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("4 bytes were taken")))
+    }
+
+    fn u64(&mut self) -> Result<u64, StorageError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("8 bytes were taken")))
+    }
+}
+
+/// A catalog kept in a database file. Every schema change is written to the
+/// file before it is visible.
+#[derive(Debug)]
+pub struct CatalogFile {
+    path: PathBuf,
+    catalog: SystemCatalog,
+}
+
+impl CatalogFile {
+    /// Open the catalog stored at `path`, or an empty one if the file does
+    /// not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let path = path.into();
+        let catalog = match fs::read(&path) {
+            Ok(bytes) => SystemCatalog::from_bytes(&bytes)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => SystemCatalog::new(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { path, catalog })
+    }
+
+    pub fn catalog(&self) -> &SystemCatalog {
+        &self.catalog
+    }
+
+    /// Apply the schema change of `stmt` and write it to the file. Nothing
+    /// changes if the change is invalid or cannot be written.
+    pub fn apply(&mut self, stmt: &ast::Stmt) -> Result<(), Error> {
+        let mut changed = self.catalog.clone();
+        changed.apply(stmt)?;
+        if changed.version != self.catalog.version {
+            changed.save(&self.path)?;
+            self.catalog = changed;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply_all(file: &mut CatalogFile, source: &str) {
+        let parsed = parser::parse_stmts(source).expect("source should parse");
+        for stmt in &parsed.stmts.body {
+            file.apply(stmt).expect("change should apply");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("marteldb-catalog-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut file = CatalogFile::open(&path).unwrap();
+        apply_all(
+            &mut file,
+            "CREATE TABLE \"order\" (id SERIAL PRIMARY KEY, note VARCHAR(10) DEFAULT 'it''s', \
+             total NUMERIC(8, 2) DEFAULT -1 CHECK (total >= 0)); \
+             CREATE TABLE item (order_id INTEGER, CONSTRAINT fk FOREIGN KEY (order_id) REFERENCES \"order\"); \
+             CREATE UNIQUE INDEX item_order ON item (order_id DESC) WHERE order_id > 0; \
+             DROP TABLE IF EXISTS missing; ALTER TABLE item ADD COLUMN qty INTEGER NOT NULL DEFAULT 1;",
+        );
+        let reopened = CatalogFile::open(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(reopened.catalog().version(), 4);
+        assert_eq!(
+            reopened.catalog().tables().map(ToString::to_string).collect::<Vec<_>>(),
+            file.catalog().tables().map(ToString::to_string).collect::<Vec<_>>()
+        );
+        let ids = |file: &CatalogFile| {
+            let tables = file.catalog().tables().map(|table| (table.id, table.version));
+            let indexes = file.catalog().indexes().map(|index| (index.id, index.version));
+            tables.chain(indexes).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&reopened), vec![(ObjectId(0), 1), (ObjectId(1), 4), (ObjectId(2), 3)]);
+        assert_eq!(ids(&reopened), ids(&file));
+    }
+
+    #[test]
+    fn corrupt_files() {
+        let bytes = SystemCatalog::new().to_bytes();
+        assert!(SystemCatalog::from_bytes(&bytes).is_ok());
+        let errors = [
+            SystemCatalog::from_bytes(b"SQLITE"),
+            SystemCatalog::from_bytes(&bytes[..bytes.len() - 1]),
+            SystemCatalog::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+        ];
+        for error in errors {
+            assert!(matches!(error, Err(StorageError::Corrupt(_))), "{error:?}");
+        }
+    }
+}
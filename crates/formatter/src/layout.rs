@@ -0,0 +1,404 @@
+use std::fmt::Display;
+
+use ast::{self, Stmt};
+use source_index::location::Location;
+use source_index::span::Spanned;
+
+use crate::{CommaStyle, FormatOptions};
+
+/// A single output line. `start` is the source offset of the node the line
+/// was made from and is used to place comments.
+#[derive(Debug)]
+pub(crate) struct Line {
+    pub(crate) indent: usize,
+    pub(crate) text: String,
+    pub(crate) start: Location,
+}
+
+/// Breaks statements into lines.
+///
+/// Every line is made of the canonical text of whole nodes, in the order
+/// [`Display`] prints them, so a layout only ever changes the whitespace
+/// between tokens of the single line form.
+pub(crate) struct Layout<'a> {
+    options: &'a FormatOptions,
+    /// Break every clause and list, even when it would fit on one line.
+    force_break: bool,
+    lines: Vec<Line>,
+}
+
+impl<'a> Layout<'a> {
+    pub(crate) fn new(options: &'a FormatOptions) -> Self {
+        Self {
+            options,
+            force_break: false,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Lay out a statement. Statements that fit within the line width are
+    /// kept on one line unless `force_break` is set.
+    pub(crate) fn stmt(mut self, stmt: &Stmt, force_break: bool) -> Vec<Line> {
+        self.force_break = force_break;
+        let text = stmt.to_string();
+        if self.fits(0, &text) {
+            self.push(0, text, stmt.start());
+            return self.lines;
+        }
+        match stmt {
+            Stmt::Create(create) => self.create(create),
+            Stmt::Delete(delete) => self.delete(delete),
+            Stmt::Insert(insert) => self.insert(insert),
+            Stmt::Select(select) => self.select(0, select),
+            Stmt::Update(update) => self.update(update),
+            _ => self.push(0, text, stmt.start()),
+        }
+        self.lines
+    }
+
+    fn fits(&self, indent: usize, text: &str) -> bool {
+        !self.force_break && indent * self.options.indent_width + text.len() <= self.options.line_width
+    }
+
+    fn push(&mut self, indent: usize, text: impl Into<String>, start: Location) {
+        self.lines.push(Line {
+            indent,
+            text: text.into(),
+            start,
+        });
+    }
+
+    fn append(&mut self, text: &str) {
+        if let Some(line) = self.lines.last_mut() {
+            line.text.push_str(text);
+        }
+    }
+
+    // `<header> <item>, <item>, ...` on one line, or the header followed by
+    // one item per line.
+    fn list<T: Display>(
+        &mut self,
+        indent: usize,
+        header: &str,
+        start: Location,
+        items: impl IntoIterator<Item = (T, Location)>,
+    ) {
+        let items: Vec<(String, Location)> = items
+            .into_iter()
+            .map(|(item, start)| (item.to_string(), start))
+            .collect();
+        let joined = items.iter().map(|(item, _)| item.as_str()).collect::<Vec<_>>().join(", ");
+        let inline = format!("{header} {joined}");
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, start);
+            return;
+        }
+        self.push(indent, header, start);
+        self.items(indent + 1, items);
+    }
+
+    // One item per line, separated by commas in the configured style.
+    fn items(&mut self, indent: usize, items: Vec<(String, Location)>) {
+        let last = items.len().saturating_sub(1);
+        for (i, (item, start)) in items.into_iter().enumerate() {
+            let text = match self.options.comma_style {
+                CommaStyle::Trailing if i < last => format!("{item},"),
+                CommaStyle::Leading if i > 0 => format!(", {item}"),
+                _ => item,
+            };
+            self.push(indent, text, start);
+        }
+    }
+
+    // `<keyword> <expr>` on one line, or broken before every AND and OR of
+    // the expression's top level chain.
+    fn condition(&mut self, indent: usize, keyword: &str, expr: &ast::Expr) {
+        let inline = format!("{keyword} {expr}");
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, expr.start());
+            return;
+        }
+        let mut rest = Vec::new();
+        let mut first = expr;
+        while let ast::Expr::BoolOp(bool_op) = first {
+            rest.push((&bool_op.op, bool_op.right.as_ref()));
+            first = &bool_op.left;
+        }
+        self.term(indent, &format!("{keyword} "), first);
+        for (op, right) in rest.into_iter().rev() {
+            self.term(indent + 1, &format!("{op} "), right);
+        }
+    }
+
+    // `<prefix><expr>` on one line, or an expression ending in a subquery
+    // that does not fit broken around its select.
+    fn term(&mut self, indent: usize, prefix: &str, expr: &ast::Expr) {
+        let inline = format!("{prefix}{expr}");
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, expr.start());
+            return;
+        }
+        let (head, select) = match expr {
+            ast::Expr::Subquery(subquery) => (String::new(), &subquery.select),
+            ast::Expr::Exists(exists) => {
+                let not = if exists.negated { "NOT " } else { "" };
+                (format!("{not}EXISTS "), &exists.select)
+            }
+            ast::Expr::In(ast::ExprIn {
+                expr: left,
+                negated,
+                kind: ast::ExprInKind::Select(select),
+                ..
+            }) => {
+                let not = if *negated { "NOT " } else { "" };
+                (format!("{left} {not}IN "), select)
+            }
+            _ => {
+                self.push(indent, inline, expr.start());
+                return;
+            }
+        };
+        self.push(indent, format!("{prefix}{head}("), expr.start());
+        self.select(indent + 1, select);
+        self.push(indent, ")", select.span.end());
+    }
+
+    fn create(&mut self, create: &ast::StmtCreate) {
+        let ast::CreateKind::Table(table) = &create.kind else {
+            self.push(0, create.to_string(), create.span.start());
+            return;
+        };
+        let exist_check = if create.exist_check { "IF NOT EXISTS " } else { "" };
+        let header = format!("CREATE TABLE {exist_check}{}", create.id);
+        match &table.source {
+            ast::CreateTableSource::Columns(columns) => {
+                self.push(0, format!("{header} ("), create.span.start());
+                let defs = columns.columns.iter().map(|column| (column.to_string(), column.span.start()));
+                let constraints = columns
+                    .constraints
+                    .iter()
+                    .map(|constraint| (constraint.to_string(), constraint.span.start()));
+                self.items(1, defs.chain(constraints).collect());
+                self.push(0, ")", columns.span.end());
+            }
+            ast::CreateTableSource::Select(select) => {
+                self.push(0, format!("{header} AS"), create.span.start());
+                self.select(0, select);
+            }
+        }
+    }
+
+    fn delete(&mut self, delete: &ast::StmtDelete) {
+        if let Some(with) = &delete.with {
+            self.with(0, with);
+        }
+        self.push(0, format!("DELETE FROM {}", delete.table), delete.table.span.start());
+        if let Some(expr) = &delete.where_clause {
+            self.condition(0, "WHERE", expr);
+        }
+        if let Some(returning) = &delete.returning {
+            self.returning(0, returning);
+        }
+    }
+
+    fn insert(&mut self, insert: &ast::StmtInsert) {
+        if let Some(with) = &insert.with {
+            self.with(0, with);
+        }
+        let mut header = format!("INSERT INTO {}", insert.table);
+        if !insert.columns.is_empty() {
+            let columns: Vec<String> = insert.columns.iter().map(ToString::to_string).collect();
+            header = format!("{header} ({})", columns.join(", "));
+        }
+        self.push(0, header, insert.table.span.start());
+        match &insert.action.kind {
+            ast::InsertActionKind::Values(values) => {
+                let rows = values.rows.iter().map(|row| {
+                    let start = row.first().map_or(values.span.start(), |expr| expr.start());
+                    let row: Vec<String> = row.iter().map(ToString::to_string).collect();
+                    (format!("({})", row.join(", ")), start)
+                });
+                self.list(0, "VALUES", values.span.start(), rows);
+                if let Some(upsert) = &values.upsert {
+                    self.push(0, upsert.to_string(), upsert.span.start());
+                }
+            }
+            ast::InsertActionKind::Select(select) => {
+                self.select(0, &select.select);
+                if let Some(upsert) = &select.upsert {
+                    self.push(0, upsert.to_string(), upsert.span.start());
+                }
+            }
+            ast::InsertActionKind::DefaultValues => {
+                self.push(0, "DEFAULT VALUES", insert.action.span.start());
+            }
+        }
+        if let Some(returning) = &insert.returning {
+            self.returning(0, returning);
+        }
+    }
+
+    fn update(&mut self, update: &ast::StmtUpdate) {
+        if let Some(with) = &update.with {
+            self.with(0, with);
+        }
+        let mut header = String::from("UPDATE ");
+        if let Some(action) = &update.conflict_action {
+            header.push_str(&format!("OR {action} "));
+        }
+        header.push_str(&update.table.to_string());
+        self.push(0, header, update.table.span.start());
+        let assignments = update
+            .assignments
+            .iter()
+            .map(|assignment| (assignment, assignment.span.start()));
+        self.list(0, "SET", update.table.span.end(), assignments);
+        if let Some(from) = &update.from {
+            self.from(0, from);
+        }
+        if let Some(expr) = &update.where_clause {
+            self.condition(0, "WHERE", expr);
+        }
+        if let Some(returning) = &update.returning {
+            self.returning(0, returning);
+        }
+    }
+
+    fn returning(&mut self, indent: usize, returning: &ast::ReturningClause) {
+        let columns = returning.columns.iter().map(|column| (column, column.span.start()));
+        self.list(indent, "RETURNING", returning.span.start(), columns);
+    }
+
+    fn with(&mut self, indent: usize, with: &ast::WithClause) {
+        let inline = with.to_string();
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, with.span.start());
+            return;
+        }
+        let last = with.ctes.len() - 1;
+        for (i, cte) in with.ctes.iter().enumerate() {
+            let mut header = String::new();
+            if i == 0 {
+                header.push_str(if with.recursive { "WITH RECURSIVE " } else { "WITH " });
+            } else if self.options.comma_style == CommaStyle::Leading {
+                header.push_str(", ");
+            }
+            header.push_str(&cte.id.to_string());
+            if !cte.columns.is_empty() {
+                let columns: Vec<String> = cte.columns.iter().map(ToString::to_string).collect();
+                header.push_str(&format!(" ({})", columns.join(", ")));
+            }
+            header.push_str(" AS ");
+            if let Some(materialized) = &cte.materialized {
+                header.push_str(&format!("{materialized} "));
+            }
+            self.push(indent, format!("{header}("), cte.span.start());
+            self.select(indent + 1, &cte.select);
+            let close = if i < last && self.options.comma_style == CommaStyle::Trailing {
+                "),"
+            } else {
+                ")"
+            };
+            self.push(indent, close, cte.select.span.end());
+        }
+    }
+
+    fn select(&mut self, indent: usize, select: &ast::StmtSelect) {
+        if let Some(with) = &select.with {
+            self.with(indent, with);
+        }
+        let header = if select.distinct { "SELECT DISTINCT" } else { "SELECT" };
+        let columns = select.columns.iter().map(|column| (column, column.span.start()));
+        self.list(indent, header, select.span.start(), columns);
+        if let Some(from) = &select.from {
+            self.from(indent, from);
+        }
+        if let Some(expr) = &select.where_clause {
+            self.condition(indent, "WHERE", expr);
+        }
+        if !select.group_by.is_empty() {
+            let exprs = select.group_by.iter().map(|expr| (expr, expr.start()));
+            self.list(indent, "GROUP BY", select.group_by[0].start(), exprs);
+        }
+        if let Some(expr) = &select.having {
+            self.condition(indent, "HAVING", expr);
+        }
+        if !select.window.is_empty() {
+            let windows = select.window.iter().map(|window| (window, window.span.start()));
+            self.list(indent, "WINDOW", select.window[0].span.start(), windows);
+        }
+        if let Some(compound) = &select.compound {
+            self.push(indent, compound.op.to_string(), compound.span.start());
+            self.select(indent, &compound.select);
+        }
+        if !select.order_by.is_empty() {
+            let terms = select.order_by.iter().map(|term| (term, term.span.start()));
+            self.list(indent, "ORDER BY", select.order_by[0].span.start(), terms);
+        }
+        if let Some(limit) = &select.limit {
+            self.push(indent, limit.to_string(), limit.span.start());
+        }
+    }
+
+    // `FROM <item>` on one line, or one line per joined table.
+    fn from(&mut self, indent: usize, from: &ast::FromClause) {
+        let inline = from.to_string();
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, from.span.start());
+            return;
+        }
+        let mut joins = Vec::new();
+        let mut first = &from.item;
+        while let ast::FromItemKind::Join(join) = &first.kind {
+            joins.push(join);
+            first = &join.left;
+        }
+        self.table_item(indent, "FROM ", first, String::new(), first.span.start());
+        for join in joins.into_iter().rev() {
+            let op = if join.op.kind == ast::JoinKind::Comma {
+                self.append(",");
+                String::new()
+            } else {
+                format!("{} ", join.op)
+            };
+            let constraint = join
+                .constraint
+                .as_ref()
+                .map_or(String::new(), |constraint| format!(" {constraint}"));
+            if let ast::FromItemKind::Join(_) = &join.right.kind {
+                let text = format!("{op}({}){constraint}", join.right);
+                self.push(indent + 1, text, join.op.span.start());
+            } else {
+                self.table_item(indent + 1, &op, &join.right, constraint, join.op.span.start());
+            }
+        }
+    }
+
+    // `<prefix><item><suffix>` on one line, or a derived table that does not
+    // fit broken around its select.
+    fn table_item(
+        &mut self,
+        indent: usize,
+        prefix: &str,
+        item: &ast::FromItem,
+        suffix: String,
+        start: Location,
+    ) {
+        let inline = format!("{prefix}{item}{suffix}");
+        let ast::FromItemKind::Subquery(subquery) = &item.kind else {
+            self.push(indent, inline, start);
+            return;
+        };
+        if self.fits(indent, &inline) {
+            self.push(indent, inline, start);
+            return;
+        }
+        self.push(indent, format!("{prefix}("), start);
+        self.select(indent + 1, &subquery.select);
+        let alias = subquery
+            .alias
+            .as_ref()
+            .map_or(String::new(), |alias| format!(" AS {alias}"));
+        self.push(indent, format!("){alias}{suffix}"), subquery.select.span.end());
+    }
+}
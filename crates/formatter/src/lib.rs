@@ -0,0 +1,350 @@
+//! A formatter for SQL source text.
+//!
+//! The source is parsed with [`parser::parse_stmts`] and every statement is
+//! re-emitted from its canonical text, broken over several lines when it does
+//! not fit within [`FormatOptions::line_width`]. Comments are carried over
+//! from [`parser::Parsed::comments`] and placed next to the code they were
+//! attached to. Input with syntax errors is never formatted, the errors are
+//! returned instead.
+
+use ast::Stmt;
+use parser::parser::Parser;
+use parser::{parse_stmts, Comment, CommentPlacement, ParseError};
+use source_index::location::Location;
+use source_index::span::Spanned;
+
+use crate::layout::{Layout, Line};
+
+mod layout;
+
+/// Options controlling the output of [`format`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    /// Number of spaces per indentation level.
+    pub indent_width: usize,
+    /// Statements and clauses longer than this are broken over several lines.
+    pub line_width: usize,
+    pub comma_style: CommaStyle,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Upper,
+            indent_width: 4,
+            line_width: 80,
+            comma_style: CommaStyle::Trailing,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+}
+
+/// Where the comma goes when a list is broken one item per line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommaStyle {
+    /// `a,` at the end of every line but the last.
+    Trailing,
+    /// `, a` at the start of every line but the first.
+    Leading,
+}
+
+/// Format `source`, returning the syntax errors instead if it does not parse.
+pub fn format(source: &str, options: &FormatOptions) -> Result<String, Vec<ParseError>> {
+    let parsed = parse_stmts(source)?;
+    let mut comments = parsed.comments().iter().peekable();
+    let mut lines: Vec<Line> = Vec::new();
+    let mut prev_end = None;
+
+    for stmt in &parsed.stmts.body {
+        while let Some(comment) = comments.next_if(|comment| comment.span.start() < stmt.start()) {
+            match comment.placement {
+                CommentPlacement::Trailing if !lines.is_empty() => append_comment(source, &mut lines, comment),
+                _ => {
+                    separate(source, &parsed, &mut lines, prev_end, comment.span.start());
+                    prev_end = None;
+                    lines.push(comment_line(source, 0, comment));
+                }
+            }
+        }
+        separate(source, &parsed, &mut lines, prev_end, stmt.start());
+
+        let mut interior = Vec::new();
+        while let Some(comment) = comments.next_if(|comment| comment.span.start() < stmt.end()) {
+            interior.push(comment);
+        }
+        lines.extend(format_stmt(source, options, stmt, &interior));
+        prev_end = Some(stmt.end());
+    }
+
+    for comment in comments {
+        match comment.placement {
+            CommentPlacement::Trailing if !lines.is_empty() => append_comment(source, &mut lines, comment),
+            _ => {
+                separate(source, &parsed, &mut lines, prev_end, comment.span.start());
+                prev_end = None;
+                lines.push(comment_line(source, 0, comment));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for line in &lines {
+        if !line.text.is_empty() {
+            out.push_str(&" ".repeat(line.indent * options.indent_width));
+            out.push_str(&line.text);
+        }
+        out.push('\n');
+    }
+    if options.keyword_case == KeywordCase::Lower {
+        out = lower_keywords(out);
+    }
+    Ok(out)
+}
+
+// Lay out a single statement and place the comments found inside it. A
+// statement holding comments is always broken so they land between clauses,
+// leading comments take the indentation of the line they precede. Comments
+// landing on the last line follow the `;` and are outside the statement when
+// the output is formatted again, so they alone do not break it.
+fn format_stmt(source: &str, options: &FormatOptions, stmt: &Stmt, comments: &[&Comment]) -> Vec<Line> {
+    let mut lines = Layout::new(options).stmt(stmt, !comments.is_empty());
+    let last_start = lines.last().map_or(stmt.start(), |line| line.start);
+    if comments.iter().all(|comment| comment.span.start() >= last_start) {
+        lines = Layout::new(options).stmt(stmt, false);
+    }
+    if let Some(last) = lines.last_mut() {
+        last.text.push(';');
+    }
+    for comment in comments {
+        let start = comment.span.start();
+        let at = lines.iter().rposition(|line| line.start <= start);
+        match (comment.placement, at) {
+            (CommentPlacement::Trailing, Some(at)) => {
+                lines[at].text.push(' ');
+                lines[at].text.push_str(&source[comment.span]);
+            }
+            (_, at) => {
+                let at = at.map_or(0, |at| at + 1);
+                let indent = lines
+                    .get(at)
+                    .or_else(|| at.checked_sub(1).and_then(|i| lines.get(i)))
+                    .map_or(0, |line| line.indent);
+                lines.insert(at, comment_line(source, indent, comment));
+            }
+        }
+    }
+    lines
+}
+
+fn comment_line(source: &str, indent: usize, comment: &Comment) -> Line {
+    Line {
+        indent,
+        text: source[comment.span].to_string(),
+        start: comment.span.start(),
+    }
+}
+
+fn append_comment(source: &str, lines: &mut [Line], comment: &Comment) {
+    if let Some(line) = lines.last_mut() {
+        line.text.push(' ');
+        line.text.push_str(&source[comment.span]);
+    }
+}
+
+// Keep a single blank line where the source had one or more between the
+// previous statement and the code or comment starting at `next`.
+fn separate(source: &str, parsed: &parser::Parsed, lines: &mut Vec<Line>, prev: Option<Location>, next: Location) {
+    let Some(prev) = prev else {
+        return;
+    };
+    let in_comments: usize = parsed
+        .comments()
+        .iter()
+        .filter(|comment| comment.span.start() >= prev && comment.span.end() <= next)
+        .map(|comment| source[comment.span].matches('\n').count())
+        .sum();
+    if source[*prev..*next].matches('\n').count() - in_comments >= 2 {
+        lines.push(Line {
+            indent: 0,
+            text: String::new(),
+            start: next,
+        });
+    }
+}
+
+// Keywords are emitted in upper case, lower them by lexing the output again.
+fn lower_keywords(mut out: String) -> String {
+    let parsed = Parser::new(&out).parse();
+    for token in parsed.tokens().iter().filter(|token| token.kind().is_keyword()) {
+        let range = *token.start()..*token.end();
+        let lower = out[range.clone()].to_ascii_lowercase();
+        out.replace_range(range, &lower);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options() -> FormatOptions {
+        FormatOptions {
+            line_width: 40,
+            ..FormatOptions::default()
+        }
+    }
+
+    #[test]
+    fn short_statements_stay_on_one_line() {
+        let out = format("select a,b from t1 where a=1; begin;", &options()).unwrap();
+        assert_eq!(out, "SELECT a, b FROM t1 WHERE a = 1;\nBEGIN;\n");
+    }
+
+    #[test]
+    fn long_statements_are_broken() {
+        let source = "select c1, c2, count(*) as total from t1 join t2 on t1.c1 = t2.c1 \
+            where c1 > 10 and c2 is not null or c3 = 'x' group by c1, c2 order by total desc limit 5;";
+        let out = format(source, &options()).unwrap();
+        assert_eq!(
+            out,
+            "SELECT c1, c2, count(*) AS total
+FROM t1 JOIN t2 ON t1.c1 = t2.c1
+WHERE c1 > 10
+    AND c2 IS NOT NULL
+    OR c3 = 'x'
+GROUP BY c1, c2
+ORDER BY total DESC
+LIMIT 5;
+"
+        );
+    }
+
+    #[test]
+    fn lower_case_keywords_and_leading_commas() {
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            comma_style: CommaStyle::Leading,
+            indent_width: 2,
+            line_width: 30,
+        };
+        let source = "CREATE TABLE IF NOT EXISTS t1 (c1 INTEGER PRIMARY KEY, c2 VARCHAR(20) NOT NULL, \"Select\" NUMERIC);";
+        let out = format(source, &options).unwrap();
+        assert_eq!(
+            out,
+            "create table if not exists t1 (
+  c1 integer primary key
+  , c2 varchar(20) not null
+  , \"Select\" numeric
+);
+"
+        );
+    }
+
+    #[test]
+    fn comments_are_preserved() {
+        let source = "/* header */
+-- first
+begin; -- start
+
+select c1, /* inline */ c2
+    -- between lines
+    from t1; /* done */ -- really
+commit;
+-- end";
+        let out = format(source, &options()).unwrap();
+        assert_eq!(
+            out,
+            "/* header */
+-- first
+BEGIN; -- start
+
+SELECT
+    c1, /* inline */
+    c2
+-- between lines
+FROM t1; /* done */ -- really
+COMMIT;
+-- end
+"
+        );
+    }
+
+    #[test]
+    fn formatting_comments_is_idempotent() {
+        let sources = [
+            "insert into t values (1, -- one\n 2);",
+            "insert into t values (1), -- one\n (2), /* two */ (3);",
+            "-- leading\nselect a, /* inline */ b -- trailing\nfrom t where a = 1; -- after\n-- end",
+            "select a -- a\nfrom (select b, c from u where b = 1 and c = 2) as s; /* done */",
+        ];
+        for source in sources {
+            let once = format(source, &options()).unwrap();
+            assert_eq!(format(&once, &options()).unwrap(), once, "{source}");
+        }
+
+        let out = format("insert into t values (1, -- one\n 2);", &options()).unwrap();
+        assert_eq!(out, "INSERT INTO t VALUES (1, 2); -- one\n");
+    }
+
+    #[test]
+    fn nested_selects_are_broken() {
+        let options = FormatOptions {
+            line_width: 20,
+            ..FormatOptions::default()
+        };
+        let source = "select a from (select b, c from u where b = 1 and c = 2) as s \
+            where a in (select b from v);";
+        let out = format(source, &options).unwrap();
+        assert_eq!(
+            out,
+            "SELECT a
+FROM (
+    SELECT b, c
+    FROM u
+    WHERE b = 1
+        AND c = 2
+) AS s
+WHERE a IN (
+    SELECT b
+    FROM v
+);
+"
+        );
+        assert!(out.lines().all(|line| line.len() <= options.line_width), "{out}");
+    }
+
+    #[test]
+    fn invalid_source_is_not_formatted() {
+        let errors = format("select from where;", &options()).unwrap_err();
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn formatted_source_parses_to_the_same_statements() {
+        let source = "with recursive cnt(x) as (select 1 union all select x + 1 from cnt where x < 100) \
+            select x from cnt;
+            insert into t1 (c1, c2) values (1, 'one'), (2, 'two'), (3, 'three') \
+            on conflict (c1) do update set c2 = excluded.c2 returning c1;
+            update or replace t1 set c1 = c1 + 1, c2 = 'updated value' where c1 in (select c1 from t2) returning *;
+            delete from t1 where c1 between 1 and 100 and c2 like 'prefix%' returning c1, c2;
+            select * from t1, t2, t3 left join t4 using (c1) where t1.c1 = t2.c1;";
+        for comma_style in [CommaStyle::Trailing, CommaStyle::Leading] {
+            let options = FormatOptions {
+                comma_style,
+                line_width: 30,
+                ..FormatOptions::default()
+            };
+            let out = format(source, &options).unwrap();
+            let expected = parse_stmts(source).unwrap().stmts.to_string();
+            let reparsed = parse_stmts(&out).unwrap().stmts.to_string();
+            assert_eq!(reparsed, expected);
+            assert_eq!(format(&out, &options).unwrap(), out);
+        }
+    }
+}
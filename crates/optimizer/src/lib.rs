@@ -0,0 +1,402 @@
+//! Rule-based rewrites of logical plans.
+//!
+//! [`optimize`] rewrites a [`Plan`] with a fixed sequence of rules, each of
+//! which can be turned off in [`Rules`]. A rule never changes the rows a
+//! plan computes, only how much work it takes to compute them:
+//!
+//! 1. constant folding replaces operators over literals by their value,
+//! 2. `NOT` simplification moves negations onto the operators they negate,
+//! 3. `EXISTS` and `IN (SELECT ...)` filters become semi joins,
+//! 4. predicate pushdown moves filters below joins and projections,
+//! 5. filters that are always true are removed,
+//! 6. projection pruning drops the columns nothing reads.
+
+use std::collections::BTreeSet;
+
+use binder::bound::{BoundExpr, RelationId};
+use planner::plan::{JoinKind, Values};
+use planner::{LogicalPlan, Plan};
+
+mod predicate;
+mod prune;
+mod pushdown;
+mod semi_join;
+mod simplify;
+
+/// The rules [`optimize`] applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub constant_folding: bool,
+    pub not_simplification: bool,
+    pub semi_joins: bool,
+    pub predicate_pushdown: bool,
+    pub true_filter_removal: bool,
+    pub projection_pruning: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            constant_folding: true,
+            not_simplification: true,
+            semi_joins: true,
+            predicate_pushdown: true,
+            true_filter_removal: true,
+            projection_pruning: true,
+        }
+    }
+}
+
+impl Rules {
+    /// No rules, to turn on a few of them with `..Rules::none()`.
+    pub fn none() -> Self {
+        Self {
+            constant_folding: false,
+            not_simplification: false,
+            semi_joins: false,
+            predicate_pushdown: false,
+            true_filter_removal: false,
+            projection_pruning: false,
+        }
+    }
+}
+
+/// Rewrite `plan` and the plans of its subqueries with `rules`.
+pub fn optimize(mut plan: Plan, rules: &Rules) -> Plan {
+    if rules.constant_folding {
+        for_each_expr(&mut plan, simplify::fold_constants);
+    }
+    if rules.not_simplification {
+        for_each_expr(&mut plan, simplify::simplify_not);
+    }
+    if rules.semi_joins {
+        semi_join::convert(&mut plan);
+    }
+    if rules.predicate_pushdown {
+        for_each_tree(&mut plan, pushdown::push_down);
+    }
+    if rules.true_filter_removal {
+        for_each_tree(&mut plan, simplify::remove_true_filters);
+    }
+    if rules.projection_pruning {
+        prune::prune(&mut plan);
+    }
+    plan
+}
+
+fn for_each_tree(plan: &mut Plan, mut f: impl FnMut(&mut LogicalPlan)) {
+    f(&mut plan.root);
+    for subquery in plan.subqueries.values_mut() {
+        f(subquery);
+    }
+}
+
+fn for_each_expr(plan: &mut Plan, f: fn(&mut BoundExpr)) {
+    fn visit(plan: &mut LogicalPlan, f: fn(&mut BoundExpr)) {
+        for expr in plan.exprs_mut() {
+            f(expr);
+        }
+        for input in plan.inputs_mut() {
+            visit(input, f);
+        }
+    }
+    for_each_tree(plan, |tree| visit(tree, f));
+}
+
+/// Take `plan` out of its place, leaving an empty operator behind.
+pub(crate) fn take(plan: &mut LogicalPlan) -> LogicalPlan {
+    let empty = LogicalPlan::Values(Values {
+        relation: RelationId(0),
+        rows: vec![],
+    });
+    std::mem::replace(plan, empty)
+}
+
+/// Returns the relations whose columns the rows of `plan` have.
+pub(crate) fn outputs(plan: &LogicalPlan) -> BTreeSet<RelationId> {
+    match plan {
+        LogicalPlan::Scan(scan) => BTreeSet::from([scan.relation]),
+        LogicalPlan::Project(project) => BTreeSet::from([project.relation]),
+        LogicalPlan::Aggregate(aggregate) => BTreeSet::from([aggregate.relation]),
+        LogicalPlan::Values(values) => BTreeSet::from([values.relation]),
+        LogicalPlan::Join(join) if matches!(join.kind, JoinKind::Semi | JoinKind::Anti) => outputs(&join.left),
+        LogicalPlan::Join(join) => outputs(&join.left).union(&outputs(&join.right)).copied().collect(),
+        LogicalPlan::SetOp(set_op) => outputs(&set_op.left),
+        LogicalPlan::Filter(filter) => outputs(&filter.input),
+        LogicalPlan::Sort(sort) => outputs(&sort.input),
+        LogicalPlan::Limit(limit) => outputs(&limit.input),
+        LogicalPlan::Insert(_) | LogicalPlan::Update(_) | LogicalPlan::Delete(_) => BTreeSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use planner::test_util::{lines, plan_source};
+
+    use super::*;
+
+    /// The plan of `source` before and after optimizing it with `rules`.
+    fn optimized(source: &str, rules: Rules) -> (Vec<String>, Vec<String>) {
+        let plan = plan_source(source);
+        let before = lines(&plan);
+        (before, lines(&optimize(plan, &rules)))
+    }
+
+    #[test]
+    fn constant_folding() {
+        let (before, after) = optimized(
+            "SELECT age * (2 + 3), -(4 - 5), 1 / 0 FROM users WHERE age > 10 + 8 AND (1 < 2 OR name IS NULL);",
+            Rules { constant_folding: true, ..Rules::none() },
+        );
+        assert_eq!(
+            before,
+            [
+                "Project: users.age * (2 + 3) AS age * (2 + 3), -(4 - 5) AS -(4 - 5), 1 / 0 AS 1 / 0",
+                "  Filter: users.age > 10 + 8 AND (1 < 2 OR users.name IS NULL)",
+                "    Scan: users",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: users.age * 5 AS age * (2 + 3), 1 AS -(4 - 5), 1 / 0 AS 1 / 0",
+                "  Filter: users.age > 18 AND (TRUE OR users.name IS NULL)",
+                "    Scan: users",
+            ]
+        );
+    }
+
+    #[test]
+    fn not_simplification() {
+        let (before, after) = optimized(
+            "SELECT name FROM users WHERE NOT (age > 18 AND name LIKE 'a%') AND NOT NOT id IN (1, 2) \
+             AND NOT NOT (age = 3);",
+            Rules { not_simplification: true, ..Rules::none() },
+        );
+        assert_eq!(
+            before,
+            [
+                "Project: users.name",
+                "  Filter: NOT (users.age > 18 AND users.name LIKE 'a%') AND NOT NOT users.id IN (1, 2) \
+                 AND NOT NOT users.age = 3",
+                "    Scan: users",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: users.name",
+                "  Filter: (users.age <= 18 OR users.name NOT LIKE 'a%') AND users.id IN (1, 2) AND users.age = 3",
+                "    Scan: users",
+            ]
+        );
+    }
+
+    #[test]
+    fn semi_joins() {
+        let (before, after) = optimized(
+            "SELECT name FROM users AS u WHERE EXISTS (SELECT 1 FROM orders AS o WHERE o.user_id = u.id AND o.total > 10) \
+             AND id IN (SELECT user_id FROM orders) AND NOT EXISTS (SELECT 1 FROM orders WHERE total < 0) \
+             AND age NOT IN (SELECT total FROM orders);",
+            Rules { semi_joins: true, ..Rules::none() },
+        );
+        assert_eq!(
+            before,
+            [
+                "Project: u.name",
+                "  Filter: EXISTS (#2) AND u.id IN (#4) AND NOT EXISTS (#6) AND u.age NOT IN (#8)",
+                "    Scan: u",
+                "Subquery #2:",
+                "  Project: 1 AS 1",
+                "    Filter: o.user_id = u.id AND o.total > 10",
+                "      Scan: o",
+                "Subquery #4:",
+                "  Project: orders.user_id",
+                "    Scan: orders",
+                "Subquery #6:",
+                "  Project: 1 AS 1",
+                "    Filter: orders.total < 0",
+                "      Scan: orders",
+                "Subquery #8:",
+                "  Project: orders.total",
+                "    Scan: orders",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: u.name",
+                "  Filter: u.age NOT IN (#8)",
+                "    Join: ANTI",
+                "      Join: SEMI ON u.id = #4.user_id",
+                "        Join: SEMI ON o.user_id = u.id",
+                "          Scan: u",
+                "          Filter: o.total > 10",
+                "            Scan: o",
+                "        Project: orders.user_id",
+                "          Scan: orders",
+                "      Filter: orders.total < 0",
+                "        Scan: orders",
+                "Subquery #8:",
+                "  Project: orders.total",
+                "    Scan: orders",
+            ]
+        );
+    }
+
+    #[test]
+    fn predicate_pushdown() {
+        let (before, after) = optimized(
+            "SELECT * FROM (SELECT id, name, age * 2 AS double FROM users) AS u \
+             JOIN orders AS o ON o.user_id = u.id AND o.total > 5 WHERE u.double > 40 AND u.id < o.id LIMIT 3;",
+            Rules { predicate_pushdown: true, ..Rules::none() },
+        );
+        assert_eq!(
+            before,
+            [
+                "Limit: 3",
+                "  Project: u.id, u.name, u.double, o.id, o.user_id, o.total",
+                "    Filter: u.double > 40 AND u.id < o.id",
+                "      Join: INNER ON o.user_id = u.id AND o.total > 5",
+                "        Project: users.id, users.name, users.age * 2 AS double",
+                "          Scan: users",
+                "        Scan: o",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Limit: 3",
+                "  Project: u.id, u.name, u.double, o.id, o.user_id, o.total",
+                "    Join: INNER ON o.user_id = u.id AND u.id < o.id",
+                "      Project: users.id, users.name, users.age * 2 AS double",
+                "        Filter: users.age * 2 > 40",
+                "          Scan: users",
+                "      Filter: o.total > 5",
+                "        Scan: o",
+            ]
+        );
+
+        // Rows of `u` without a match keep NULLs for `o`, so a filter on `o`
+        // stays above the join and a condition on `u` stays in it.
+        let (_, after) = optimized(
+            "SELECT u.name FROM users AS u LEFT JOIN orders AS o ON o.user_id = u.id AND u.age > 3 \
+             WHERE o.total > 1 AND u.age < 2;",
+            Rules { predicate_pushdown: true, ..Rules::none() },
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: u.name",
+                "  Filter: o.total > 1",
+                "    Join: LEFT ON o.user_id = u.id AND u.age > 3",
+                "      Filter: u.age < 2",
+                "        Scan: u",
+                "      Scan: o",
+            ]
+        );
+    }
+
+    #[test]
+    fn true_filter_removal() {
+        let (before, after) = optimized(
+            "SELECT name FROM users AS u JOIN orders ON TRUE WHERE TRUE AND age > 1 AND TRUE;",
+            Rules { true_filter_removal: true, ..Rules::none() },
+        );
+        assert_eq!(
+            before,
+            [
+                "Project: u.name",
+                "  Filter: TRUE AND u.age > 1 AND TRUE",
+                "    Join: INNER ON TRUE",
+                "      Scan: u",
+                "      Scan: orders",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: u.name",
+                "  Filter: u.age > 1",
+                "    Join: INNER",
+                "      Scan: u",
+                "      Scan: orders",
+            ]
+        );
+    }
+
+    #[test]
+    fn projection_pruning() {
+        let rules = || Rules { projection_pruning: true, ..Rules::none() };
+        let (before, after) = optimized("SELECT t.b FROM (SELECT name AS a, age AS b, id + 1 AS c FROM users) AS t;", rules());
+        assert_eq!(
+            before,
+            [
+                "Project: t.b",
+                "  Project: users.name AS a, users.age AS b, users.id + 1 AS c",
+                "    Scan: users",
+            ]
+        );
+        assert_eq!(after, ["Project: t.b", "  Project: users.age AS b", "    Scan: users (age)"]);
+
+        // The columns of a DISTINCT and of the inputs of a UNION are its rows.
+        let (_, after) = optimized(
+            "SELECT DISTINCT t.b FROM (SELECT name AS a, age AS b FROM users) AS t UNION SELECT id FROM orders;",
+            rules(),
+        );
+        assert_eq!(
+            after,
+            [
+                "SetOp: UNION",
+                "  Aggregate: group by [#2.b]",
+                "    Project: t.b",
+                "      Project: users.age AS b",
+                "        Scan: users (age)",
+                "  Project: orders.id",
+                "    Scan: orders (id)",
+            ]
+        );
+
+        // Updated rows are written back whole.
+        let (before, after) = optimized("UPDATE users SET age = 1 WHERE name = 'x';", rules());
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn all_rules() {
+        let (before, after) = optimized(
+            "SELECT t.n FROM (SELECT name AS n, age AS a FROM users) AS t JOIN orders AS o ON o.user_id = t.a \
+             WHERE t.a > 10 + 8 AND NOT NOT (1 = 1) AND EXISTS (SELECT 1 FROM orders AS p WHERE p.id = o.id);",
+            Rules::default(),
+        );
+        assert_eq!(
+            before,
+            [
+                "Project: t.n",
+                "  Filter: t.a > 10 + 8 AND NOT NOT 1 = 1 AND EXISTS (#4)",
+                "    Join: INNER ON o.user_id = t.a",
+                "      Project: users.name AS n, users.age AS a",
+                "        Scan: users",
+                "      Scan: o",
+                "Subquery #4:",
+                "  Project: 1 AS 1",
+                "    Filter: p.id = o.id",
+                "      Scan: p",
+            ]
+        );
+        assert_eq!(
+            after,
+            [
+                "Project: t.n",
+                "  Join: SEMI ON p.id = o.id",
+                "    Join: INNER ON o.user_id = t.a",
+                "      Project: users.name AS n, users.age AS a",
+                "        Filter: users.age > 18",
+                "          Scan: users (name, age)",
+                "      Scan: o (id, user_id)",
+                "    Scan: p (id)",
+            ]
+        );
+        assert_eq!(optimized("SELECT 1;", Rules::none()).1, ["Project: 1 AS 1", "  Values: ()"]);
+    }
+}
@@ -0,0 +1,61 @@
+//! Taking predicates apart and putting them back together.
+
+use std::collections::BTreeSet;
+
+use binder::bound::{BoundBoolOp, BoundExpr, RelationId};
+use planner::expr::{children, subquery};
+use source_index::span::{Span, Spanned};
+
+/// Split `expr` at its top-level ANDs.
+pub(crate) fn conjuncts(expr: BoundExpr) -> Vec<BoundExpr> {
+    match expr {
+        BoundExpr::BoolOp(expr) if expr.op == ast::BooleanOperator::And => {
+            let mut conjuncts = conjuncts(*expr.left);
+            conjuncts.extend(self::conjuncts(*expr.right));
+            conjuncts
+        }
+        expr => vec![expr],
+    }
+}
+
+/// The AND of `exprs`, `None` if there are none.
+pub(crate) fn conjunction(exprs: Vec<BoundExpr>) -> Option<BoundExpr> {
+    exprs.into_iter().reduce(|left, right| {
+        let (start, end) = (left.span(), right.span());
+        BoundExpr::BoolOp(BoundBoolOp {
+            span: Span::new(start.start().min(end.start()), start.end().max(end.end())),
+            left: Box::new(left),
+            op: ast::BooleanOperator::And,
+            right: Box::new(right),
+        })
+    })
+}
+
+/// Returns the relations `expr` reads columns of, leaving out those read
+/// by its subqueries.
+pub(crate) fn relations(expr: &BoundExpr) -> BTreeSet<RelationId> {
+    fn walk(expr: &BoundExpr, relations: &mut BTreeSet<RelationId>) {
+        if let BoundExpr::Column(column) = expr {
+            relations.insert(column.relation);
+        }
+        for child in children(expr) {
+            walk(child, relations);
+        }
+    }
+    let mut relations = BTreeSet::new();
+    walk(expr, &mut relations);
+    relations
+}
+
+/// Returns the relations of the selects of the subqueries in `expr`.
+pub(crate) fn subqueries(expr: &BoundExpr) -> Vec<RelationId> {
+    fn walk(expr: &BoundExpr, ids: &mut Vec<RelationId>) {
+        ids.extend(subquery(expr).map(|select| select.id));
+        for child in children(expr) {
+            walk(child, ids);
+        }
+    }
+    let mut ids = Vec::new();
+    walk(expr, &mut ids);
+    ids
+}
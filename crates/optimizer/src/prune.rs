@@ -0,0 +1,176 @@
+//! Projection pruning: scans read only the columns something uses, and
+//! projections inside the plan compute only the columns read above them.
+//!
+//! The columns of a projection are numbered by position, so the columns
+//! kept are renumbered, in the relation and in every reference to it. The
+//! result of the statement, the inputs of set operations and of inserts,
+//! which are read by position, and the rows updated or deleted keep all
+//! their columns.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use binder::bound::{BoundExpr, RelationId};
+use binder::ColumnId;
+use planner::expr::children_mut;
+use planner::plan::ScanSource;
+use planner::{LogicalPlan, Plan};
+
+use crate::predicate::relations;
+
+pub(crate) fn prune(plan: &mut Plan) {
+    // Dropping a column of a projection can leave the columns it read
+    // unused, so repeat until nothing changes.
+    while prune_once(plan) {}
+}
+
+fn prune_once(plan: &mut Plan) -> bool {
+    let used = used_columns(plan);
+    let mut pinned = BTreeSet::new();
+    let mut projects = BTreeSet::new();
+    for tree in trees(plan) {
+        find_prunable(tree, true, &mut pinned, &mut projects);
+    }
+
+    let mut renumbered: BTreeMap<RelationId, Vec<Option<ColumnId>>> = BTreeMap::new();
+    for relation in projects.difference(&pinned) {
+        let columns = &mut plan.relations[relation.0 as usize].columns;
+        if (0..columns.len()).all(|column| used.contains(&(*relation, ColumnId(column as u32)))) {
+            continue;
+        }
+        let mut next = 0;
+        let numbers = (0..columns.len() as u32)
+            .map(|column| {
+                used.contains(&(*relation, ColumnId(column))).then(|| {
+                    next += 1;
+                    ColumnId(next - 1)
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut numbers_iter = numbers.iter();
+        columns.retain(|_| numbers_iter.next().is_some_and(Option::is_some));
+        renumbered.insert(*relation, numbers);
+    }
+
+    let mut changed = !renumbered.is_empty();
+    let trees = std::iter::once(&mut plan.root).chain(plan.subqueries.values_mut());
+    for tree in trees {
+        changed |= apply(tree, &used, &renumbered, &BTreeSet::new());
+    }
+    changed
+}
+
+fn trees(plan: &Plan) -> impl Iterator<Item = &LogicalPlan> {
+    std::iter::once(&plan.root).chain(plan.subqueries.values())
+}
+
+/// The columns read by the expressions of `plan`.
+fn used_columns(plan: &Plan) -> BTreeSet<(RelationId, ColumnId)> {
+    fn walk(expr: &BoundExpr, used: &mut BTreeSet<(RelationId, ColumnId)>) {
+        if let BoundExpr::Column(column) = expr {
+            used.insert((column.relation, column.column));
+        }
+        for child in planner::expr::children(expr) {
+            walk(child, used);
+        }
+    }
+    fn visit(plan: &LogicalPlan, used: &mut BTreeSet<(RelationId, ColumnId)>) {
+        for expr in plan.exprs() {
+            walk(expr, used);
+        }
+        for input in plan.inputs() {
+            visit(input, used);
+        }
+    }
+    let mut used = BTreeSet::new();
+    for tree in trees(plan) {
+        visit(tree, &mut used);
+    }
+    used
+}
+
+/// Collect the relations of the projections of `plan` into `projects`, and
+/// into `pinned` those of the ones that must keep all their columns, which
+/// `plan` must if `keep` is set.
+fn find_prunable(
+    plan: &LogicalPlan,
+    keep: bool,
+    pinned: &mut BTreeSet<RelationId>,
+    projects: &mut BTreeSet<RelationId>,
+) {
+    let keep_inputs = match plan {
+        LogicalPlan::Project(project) => {
+            projects.insert(project.relation);
+            if keep {
+                pinned.insert(project.relation);
+            }
+            false
+        }
+        LogicalPlan::Filter(_) | LogicalPlan::Sort(_) | LogicalPlan::Limit(_) => keep,
+        // A DISTINCT groups the columns of the projection below it, which
+        // is the result in its place.
+        LogicalPlan::Aggregate(aggregate) => {
+            let distinct = aggregate.aggregates.is_empty()
+                && aggregate
+                    .group_by
+                    .iter()
+                    .all(|expr| relations(expr).contains(&aggregate.relation));
+            keep && distinct
+        }
+        LogicalPlan::SetOp(_) | LogicalPlan::Insert(_) => true,
+        _ => false,
+    };
+    for input in plan.inputs() {
+        find_prunable(input, keep_inputs, pinned, projects);
+    }
+}
+
+/// Drop the unused columns of the scans and the `renumbered` projections of
+/// `plan`, and renumber the references to them. The rows of `targets` are
+/// written back and keep all columns.
+fn apply(
+    plan: &mut LogicalPlan,
+    used: &BTreeSet<(RelationId, ColumnId)>,
+    renumbered: &BTreeMap<RelationId, Vec<Option<ColumnId>>>,
+    targets: &BTreeSet<RelationId>,
+) -> bool {
+    let mut changed = false;
+    let mut targets = targets.clone();
+    match plan {
+        LogicalPlan::Scan(scan) if matches!(scan.source, ScanSource::Table(_)) && !targets.contains(&scan.relation) => {
+            let before = scan.columns.len();
+            scan.columns.retain(|column| used.contains(&(scan.relation, *column)));
+            changed = scan.columns.len() != before;
+        }
+        LogicalPlan::Project(project) => {
+            if let Some(numbers) = renumbered.get(&project.relation) {
+                let mut numbers = numbers.iter();
+                project.columns.retain(|_| numbers.next().is_some_and(Option::is_some));
+            }
+        }
+        LogicalPlan::Update(update) => {
+            targets.insert(update.relation);
+        }
+        LogicalPlan::Delete(delete) => {
+            targets.insert(delete.relation);
+        }
+        _ => {}
+    }
+    for expr in plan.exprs_mut() {
+        renumber(expr, renumbered);
+    }
+    for input in plan.inputs_mut() {
+        changed |= apply(input, used, renumbered, &targets);
+    }
+    changed
+}
+
+fn renumber(expr: &mut BoundExpr, renumbered: &BTreeMap<RelationId, Vec<Option<ColumnId>>>) {
+    if let BoundExpr::Column(column) = expr {
+        if let Some(numbers) = renumbered.get(&column.relation) {
+            column.column = numbers[column.column.0 as usize].expect("a read column is kept");
+        }
+    }
+    for child in children_mut(expr) {
+        renumber(child, renumbered);
+    }
+}
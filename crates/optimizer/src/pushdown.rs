@@ -0,0 +1,134 @@
+//! Predicate pushdown: each conjunct of a filter or join condition moves
+//! down to the lowest operator that has the columns it reads, so fewer rows
+//! reach the joins and projections above it.
+//!
+//! A conjunct moves through a [`Project`] by reading the expressions of the
+//! columns instead, and into the side of a join it only reads, unless that
+//! side keeps rows without a match. Conjuncts with subqueries stay where
+//! they are.
+
+use binder::bound::BoundExpr;
+use planner::plan::{Filter, JoinKind, Project};
+use planner::LogicalPlan;
+
+use crate::predicate::{conjunction, conjuncts, relations};
+use crate::{outputs, take};
+
+pub(crate) fn push_down(plan: &mut LogicalPlan) {
+    for input in plan.inputs_mut() {
+        push_down(input);
+    }
+    match plan {
+        LogicalPlan::Filter(filter) => {
+            let mut input = take(&mut filter.input);
+            for conjunct in conjuncts(filter.predicate.clone()) {
+                input = push(input, conjunct);
+            }
+            *plan = input;
+        }
+        LogicalPlan::Join(join) => {
+            let Some(condition) = join.condition.take() else {
+                return;
+            };
+            let mut kept = Vec::new();
+            for conjunct in conjuncts(condition) {
+                let (left, right) = sides(&conjunct, &join.left, &join.right);
+                if left && !right && matches!(join.kind, JoinKind::Inner | JoinKind::Right) {
+                    *join.left = push(take(&mut join.left), conjunct);
+                } else if right
+                    && !left
+                    && matches!(join.kind, JoinKind::Inner | JoinKind::Left | JoinKind::Semi | JoinKind::Anti)
+                {
+                    *join.right = push(take(&mut join.right), conjunct);
+                } else {
+                    kept.push(conjunct);
+                }
+            }
+            join.condition = conjunction(kept);
+        }
+        _ => {}
+    }
+}
+
+/// Filter `plan` by `predicate`, as far down in it as possible.
+fn push(plan: LogicalPlan, predicate: BoundExpr) -> LogicalPlan {
+    if !crate::predicate::subqueries(&predicate).is_empty() {
+        return filter(plan, predicate);
+    }
+    match plan {
+        LogicalPlan::Filter(mut filter) => {
+            filter.input = Box::new(push(*filter.input, predicate));
+            LogicalPlan::Filter(filter)
+        }
+        LogicalPlan::Sort(mut sort) => {
+            sort.input = Box::new(push(*sort.input, predicate));
+            LogicalPlan::Sort(sort)
+        }
+        LogicalPlan::Project(mut project) => match substitute(&predicate, &project) {
+            Some(predicate) => {
+                project.input = Box::new(push(*project.input, predicate));
+                LogicalPlan::Project(project)
+            }
+            None => filter(LogicalPlan::Project(project), predicate),
+        },
+        LogicalPlan::Join(mut join) => {
+            let (left, right) = sides(&predicate, &join.left, &join.right);
+            match (left, right) {
+                (true, false) if join.kind != JoinKind::Right && join.kind != JoinKind::Full => {
+                    join.left = Box::new(push(*join.left, predicate));
+                }
+                (false, true) if matches!(join.kind, JoinKind::Inner | JoinKind::Right) => {
+                    join.right = Box::new(push(*join.right, predicate));
+                }
+                (true, true) if join.kind == JoinKind::Inner => {
+                    join.condition = conjunction(join.condition.take().into_iter().chain([predicate]).collect());
+                }
+                _ => return filter(LogicalPlan::Join(join), predicate),
+            }
+            LogicalPlan::Join(join)
+        }
+        plan => filter(plan, predicate),
+    }
+}
+
+/// Whether `predicate` reads columns of the left and of the right input.
+fn sides(predicate: &BoundExpr, left: &LogicalPlan, right: &LogicalPlan) -> (bool, bool) {
+    let relations = relations(predicate);
+    let (left, right) = (outputs(left), outputs(right));
+    (
+        relations.iter().any(|relation| left.contains(relation)),
+        relations.iter().any(|relation| right.contains(relation)),
+    )
+}
+
+/// `predicate` reading the input of `project` instead of its columns.
+/// `None` if a column it reads is computed per window or by a subquery,
+/// whose value depends on the rows around it.
+fn substitute(predicate: &BoundExpr, project: &Project) -> Option<BoundExpr> {
+    let mut substituted = predicate.clone();
+    let mut valid = true;
+    planner::expr::replace(&mut substituted, &mut |expr| match expr {
+        BoundExpr::Column(column) if column.relation == project.relation => {
+            let expr = &project.columns[column.column.0 as usize].expr;
+            valid &= !has_window_or_subquery(expr);
+            Some(expr.clone())
+        }
+        _ => None,
+    });
+    valid.then_some(substituted)
+}
+
+fn has_window_or_subquery(expr: &BoundExpr) -> bool {
+    match expr {
+        BoundExpr::Function(function) if function.over.is_some() => true,
+        _ if planner::expr::subquery(expr).is_some() => true,
+        _ => planner::expr::children(expr).into_iter().any(has_window_or_subquery),
+    }
+}
+
+fn filter(input: LogicalPlan, predicate: BoundExpr) -> LogicalPlan {
+    LogicalPlan::Filter(Filter {
+        input: Box::new(input),
+        predicate,
+    })
+}
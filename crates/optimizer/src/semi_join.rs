@@ -0,0 +1,182 @@
+//! Semi joins for `EXISTS` and `IN (SELECT ...)` in filters.
+//!
+//! A subquery in a filter runs once per row. As the right input of a semi
+//! join it runs once, with the filters of an `EXISTS` that read the row
+//! moved into the join condition. `NOT EXISTS` becomes an anti join.
+//! `NOT IN` is kept: a NULL in its subquery makes it NULL for every row,
+//! which an anti join does not do.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use binder::bound::{BoundCompare, BoundExpr, BoundInKind, ColumnRef, Relation, RelationId};
+use binder::ColumnId;
+use planner::plan::{Filter, Join, JoinKind};
+use planner::{LogicalPlan, Plan};
+
+use crate::predicate::{conjunction, conjuncts, relations, subqueries};
+use crate::{outputs, take};
+
+pub(crate) fn convert(plan: &mut Plan) {
+    let mut converter = Converter {
+        subqueries: std::mem::take(&mut plan.subqueries),
+        relations: &plan.relations,
+    };
+    converter.convert(&mut plan.root);
+    let ids: Vec<RelationId> = converter.subqueries.keys().copied().collect();
+    for id in ids {
+        // Converting one subquery may have moved another into a join.
+        if let Some(mut subquery) = converter.subqueries.remove(&id) {
+            converter.convert(&mut subquery);
+            converter.subqueries.insert(id, subquery);
+        }
+    }
+    plan.subqueries = converter.subqueries;
+}
+
+struct Converter<'a> {
+    subqueries: BTreeMap<RelationId, LogicalPlan>,
+    relations: &'a [Relation],
+}
+
+impl Converter<'_> {
+    fn convert(&mut self, plan: &mut LogicalPlan) {
+        for input in plan.inputs_mut() {
+            self.convert(input);
+        }
+        let LogicalPlan::Filter(filter) = plan else {
+            return;
+        };
+        let mut input = take(&mut filter.input);
+        let mut kept = Vec::new();
+        for conjunct in conjuncts(filter.predicate.clone()) {
+            match self.semi_join(&input, &conjunct) {
+                Some((kind, mut right, condition)) => {
+                    self.convert(&mut right);
+                    input = LogicalPlan::Join(Join {
+                        left: Box::new(input),
+                        kind,
+                        right: Box::new(right),
+                        condition,
+                    });
+                }
+                None => kept.push(conjunct),
+            }
+        }
+        *plan = match conjunction(kept) {
+            Some(predicate) => LogicalPlan::Filter(Filter {
+                input: Box::new(input),
+                predicate,
+            }),
+            None => input,
+        };
+    }
+
+    /// The kind, right input and condition of the join of `left` that
+    /// filters it like `conjunct`. The subquery of a converted conjunct is
+    /// taken out of the subqueries.
+    fn semi_join(
+        &mut self,
+        left: &LogicalPlan,
+        conjunct: &BoundExpr,
+    ) -> Option<(JoinKind, LogicalPlan, Option<BoundExpr>)> {
+        let (kind, right, condition, id) = match conjunct {
+            BoundExpr::Exists(exists) => {
+                let kind = if exists.negated { JoinKind::Anti } else { JoinKind::Semi };
+                let mut right = self.subqueries.get(&exists.select.id)?.clone();
+                // Whether there is a row does not depend on its columns or
+                // on the order of the rows.
+                loop {
+                    match right {
+                        LogicalPlan::Project(project) => right = *project.input,
+                        LogicalPlan::Sort(sort) => right = *sort.input,
+                        _ => break,
+                    }
+                }
+                let mut condition = Vec::new();
+                let right = self.decorrelate(right, &outputs(left), &mut condition);
+                (kind, right, conjunction(condition), exists.select.id)
+            }
+            BoundExpr::In(expr) if !expr.negated => {
+                let BoundInKind::Select(select) = &expr.kind else {
+                    return None;
+                };
+                if matches!(*expr.expr, BoundExpr::List(_)) || !subqueries(&expr.expr).is_empty() {
+                    return None;
+                }
+                let column = BoundExpr::Column(ColumnRef {
+                    span: expr.span,
+                    relation: select.id,
+                    column: ColumnId(0),
+                    name: self.relations[select.id.0 as usize].columns[0].name.clone(),
+                });
+                let condition = BoundExpr::Compare(BoundCompare {
+                    span: expr.span,
+                    left: expr.expr.clone(),
+                    op: ast::CmpOperator::Eq,
+                    right: Box::new(column),
+                });
+                let right = self.subqueries.get(&select.id)?.clone();
+                (JoinKind::Semi, right, Some(condition), select.id)
+            }
+            _ => return None,
+        };
+        // Anything else reading the rows of `left` would need a row of it.
+        if self.reads(&right, &produced(left)) {
+            return None;
+        }
+        self.subqueries.remove(&id);
+        Some((kind, right, condition))
+    }
+
+    /// Move the conjuncts of the filters at the top of `plan` that read the
+    /// relations `outer` into `condition`.
+    fn decorrelate(
+        &self,
+        plan: LogicalPlan,
+        outer: &BTreeSet<RelationId>,
+        condition: &mut Vec<BoundExpr>,
+    ) -> LogicalPlan {
+        let LogicalPlan::Filter(filter) = plan else {
+            return plan;
+        };
+        let input = self.decorrelate(*filter.input, outer, condition);
+        let mut kept = Vec::new();
+        for conjunct in conjuncts(filter.predicate) {
+            let correlated = relations(&conjunct).iter().any(|relation| outer.contains(relation));
+            if correlated && subqueries(&conjunct).is_empty() {
+                condition.push(conjunct);
+            } else {
+                kept.push(conjunct);
+            }
+        }
+        match conjunction(kept) {
+            Some(predicate) => LogicalPlan::Filter(Filter {
+                input: Box::new(input),
+                predicate,
+            }),
+            None => input,
+        }
+    }
+
+    /// Whether `plan` or one of its subqueries reads a column of one of
+    /// `relations`.
+    fn reads(&self, plan: &LogicalPlan, relations: &BTreeSet<RelationId>) -> bool {
+        plan.exprs().into_iter().any(|expr| {
+            crate::predicate::relations(expr).iter().any(|relation| relations.contains(relation))
+                || subqueries(expr)
+                    .iter()
+                    .filter_map(|id| self.subqueries.get(id))
+                    .any(|subquery| self.reads(subquery, relations))
+        }) || plan.inputs().into_iter().any(|input| self.reads(input, relations))
+    }
+}
+
+/// Returns the relations of all operators of `plan`, not only those of its
+/// result.
+fn produced(plan: &LogicalPlan) -> BTreeSet<RelationId> {
+    let mut relations = outputs(plan);
+    for input in plan.inputs() {
+        relations.extend(produced(input));
+    }
+    relations
+}
@@ -0,0 +1,277 @@
+//! Rules rewriting expressions on their own: constant folding, `NOT`
+//! simplification and the removal of predicates that are always true.
+
+use ast::{
+    BinaryOperator, BoolLiteral, BooleanOperator, CmpOperator, FloatLiteral, IntLiteral, LiteralValue, NullLiteral,
+    UnaryOperator,
+};
+use binder::bound::{BoundCompare, BoundExpr, BoundUnaryOp};
+use planner::expr::children_mut;
+use planner::LogicalPlan;
+use source_index::span::{Span, Spanned};
+
+use crate::predicate::{conjunction, conjuncts};
+use crate::take;
+
+/// Replace the arithmetic, comparisons and logic over literals by their
+/// value. Arithmetic that overflows or divides by zero is left for
+/// execution to report.
+pub(crate) fn fold_constants(expr: &mut BoundExpr) {
+    for child in children_mut(expr) {
+        fold_constants(child);
+    }
+    let span = expr.span();
+    let value = match expr {
+        BoundExpr::BinaryOp(expr) => binary(value(&expr.left), &expr.op, value(&expr.right)),
+        BoundExpr::UnaryOp(expr) => unary(&expr.op, value(&expr.operand)),
+        BoundExpr::Compare(expr) => compare(value(&expr.left), &expr.op, value(&expr.right)),
+        BoundExpr::BoolOp(expr) => boolean(value(&expr.left), &expr.op, value(&expr.right)),
+        _ => None,
+    };
+    if let Some(value) = value {
+        *expr = BoundExpr::Literal(value.literal(span));
+    }
+}
+
+/// The value of a numeric, boolean or NULL literal. `TRUE` and `FALSE` are
+/// the integers 1 and 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn literal(self, span: Span) -> LiteralValue {
+        match self {
+            Value::Null => LiteralValue::NullLiteral(NullLiteral { span }),
+            Value::Integer(value) => LiteralValue::IntLiteral(IntLiteral { span, value }),
+            Value::Float(value) => LiteralValue::FloatLiteral(FloatLiteral { span, value }),
+            Value::Boolean(value) => LiteralValue::BoolLiteral(BoolLiteral { span, value }),
+        }
+    }
+
+    fn float(self) -> Option<f64> {
+        match self {
+            Value::Null => None,
+            Value::Integer(value) => Some(value as f64),
+            Value::Float(value) => Some(value),
+            Value::Boolean(value) => Some(value as i64 as f64),
+        }
+    }
+
+    /// `None` for NULL, which is neither true nor false.
+    fn truth(self) -> Option<bool> {
+        self.float().map(|value| value != 0.0)
+    }
+}
+
+fn value(expr: &BoundExpr) -> Option<Value> {
+    match expr {
+        BoundExpr::Literal(LiteralValue::NullLiteral(_)) => Some(Value::Null),
+        BoundExpr::Literal(LiteralValue::IntLiteral(literal)) => Some(Value::Integer(literal.value)),
+        BoundExpr::Literal(LiteralValue::FloatLiteral(literal)) => Some(Value::Float(literal.value)),
+        BoundExpr::Literal(LiteralValue::BoolLiteral(literal)) => Some(Value::Boolean(literal.value)),
+        _ => None,
+    }
+}
+
+fn integer(value: Value) -> Option<i64> {
+    match value {
+        Value::Integer(value) => Some(value),
+        Value::Boolean(value) => Some(value as i64),
+        _ => None,
+    }
+}
+
+fn binary(left: Option<Value>, op: &BinaryOperator, right: Option<Value>) -> Option<Value> {
+    let (left, right) = (left?, right?);
+    if left == Value::Null || right == Value::Null {
+        return Some(Value::Null);
+    }
+    if let (Some(left), Some(right)) = (integer(left), integer(right)) {
+        let value = match op {
+            BinaryOperator::Add => left.checked_add(right),
+            BinaryOperator::Sub => left.checked_sub(right),
+            BinaryOperator::Mult => left.checked_mul(right),
+            BinaryOperator::Div => left.checked_div(right),
+            BinaryOperator::Mod => left.checked_rem(right),
+        };
+        return value.map(Value::Integer);
+    }
+    let (left, right) = (left.float()?, right.float()?);
+    let value = match op {
+        BinaryOperator::Add => left + right,
+        BinaryOperator::Sub => left - right,
+        BinaryOperator::Mult => left * right,
+        BinaryOperator::Div if right != 0.0 => left / right,
+        _ => return None,
+    };
+    value.is_finite().then_some(Value::Float(value))
+}
+
+fn unary(op: &UnaryOperator, operand: Option<Value>) -> Option<Value> {
+    let operand = operand?;
+    if operand == Value::Null {
+        return Some(Value::Null);
+    }
+    match op {
+        UnaryOperator::Not => operand.truth().map(|truth| Value::Boolean(!truth)),
+        UnaryOperator::Positive => Some(operand),
+        UnaryOperator::Negative => match operand {
+            Value::Float(value) => Some(Value::Float(-value)),
+            _ => integer(operand)?.checked_neg().map(Value::Integer),
+        },
+    }
+}
+
+fn compare(left: Option<Value>, op: &CmpOperator, right: Option<Value>) -> Option<Value> {
+    let (left, right) = (left?, right?);
+    if left == Value::Null || right == Value::Null {
+        return Some(Value::Null);
+    }
+    let ordering = match (integer(left), integer(right)) {
+        (Some(left), Some(right)) => left.cmp(&right),
+        _ => left.float()?.partial_cmp(&right.float()?)?,
+    };
+    let value = match op {
+        CmpOperator::Gt => ordering.is_gt(),
+        CmpOperator::GtE => ordering.is_ge(),
+        CmpOperator::Lt => ordering.is_lt(),
+        CmpOperator::LtE => ordering.is_le(),
+        CmpOperator::NotEq => ordering.is_ne(),
+        CmpOperator::Eq => ordering.is_eq(),
+    };
+    Some(Value::Boolean(value))
+}
+
+fn boolean(left: Option<Value>, op: &BooleanOperator, right: Option<Value>) -> Option<Value> {
+    let (left, right) = (left?.truth(), right?.truth());
+    let value = match op {
+        BooleanOperator::And if left == Some(false) || right == Some(false) => Some(false),
+        BooleanOperator::Or if left == Some(true) || right == Some(true) => Some(true),
+        _ => left.zip(right).map(|(left, _)| left),
+    };
+    Some(value.map_or(Value::Null, Value::Boolean))
+}
+
+/// Move every `NOT` onto the operators it negates: `NOT a = b` becomes
+/// `a != b`, `NOT (a AND b)` becomes `NOT a OR NOT b`, and `NOT NOT a`
+/// becomes `a` where `a` is true or false.
+pub(crate) fn simplify_not(expr: &mut BoundExpr) {
+    if let BoundExpr::UnaryOp(not) = expr {
+        if not.op == UnaryOperator::Not {
+            if let Some(negated) = negate(&not.operand) {
+                *expr = negated;
+            }
+        }
+    }
+    for child in children_mut(expr) {
+        simplify_not(child);
+    }
+}
+
+/// The expression that is true where `expr` is false and the other way
+/// around, `None` if it needs a `NOT`.
+fn negate(expr: &BoundExpr) -> Option<BoundExpr> {
+    let negated = match expr.clone() {
+        BoundExpr::UnaryOp(not) if not.op == UnaryOperator::Not && is_boolean(&not.operand) => *not.operand,
+        BoundExpr::Compare(compare) => BoundExpr::Compare(BoundCompare {
+            op: match compare.op {
+                CmpOperator::Gt => CmpOperator::LtE,
+                CmpOperator::GtE => CmpOperator::Lt,
+                CmpOperator::Lt => CmpOperator::GtE,
+                CmpOperator::LtE => CmpOperator::Gt,
+                CmpOperator::NotEq => CmpOperator::Eq,
+                CmpOperator::Eq => CmpOperator::NotEq,
+            },
+            ..compare
+        }),
+        BoundExpr::BoolOp(mut expr) => {
+            expr.op = match expr.op {
+                BooleanOperator::And => BooleanOperator::Or,
+                BooleanOperator::Or => BooleanOperator::And,
+            };
+            expr.left = Box::new(not(*expr.left));
+            expr.right = Box::new(not(*expr.right));
+            BoundExpr::BoolOp(expr)
+        }
+        BoundExpr::Like(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::Like(expr)
+        }
+        BoundExpr::IsNull(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::IsNull(expr)
+        }
+        BoundExpr::Is(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::Is(expr)
+        }
+        BoundExpr::Between(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::Between(expr)
+        }
+        BoundExpr::In(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::In(expr)
+        }
+        BoundExpr::Exists(mut expr) => {
+            expr.negated = !expr.negated;
+            BoundExpr::Exists(expr)
+        }
+        _ => return None,
+    };
+    Some(negated)
+}
+
+fn not(expr: BoundExpr) -> BoundExpr {
+    BoundExpr::UnaryOp(BoundUnaryOp {
+        span: expr.span(),
+        op: UnaryOperator::Not,
+        operand: Box::new(expr),
+    })
+}
+
+/// The value of `expr` is true, false or NULL, rather than any number.
+fn is_boolean(expr: &BoundExpr) -> bool {
+    match expr {
+        BoundExpr::UnaryOp(expr) => expr.op == UnaryOperator::Not,
+        BoundExpr::Literal(literal) => matches!(literal, LiteralValue::BoolLiteral(_)),
+        BoundExpr::Compare(_)
+        | BoundExpr::BoolOp(_)
+        | BoundExpr::Like(_)
+        | BoundExpr::IsNull(_)
+        | BoundExpr::Is(_)
+        | BoundExpr::Between(_)
+        | BoundExpr::In(_)
+        | BoundExpr::Exists(_) => true,
+        _ => false,
+    }
+}
+
+/// Drop the conjuncts of filters and join conditions that are literals
+/// known to be true, and the filters left without any.
+pub(crate) fn remove_true_filters(plan: &mut LogicalPlan) {
+    for input in plan.inputs_mut() {
+        remove_true_filters(input);
+    }
+    match plan {
+        LogicalPlan::Filter(filter) => match without_true(filter.predicate.clone()) {
+            Some(predicate) => filter.predicate = predicate,
+            None => *plan = take(&mut filter.input),
+        },
+        LogicalPlan::Join(join) => join.condition = join.condition.take().and_then(without_true),
+        _ => {}
+    }
+}
+
+fn without_true(predicate: BoundExpr) -> Option<BoundExpr> {
+    let conjuncts = conjuncts(predicate)
+        .into_iter()
+        .filter(|conjunct| value(conjunct).and_then(Value::truth) != Some(true))
+        .collect();
+    conjunction(conjuncts)
+}
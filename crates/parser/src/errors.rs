@@ -1,11 +1,10 @@
-use std::{
-    fmt::{Display, Write},
-    ops::Deref,
-};
+use std::fmt::Display;
 
+use source_index::diagnostic::{Diagnostic, Label};
 use source_index::span::Span;
 
-use crate::tokens::TokenKind;
+use crate::lexer::Lexer;
+use crate::tokens::{TokenKind, KEYWORDS};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParseError {
@@ -18,10 +17,136 @@ impl ParseError {
     pub fn new(kind: ParseErrorKind, span: Span) -> Self {
         Self { kind, span }
     }
+
+    /// The keyword the user most likely meant, when the error is at an
+    /// identifier that is a misspelled keyword e.g. `SELCT`.
+    pub fn suggestion(&self, source: &str) -> Option<TokenKind> {
+        let word = word_at(source, self.span);
+        if word.is_empty() || TokenKind::from_keyword(word).is_some() {
+            return None;
+        }
+        let candidates = KEYWORDS.iter().map(|(_, kind)| *kind);
+        match self.kind {
+            ParseErrorKind::ExpectedToken { expected, .. } if expected.is_keyword() => {
+                closest_keyword(word, [expected])
+            }
+            ParseErrorKind::ExpectedType { .. } => {
+                closest_keyword(word, candidates.filter(|kind| kind.is_type()))
+            }
+            ParseErrorKind::UnexpectedToken { .. }
+            | ParseErrorKind::InvalidCreateTarget
+            | ParseErrorKind::InvalidDropTarget
+            | ParseErrorKind::InvalidWithTarget
+            | ParseErrorKind::MissingSemicolon => closest_keyword(word, candidates),
+            _ => None,
+        }
+    }
+
+    /// Describe the error for rendering against the `source` it was found in.
+    pub fn to_diagnostic(&self, source: &str) -> Diagnostic {
+        let label = match self.kind {
+            ParseErrorKind::ExpectedExpression { .. } => "expected an expression".to_string(),
+            ParseErrorKind::ExpectedIdentifier { .. } => "expected an identifier".to_string(),
+            ParseErrorKind::ExpectedNumeric { .. } => "expected a number".to_string(),
+            ParseErrorKind::ExpectedToken { expected, .. } => format!("expected {expected}"),
+            ParseErrorKind::ExpectedType { .. } => "expected a type".to_string(),
+            ParseErrorKind::UnexpectedToken { .. } => "not the start of a statement".to_string(),
+            ParseErrorKind::MissingSemicolon => "expected `;`".to_string(),
+            _ => String::new(),
+        };
+        let mut diagnostic = Diagnostic::error(self.kind.to_string())
+            .with_code(self.kind.code())
+            .with_label(Label::primary(self.span, label));
+        if let ParseErrorKind::ExpectedToken { expected: TokenKind::RParen, .. } = self.kind {
+            if let Some(open) = unclosed_paren(&source[..*self.span.start()]) {
+                diagnostic = diagnostic.with_label(Label::secondary(open, "unclosed `(`"));
+            }
+        }
+        if let Some(keyword) = self.suggestion(source) {
+            diagnostic = diagnostic.with_note(format!("help: did you mean {keyword}?"));
+        } else if let Some(hint) = self.kind.hint() {
+            diagnostic = diagnostic.with_note(format!("help: {hint}"));
+        }
+        diagnostic
+    }
+}
+
+// The innermost `(` left open at the end of `source`.
+fn unclosed_paren(source: &str) -> Option<Span> {
+    let mut lexer = Lexer::new(source);
+    let mut open = Vec::new();
+    loop {
+        match lexer.next_token() {
+            TokenKind::LParen => open.push(lexer.current_span()),
+            TokenKind::RParen => {
+                open.pop();
+            }
+            TokenKind::EndOfFile => return open.pop(),
+            _ => {}
+        }
+    }
+}
+
+// The bare word an error points at. Some errors are reported with an empty
+// span at the start of the offending token, so the word is read from there.
+fn word_at(source: &str, span: Span) -> &str {
+    let start = (*span.start()).min(source.len());
+    let rest = &source[start..];
+    let len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    if *span.end() > start && *span.end() != start + len {
+        return "";
+    }
+    &rest[..len]
+}
+
+// The candidate keyword closest to `word`, if it is close enough to be a
+// typo: one edit for short words and two for words of six or more letters.
+fn closest_keyword(word: &str, candidates: impl IntoIterator<Item = TokenKind>) -> Option<TokenKind> {
+    if word.len() < 3 {
+        return None;
+    }
+    let word = word.to_ascii_uppercase();
+    let max_distance = if word.len() < 6 { 1 } else { 2 };
+    candidates
+        .into_iter()
+        .filter_map(|kind| Some((edit_distance(&word, kind.keyword_text()?), kind)))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, kind)| kind)
+}
+
+// Optimal string alignment distance: Levenshtein distance where swapping
+// two adjacent characters counts as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ParseErrorKind {
+    ExpectedExpression {
+        found: TokenKind,
+    },
     ExpectedIdentifier {
         found: TokenKind,
     },
@@ -39,35 +164,133 @@ pub enum ParseErrorKind {
     UnexpectedToken {
         found: TokenKind,
     },
+    InvalidCreateTarget,
     InvalidDropTarget,
+    InvalidWithTarget,
     Lexical(LexicalErrorKind),
     MissingSemicolon,
 }
 
+impl ParseErrorKind {
+    /// The token found where the parser expected something else.
+    pub fn found(&self) -> Option<TokenKind> {
+        match *self {
+            ParseErrorKind::ExpectedExpression { found }
+            | ParseErrorKind::ExpectedIdentifier { found }
+            | ParseErrorKind::ExpectedNumeric { found }
+            | ParseErrorKind::ExpectedToken { found, .. }
+            | ParseErrorKind::ExpectedType { found }
+            | ParseErrorKind::UnexpectedToken { found } => Some(found),
+            _ => None,
+        }
+    }
+
+    /// A stable code identifying the kind of error, for looking it up in the
+    /// documentation. Codes are never reused once a kind is removed.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::ExpectedExpression { .. } => "P0001",
+            ParseErrorKind::ExpectedIdentifier { .. } => "P0002",
+            ParseErrorKind::ExpectedNumeric { .. } => "P0003",
+            ParseErrorKind::ExpectedToken { .. } => "P0004",
+            ParseErrorKind::ExpectedType { .. } => "P0005",
+            ParseErrorKind::ExpectedValue => "P0006",
+            ParseErrorKind::UnexpectedToken { .. } => "P0007",
+            ParseErrorKind::InvalidCreateTarget => "P0008",
+            ParseErrorKind::InvalidDropTarget => "P0009",
+            ParseErrorKind::InvalidWithTarget => "P0010",
+            ParseErrorKind::MissingSemicolon => "P0011",
+            ParseErrorKind::Lexical(lexical_error) => match lexical_error {
+                LexicalErrorKind::InvalidToken => "P0100",
+                LexicalErrorKind::InvalidBlob => "P0101",
+                LexicalErrorKind::InvalidNumber => "P0102",
+                LexicalErrorKind::InvalidFloat => "P0103",
+                LexicalErrorKind::InvalidInt => "P0104",
+                LexicalErrorKind::InvalidParameter => "P0105",
+                LexicalErrorKind::UnterminatedComment => "P0106",
+                LexicalErrorKind::UnterminatedIdentifier => "P0107",
+                LexicalErrorKind::UnterminatedString => "P0108",
+            },
+        }
+    }
+
+    /// How the error can usually be fixed, for kinds where that does not
+    /// depend on the surrounding source.
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            ParseErrorKind::ExpectedIdentifier { found } if found.is_keyword() => {
+                let keyword = found.keyword_text()?;
+                format!(
+                    "{found} is a keyword, write it in double quotes to use it as a name: \"{}\"",
+                    keyword.to_ascii_lowercase()
+                )
+            }
+            ParseErrorKind::ExpectedToken { expected: TokenKind::RParen, .. } => {
+                "add `)` to close the parenthesis".to_string()
+            }
+            ParseErrorKind::ExpectedType { .. } => {
+                let types: Vec<&str> = KEYWORDS
+                    .iter()
+                    .filter(|(_, kind)| kind.is_type())
+                    .map(|(keyword, _)| *keyword)
+                    .collect();
+                format!("the supported types are {}", types.join(", "))
+            }
+            ParseErrorKind::MissingSemicolon => "end each statement with `;`".to_string(),
+            ParseErrorKind::Lexical(LexicalErrorKind::InvalidBlob) => {
+                "blob literals are written as X'01AB'".to_string()
+            }
+            ParseErrorKind::Lexical(LexicalErrorKind::InvalidParameter) => {
+                "bind parameters are written as ?, ?1, :name, @name or $name".to_string()
+            }
+            ParseErrorKind::Lexical(LexicalErrorKind::UnterminatedComment) => {
+                "close the comment with `*/`".to_string()
+            }
+            ParseErrorKind::Lexical(LexicalErrorKind::UnterminatedIdentifier) => {
+                "close the identifier with the quote it starts with".to_string()
+            }
+            ParseErrorKind::Lexical(LexicalErrorKind::UnterminatedString) => {
+                "close the string with `'`, a quote inside a string is written as `''`".to_string()
+            }
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
 impl Display for ParseErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ParseErrorKind::ExpectedExpression { found } => {
+                write!(f, "Expected an expression, found {found} instead.")
+            }
             ParseErrorKind::ExpectedIdentifier { found } => {
                 write!(f, "Expected an identifier, found {found} instead.")
             }
             ParseErrorKind::ExpectedNumeric { found } => {
-                write!(f, "Expected Numeric, found {found} instead.")
+                write!(f, "Expected a number, found {found} instead.")
             }
             ParseErrorKind::ExpectedToken { found, expected } => {
-                write!(f, "Expected {expected}, Found {found}")
+                write!(f, "Expected {expected}, found {found} instead.")
             }
             ParseErrorKind::ExpectedType { found } => {
-                write!(f, "Expected A Type Token, Found {found}")
+                write!(f, "Expected a type name, found {found} instead.")
             }
             ParseErrorKind::ExpectedValue => {
                 write!(f, "Expected a value to be attached to this token")
             }
             ParseErrorKind::UnexpectedToken { found } => {
-                write!(f, "Unexpected Token {found}. Can not start statement")
+                write!(f, "Found {found} where a statement was expected.")
+            }
+            ParseErrorKind::InvalidCreateTarget => {
+                f.write_str("Expected either TABLE, INDEX or UNIQUE INDEX following CREATE")
             }
             ParseErrorKind::InvalidDropTarget => {
                 f.write_str("Expected either TABLE or INDEX following DROP")
             }
+            ParseErrorKind::InvalidWithTarget => {
+                f.write_str("Expected SELECT, INSERT, UPDATE or DELETE following WITH clause")
+            }
             ParseErrorKind::MissingSemicolon => {
                 f.write_str("Missing terminating semicolon at end of statement.")
             }
@@ -80,7 +303,7 @@ impl Display for ParseErrorKind {
 
 #[derive(Debug)]
 pub struct LexicalError {
-    kind: LexicalErrorKind,
+    pub(crate) kind: LexicalErrorKind,
 
     span: Span,
 }
@@ -89,9 +312,14 @@ impl LexicalError {
     pub fn new(kind: LexicalErrorKind, span: Span) -> Self {
         Self { kind, span }
     }
+}
 
-    pub fn error_kind(&self) -> LexicalErrorKind {
-        self.kind
+impl From<LexicalError> for ParseError {
+    fn from(error: LexicalError) -> Self {
+        ParseError {
+            kind: ParseErrorKind::Lexical(error.kind),
+            span: error.span,
+        }
     }
 }
 
@@ -107,9 +335,13 @@ pub enum LexicalErrorKind {
     #[default]
     InvalidToken,
 
+    InvalidBlob,
     InvalidNumber,
     InvalidFloat,
     InvalidInt,
+    InvalidParameter,
+    UnterminatedComment,
+    UnterminatedIdentifier,
     UnterminatedString,
 }
 
@@ -117,6 +349,13 @@ impl Display for LexicalErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LexicalErrorKind::UnterminatedString => f.write_str("Unterminated string literal"),
+            LexicalErrorKind::UnterminatedComment => f.write_str("Unterminated block comment"),
+            LexicalErrorKind::UnterminatedIdentifier => {
+                f.write_str("Unterminated quoted identifier")
+            }
+            LexicalErrorKind::InvalidBlob => {
+                f.write_str("Blob literal must contain an even number of hexadecimal digits")
+            }
             LexicalErrorKind::InvalidInt => {
                 f.write_str("Integer format not supported or is too large")
             }
@@ -124,6 +363,9 @@ impl Display for LexicalErrorKind {
                 f.write_str("Float format not supported or is too large")
             }
             LexicalErrorKind::InvalidNumber => f.write_str("This number format is not supported"),
+            LexicalErrorKind::InvalidParameter => {
+                f.write_str("Bind parameter must be a positive number or a name")
+            }
             LexicalErrorKind::InvalidToken => f.write_str("Unexpected or invalid token found"),
         }
     }
@@ -1,7 +1,7 @@
 use std::f64;
 use std::str::FromStr;
 
-use ast::name::Name;
+use ast::{name::Name, ParameterKind};
 use source_index::{location::Location, span::Span};
 
 use crate::{
@@ -47,10 +47,7 @@ impl<'src> Lexer<'src> {
         self.cursor.start_token();
         self.current_value = TokenValue::None;
         self.current_kind = self.lex_token();
-
-        if !matches!(self.current_kind(), TokenKind::Unknown) {
-            self.current_span = self.token_range();
-        }
+        self.current_span = self.token_range();
         self.current_kind
     }
 
@@ -94,11 +91,8 @@ impl<'src> Lexer<'src> {
     }
 
     fn skip_whitespace(&mut self) {
-        loop {
-            match self.cursor.first() {
-                ' ' | '\n' | '\t' => self.cursor.bump(),
-                _ => break,
-            };
+        while let ' ' | '\n' | '\t' = self.cursor.first() {
+            self.cursor.bump();
         }
     }
 
@@ -109,9 +103,13 @@ impl<'src> Lexer<'src> {
     }
 
     fn lex_ascii(&mut self, c: char) -> TokenKind {
-        let token = match c {
-            c if is_identifier_start(c) => self.lex_identifier_or_keyword(c),
+        match c {
+            'x' | 'X' if self.cursor.first() == '\'' => self.lex_blob(),
+            c if is_identifier_start(c) || is_identifier_quote(c) => {
+                self.lex_identifier_or_keyword(c)
+            }
             '0'..='9' => self.lex_number(c),
+            '.' if self.cursor.first().is_ascii_digit() => self.lex_number(c),
             '\'' => self.lex_string(),
             '-' => {
                 if self.cursor.eat_char('-') {
@@ -120,7 +118,12 @@ impl<'src> Lexer<'src> {
                 TokenKind::Minus
             }
             '*' => TokenKind::Star,
-            '/' => TokenKind::Slash,
+            '/' => {
+                if self.cursor.eat_char('*') {
+                    return self.lex_block_comment();
+                }
+                TokenKind::Slash
+            }
             '%' => TokenKind::Percent,
             '=' => TokenKind::Equals,
             '>' => {
@@ -151,165 +154,125 @@ impl<'src> Lexer<'src> {
             ')' => TokenKind::RParen,
             ',' => TokenKind::Comma,
             ';' => TokenKind::Semicolon,
+            '?' | '$' | ':' | '@' => self.lex_parameter(c),
             _ => self.push_error(LexicalError::new(
                 LexicalErrorKind::InvalidToken,
                 self.token_range(),
             )),
-        };
-        token
+        }
     }
 
+    // Lex an identifier, a table qualified identifier or a keyword. Quoted
+    // identifiers keep their case and are never treated as keywords.
     fn lex_identifier_or_keyword(&mut self, c: char) -> TokenKind {
-        self.cursor.eat_while(|c| is_identifier_rest(c));
+        let first = match self.lex_identifier_part(c) {
+            Ok(name) => name,
+            Err(kind) => return self.push_error(LexicalError::new(kind, self.token_range())),
+        };
         // Handle table qualified Identifier. Let parser figure this out.
         if self.cursor.eat_char('.') {
             // table.*
             if self.cursor.eat_char('*') {
-                let text = self.token_text();
-                // Discard '.*' and just store table name
-                self.current_value =
-                    TokenValue::TableAll(Name::new(text[0..text.len() - 2].to_string()));
+                self.current_value = TokenValue::TableAll(first);
                 return TokenKind::TableAll;
-            } else if is_identifier_start(c) {
-                // table.column
-                self.cursor.eat_if(|c| is_identifier_start(c));
-                self.cursor.eat_while(|c| is_identifier_rest(c));
-
-                let text = self.token_text();
-                let (table, col) = text.split_once('.').unwrap();
-
-                self.current_value =
-                    TokenValue::TableCol(Name::new(table.to_string()), Name::new(col.to_string()));
-                return TokenKind::TableCol;
-            } else {
-                return self.push_error(LexicalError::new(
-                    LexicalErrorKind::InvalidToken,
-                    self.token_range(),
-                ));
             }
+            // table.column
+            let second = match self.cursor.bump() {
+                Some(c) if is_identifier_start(c) || is_identifier_quote(c) => {
+                    self.lex_identifier_part(c)
+                }
+                _ => Err(LexicalErrorKind::InvalidToken),
+            };
+            return match second {
+                Ok(second) => {
+                    self.current_value = TokenValue::TableCol(first, second);
+                    TokenKind::TableCol
+                }
+                Err(kind) => self.push_error(LexicalError::new(kind, self.token_range())),
+            };
+        }
+        if is_identifier_quote(c) {
+            self.current_value = TokenValue::Name(first);
+            return TokenKind::Name;
         }
         let text = self.token_text();
-        match text.to_uppercase().as_str() {
-            "ABORT" => TokenKind::Abort,
-            "ACTION" => TokenKind::Action,
-            "ADD" => TokenKind::Add,
-            "ALL" => TokenKind::All,
-            "ALTER" => TokenKind::Alter,
-            "AND" => TokenKind::And,
-            "AS" => TokenKind::As,
-            "ASC" => TokenKind::Asc,
-            "BEGIN" => TokenKind::Begin,
-            "BETWEEN" => TokenKind::Between,
-            "BY" => TokenKind::By,
-            "CASCADE" => TokenKind::Cascade,
-            "CHAR" => TokenKind::Char,
-            "CHECK" => TokenKind::Check,
-            "COLLATE" => TokenKind::Collate,
-            "COLUMN" => TokenKind::Column,
-            "COMMIT" => TokenKind::Commit,
-            "CONFLICT" => TokenKind::Conflict,
-            "CONSTRAINT" => TokenKind::Constraint,
-            "CREATE" => TokenKind::Create,
-            "CROSS" => TokenKind::Cross,
-            "DEFAULT" => TokenKind::Default,
-            "DELETE" => TokenKind::Delete,
-            "DESC" => TokenKind::Desc,
-            "DISTINCT" => TokenKind::Distinct,
-            "DROP" => TokenKind::Drop,
-            "EXCEPT" => TokenKind::Except,
-            "EXISTS" => TokenKind::Exists,
-            "FAIL" => TokenKind::Fail,
-            "FROM" => TokenKind::From,
-            "FULL" => TokenKind::Full,
-            "GROUP" => TokenKind::Group,
-            "HAVING" => TokenKind::Having,
-            "IF" => TokenKind::If,
-            "IGNORE" => TokenKind::Ignore,
-            "IN" => TokenKind::In,
-            "INDEX" => TokenKind::Index,
-            "INDEXED" => TokenKind::Indexed,
-            "INNER" => TokenKind::Inner,
-            "INSERT" => TokenKind::Insert,
-            "INTEGER" => TokenKind::Integer,
-            "IS" => TokenKind::Is,
-            "JOIN" => TokenKind::Join,
-            "KEY" => TokenKind::Key,
-            "LEFT" => TokenKind::Left,
-            "LIKE" => TokenKind::Like,
-            "LIMIT" => TokenKind::Limit,
-            "NATURAL" => TokenKind::Natural,
-            "NO" => TokenKind::No,
-            "NOT" => TokenKind::Not,
-            "NULL" => TokenKind::Null,
-            "NUMERIC" => TokenKind::Numeric,
-            "OFFSET" => TokenKind::Offset,
-            "ON" => TokenKind::On,
-            "OR" => TokenKind::Or,
-            "ORDER" => TokenKind::Order,
-            "OUTER" => TokenKind::Outer,
-            "PRIMARY" => TokenKind::Primary,
-            "REFERENCES" => TokenKind::References,
-            "REINDEX" => TokenKind::Reindex,
-            "RELEASE" => TokenKind::Release,
-            "RENAME" => TokenKind::Rename,
-            "REPLACE" => TokenKind::Replace,
-            "RESTRICT" => TokenKind::Restrict,
-            "RIGHT" => TokenKind::Right,
-            "ROLLBACK" => TokenKind::Rollback,
-            "SAVEPOINT" => TokenKind::Savepoint,
-            "SELECT" => TokenKind::Select,
-            "SERIAL" => TokenKind::Serial,
-            "SET" => TokenKind::Set,
-            "TABLE" => TokenKind::Table,
-            "TO" => TokenKind::To,
-            "TRANSACTION" => TokenKind::Transaction,
-            "UNION" => TokenKind::Union,
-            "UNIQUE" => TokenKind::Unique,
-            "UPDATE" => TokenKind::Update,
-            "USING" => TokenKind::Using,
-            "VACUUM" => TokenKind::Vacuum,
-            "VALUES" => TokenKind::Values,
-            "VARCHAR" => TokenKind::Varchar,
-            "WHERE" => TokenKind::Where,
-            _ => {
-                self.current_value = TokenValue::Name(Name::new(text.to_string()));
-                TokenKind::Name
-            }
+        if let Some(keyword) = TokenKind::from_keyword(text) {
+            return keyword;
         }
+        self.current_value = TokenValue::Name(Name::new(text.to_string()));
+        TokenKind::Name
     }
 
-    fn lex_number(&mut self, c: char) -> TokenKind {
+    // Lex a bare or quoted identifier whose first character has already been
+    // consumed.
+    fn lex_identifier_part(&mut self, c: char) -> Result<Name, LexicalErrorKind> {
+        if is_identifier_quote(c) {
+            let text = self
+                .lex_quoted(c)
+                .ok_or(LexicalErrorKind::UnterminatedIdentifier)?;
+            return Ok(Name::new(text));
+        }
+        let mut owned = String::from(c);
+        while let Some(c) = self.cursor.eat_if(is_identifier_rest) {
+            owned.push(c);
+        }
+        Ok(Name::new(owned))
+    }
+
+    // Lex the rest of a quoted token after its opening quote. A doubled quote
+    // stands for a single quote character. Returns `None` if the closing quote
+    // is missing.
+    fn lex_quoted(&mut self, quote: char) -> Option<String> {
         let mut owned = String::new();
-        let mut is_float = false;
+        loop {
+            match self.cursor.bump()? {
+                c if c == quote => {
+                    if !self.cursor.eat_char(quote) {
+                        return Some(owned);
+                    }
+                    owned.push(quote);
+                }
+                c => owned.push(c),
+            }
+        }
+    }
 
-        // float with leading 0 (0.xxxx)
-        if matches!(c, '0') {
+    fn lex_number(&mut self, c: char) -> TokenKind {
+        if c == '0' && matches!(self.cursor.first(), 'x' | 'X') {
+            return self.lex_hex_integer();
+        }
+        let mut owned = String::from(c);
+        // `.5` has already consumed its decimal point
+        let mut is_float = c == '.';
+        while let Some(digit) = self.cursor.eat_if(is_digit) {
+            owned.push(digit);
+        }
+        // check for float point
+        if !is_float && self.cursor.eat_char('.') {
             is_float = true;
-            owned.push(c);
-            if !self.cursor.eat_char('.') {
+            owned.push('.');
+            // add digits after point
+            while let Some(digit) = self.cursor.eat_if(is_digit) {
+                owned.push(digit);
+            }
+        }
+        // scientific notation, 1e10, 2.5E-3
+        if let Some(e) = self.cursor.eat_if(|c| matches!(c, 'e' | 'E')) {
+            is_float = true;
+            owned.push(e);
+            if let Some(sign) = self.cursor.eat_if(|c| matches!(c, '+' | '-')) {
+                owned.push(sign);
+            }
+            let Some(digit) = self.cursor.eat_if(is_digit) else {
                 return self.push_error(LexicalError::new(
                     LexicalErrorKind::InvalidNumber,
                     self.token_range(),
                 ));
-            }
-            owned.push('.');
-            while let Some(digit) = self.cursor.eat_if(|c| is_digit(c)) {
-                owned.push(digit);
-            }
-        } else {
-            owned.push(c);
-            while let Some(digit) = self.cursor.eat_if(|c| is_digit(c)) {
+            };
+            owned.push(digit);
+            while let Some(digit) = self.cursor.eat_if(is_digit) {
                 owned.push(digit);
             }
-            // check for float point
-            if self.cursor.eat_char('.') {
-                is_float = true;
-                owned.push('.');
-                // add digits after point
-                while let Some(digit) = self.cursor.eat_if(|c| is_digit(c)) {
-                    owned.push(digit);
-                }
-            }
         }
         if is_float {
             let Ok(val) = f64::from_str(&owned) else {
@@ -331,38 +294,119 @@ impl<'src> Lexer<'src> {
         TokenKind::Int
     }
 
+    // Lex `0x` followed by hex digits. Like SQLite, values up to 64 bits are
+    // accepted and reinterpreted as a signed integer.
+    fn lex_hex_integer(&mut self) -> TokenKind {
+        self.cursor.bump();
+        let mut owned = String::new();
+        while let Some(digit) = self.cursor.eat_if(|c| c.is_ascii_hexdigit()) {
+            owned.push(digit);
+        }
+        if owned.is_empty() {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::InvalidNumber,
+                self.token_range(),
+            ));
+        }
+        let Ok(val) = u64::from_str_radix(&owned, 16) else {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::InvalidInt,
+                self.token_range(),
+            ));
+        };
+        self.current_value = TokenValue::Int(val as i64);
+        TokenKind::Int
+    }
+
+    // Lex `X'<hex digits>'` after the leading X.
+    fn lex_blob(&mut self) -> TokenKind {
+        self.cursor.bump();
+        let Some(text) = self.lex_quoted('\'') else {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::UnterminatedString,
+                self.token_range(),
+            ));
+        };
+        if text.len() % 2 != 0 || !text.chars().all(|c| c.is_ascii_hexdigit()) {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::InvalidBlob,
+                self.token_range(),
+            ));
+        }
+        let value = (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect();
+        self.current_value = TokenValue::Blob(value);
+        TokenKind::Blob
+    }
+
     fn lex_comment(&mut self) -> TokenKind {
         self.cursor.eat_while(|c| !matches!(c, '\n' | '\r'));
         TokenKind::Comment
     }
 
-    fn lex_string(&mut self) -> TokenKind {
-        let mut num_backslashes = 0;
+    // Lex a `/* ... */` comment after its opening `/*`. Block comments do not
+    // nest, the first `*/` closes the comment.
+    fn lex_block_comment(&mut self) -> TokenKind {
         loop {
             match self.cursor.bump() {
-                Some(c) => match c {
-                    '\\' => num_backslashes += 1,
-                    '\'' => {
-                        if num_backslashes % 2 == 0 {
-                            break;
-                        }
-                        num_backslashes = 0
-                    }
-                    _ => num_backslashes = 0,
-                },
+                Some('*') if self.cursor.eat_char('/') => return TokenKind::Comment,
+                Some(_) => {}
                 None => {
                     return self.push_error(LexicalError::new(
-                        LexicalErrorKind::UnterminatedString,
+                        LexicalErrorKind::UnterminatedComment,
                         self.token_range(),
                     ))
                 }
             }
         }
-        let text = self.token_text();
-        self.current_value = TokenValue::String(text.to_string());
+    }
+
+    fn lex_string(&mut self) -> TokenKind {
+        let Some(text) = self.lex_quoted('\'') else {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::UnterminatedString,
+                self.token_range(),
+            ));
+        };
+        self.current_value = TokenValue::String(text);
         TokenKind::String
     }
 
+    // Lex a bind parameter. `?` and `$` may be followed by a number, `$`, `:`
+    // and `@` may be followed by a name which keeps its prefix.
+    fn lex_parameter(&mut self, c: char) -> TokenKind {
+        if matches!(c, '?' | '$') && self.cursor.first().is_ascii_digit() {
+            self.cursor.eat_while(|c| c.is_ascii_digit());
+            let text = self.token_text();
+            return match text[1..].parse::<u32>() {
+                Ok(number) if number > 0 => {
+                    self.current_value = TokenValue::Parameter(ParameterKind::Numbered(number));
+                    TokenKind::Parameter
+                }
+                _ => self.push_error(LexicalError::new(
+                    LexicalErrorKind::InvalidParameter,
+                    self.token_range(),
+                )),
+            };
+        }
+        if c == '?' {
+            self.current_value = TokenValue::Parameter(ParameterKind::Anonymous);
+            return TokenKind::Parameter;
+        }
+        if self.cursor.eat_if(is_identifier_start).is_none() {
+            return self.push_error(LexicalError::new(
+                LexicalErrorKind::InvalidParameter,
+                self.token_range(),
+            ));
+        }
+        self.cursor.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        let name = Name::new(self.token_text().to_string());
+        self.current_value = TokenValue::Parameter(ParameterKind::Named(name));
+        TokenKind::Parameter
+    }
+
     fn token_text(&self) -> &'src str {
         &self.source[self.token_range()]
     }
@@ -378,6 +422,12 @@ impl<'src> Lexer<'src> {
         Location::new(self.source.len()) - self.cursor.text_len()
     }
 
+    /// Consume the lexer and return the errors it encountered, in source
+    /// order.
+    pub(crate) fn finish(self) -> Vec<LexicalError> {
+        self.errors
+    }
+
     // Create a checkpoint of the lexer
     pub fn checkpoint(&self) -> LexerCheckpoint {
         LexerCheckpoint {
@@ -422,12 +472,16 @@ fn is_identifier_start(c: char) -> bool {
     matches!(c, 'a'..='z' | 'A'..='Z' | '_')
 }
 
+fn is_identifier_quote(c: char) -> bool {
+    matches!(c, '"' | '`')
+}
+
 fn is_identifier_rest(c: char) -> bool {
     matches!(c, 'a'..='z' | 'A'..='Z' | '_' | '0'..='9' | '-')
 }
 
 fn is_digit(c: char) -> bool {
-    matches!(c, '0'..='9')
+    c.is_ascii_digit()
 }
 
 #[cfg(test)]
@@ -477,6 +531,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn every_keyword_lexes() {
+        // keywords are looked up with a binary search
+        assert!(crate::tokens::KEYWORDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        for (keyword, kind) in crate::tokens::KEYWORDS {
+            let lower = keyword.to_lowercase();
+            assert_eq!(Lexer::new(keyword).next_token(), *kind);
+            assert_eq!(Lexer::new(&lower).next_token(), *kind);
+            assert!(kind.is_keyword(), "{keyword}");
+        }
+    }
+
     #[test]
     fn simple_identifier() {
         let source = "col";
@@ -580,10 +646,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn block_comment_token() {
+        let source = "/* header
+            * SELECT ; */ 8 / 2 /* a /* b */ ;";
+        let mut lexer = Lexer::new(source);
+        assert_eq!(lexer.next_token(), TokenKind::Comment);
+        assert_eq!(lexer.current_span(), Span::new(Location::new(0), Location::new(35)));
+        assert_eq!(lexer.next_token(), TokenKind::Int);
+        assert_eq!(lexer.next_token(), TokenKind::Slash);
+        assert_eq!(lexer.next_token(), TokenKind::Int);
+        // block comments do not nest
+        assert_eq!(lexer.next_token(), TokenKind::Comment);
+        assert_eq!(lexer.next_token(), TokenKind::Semicolon);
+        assert!(lexer.errors.is_empty());
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        let mut lexer = Lexer::new("SELECT /* never closed *");
+        assert_eq!(lexer.next_token(), TokenKind::Select);
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(
+            lexer.errors.first().map(|error| error.kind),
+            Some(LexicalErrorKind::UnterminatedComment)
+        );
+    }
+
     #[test]
     fn string_literal() {
         let source = "'string lit'";
-        let _expected = TokenValue::String(String::from(source));
+        let _expected = TokenValue::String(String::from("string lit"));
         let mut lexer = Lexer::new(source);
         let token = lexer.next_token();
         assert!(
@@ -610,16 +703,16 @@ mod tests {
         let e = lexer.errors.first().unwrap();
         assert_eq!(
             _expected,
-            e.error_kind(),
+            e.kind,
             "Did not get UnterminatedString, got {:?}",
-            e.error_kind()
+            e.kind
         );
     }
 
     #[test]
     fn escaped_string_terminator() {
-        let source = "'string\\'s are lit'";
-        let _expected = TokenValue::String(String::from(source));
+        let source = "'string''s are lit'";
+        let _expected = TokenValue::String(String::from("string's are lit"));
         let mut lexer = Lexer::new(source);
         let token = lexer.next_token();
         assert!(
@@ -634,9 +727,10 @@ mod tests {
     }
 
     #[test]
-    fn double_bkslh_string() {
-        let source = "'string\\\\'";
-        let _expected = TokenValue::String(String::from(source));
+    fn backslash_string() {
+        // backslashes have no special meaning
+        let source = "'string\\'";
+        let _expected = TokenValue::String(String::from("string\\"));
         let mut lexer = Lexer::new(source);
         let token = lexer.next_token();
         assert!(
@@ -660,4 +754,99 @@ mod tests {
             "BEGIN token not found. Got {token}"
         );
     }
+
+    #[test]
+    fn parameters() {
+        let source = "? ?12 $3 :id @name $name ?0 :";
+        let mut lexer = Lexer::new(source);
+        let expected = [
+            ParameterKind::Anonymous,
+            ParameterKind::Numbered(12),
+            ParameterKind::Numbered(3),
+            ParameterKind::Named(Name::new(":id".to_string())),
+            ParameterKind::Named(Name::new("@name".to_string())),
+            ParameterKind::Named(Name::new("$name".to_string())),
+        ];
+        for kind in expected {
+            assert_eq!(lexer.next_token(), TokenKind::Parameter);
+            assert_eq!(lexer.take_value(), TokenValue::Parameter(kind));
+        }
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(lexer.errors.len(), 2);
+        assert_eq!(lexer.errors[0].kind, LexicalErrorKind::InvalidParameter);
+    }
+
+    #[test]
+    fn quoted_identifiers() {
+        let source = "\"Select\" `my``col` \"t\".\"C\"\"1\" t.\"*\" \"t\".* \"open";
+        let mut lexer = Lexer::new(source);
+        assert_eq!(lexer.next_token(), TokenKind::Name);
+        assert_eq!(lexer.take_value(), TokenValue::Name(Name::new("Select".to_string())));
+        assert_eq!(lexer.next_token(), TokenKind::Name);
+        assert_eq!(lexer.take_value(), TokenValue::Name(Name::new("my`col".to_string())));
+        assert_eq!(lexer.next_token(), TokenKind::TableCol);
+        assert_eq!(
+            lexer.take_value(),
+            TokenValue::TableCol(Name::new("t".to_string()), Name::new("C\"1".to_string()))
+        );
+        assert_eq!(lexer.next_token(), TokenKind::TableCol);
+        assert_eq!(
+            lexer.take_value(),
+            TokenValue::TableCol(Name::new("t".to_string()), Name::new("*".to_string()))
+        );
+        assert_eq!(lexer.next_token(), TokenKind::TableAll);
+        assert_eq!(lexer.take_value(), TokenValue::TableAll(Name::new("t".to_string())));
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(
+            lexer.errors.first().map(|error| error.kind),
+            Some(LexicalErrorKind::UnterminatedIdentifier)
+        );
+    }
+
+    #[test]
+    fn number_formats() {
+        let source = "0 007 1e10 2.5E-3 .5 3. 0x1F 0XffFFffFFffFFffFF";
+        let mut lexer = Lexer::new(source);
+        let expected = [
+            TokenValue::Int(0),
+            TokenValue::Int(7),
+            TokenValue::Float(1e10),
+            TokenValue::Float(2.5e-3),
+            TokenValue::Float(0.5),
+            TokenValue::Float(3.0),
+            TokenValue::Int(31),
+            TokenValue::Int(-1),
+        ];
+        for value in expected {
+            let token = lexer.next_token();
+            assert!(matches!(token, TokenKind::Int | TokenKind::Float), "got {token}");
+            assert_eq!(lexer.take_value(), value);
+        }
+        assert_eq!(lexer.next_token(), TokenKind::EndOfFile);
+
+        for invalid in ["1e", "2E+", "0x"] {
+            let mut lexer = Lexer::new(invalid);
+            assert_eq!(lexer.next_token(), TokenKind::Unknown, "{invalid} should not lex");
+            assert_eq!(lexer.errors[0].kind, LexicalErrorKind::InvalidNumber);
+        }
+    }
+
+    #[test]
+    fn blob_literals() {
+        let source = "X'ABcd01' x'' x'ABC' X'AG'";
+        let mut lexer = Lexer::new(source);
+        assert_eq!(lexer.next_token(), TokenKind::Blob);
+        assert_eq!(lexer.take_value(), TokenValue::Blob(vec![0xab, 0xcd, 0x01]));
+        assert_eq!(lexer.next_token(), TokenKind::Blob);
+        assert_eq!(lexer.take_value(), TokenValue::Blob(Vec::new()));
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(lexer.next_token(), TokenKind::Unknown);
+        assert_eq!(lexer.errors.len(), 2);
+        assert!(lexer.errors.iter().all(|e| e.kind == LexicalErrorKind::InvalidBlob));
+
+        // a bare x is still an identifier
+        let mut lexer = Lexer::new("x");
+        assert_eq!(lexer.next_token(), TokenKind::Name);
+    }
 }
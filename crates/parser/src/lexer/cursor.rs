@@ -9,8 +9,6 @@ pub(super) struct Cursor<'src> {
     chars: Chars<'src>,
 
     source_length: Location,
-
-    current: Location,
 }
 
 impl<'src> Cursor<'src> {
@@ -18,7 +16,6 @@ impl<'src> Cursor<'src> {
         Self {
             chars: source.chars(),
             source_length: Location::new(source.len()),
-            current: Location::new(0),
         }
     }
 
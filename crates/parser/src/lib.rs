@@ -1,6 +1,8 @@
-use crate::errors::ParseError;
-use crate::tokens::Token;
+use std::ops::Deref;
+
+use ast::name::Name;
 use ast::Stmts;
+use source_index::span::Span;
 use parser::Parser;
 pub mod parser;
 
@@ -9,6 +11,9 @@ mod lexer;
 mod token_source;
 mod tokens;
 
+pub use crate::errors::{LexicalErrorKind, ParseError, ParseErrorKind};
+pub use crate::tokens::{Token, TokenKind};
+
 pub fn parse_stmts(source: &str) -> Result<Parsed, Vec<ParseError>> {
     Parser::new(source).parse().into_result()
 }
@@ -18,6 +23,8 @@ pub fn parse_stmts(source: &str) -> Result<Parsed, Vec<ParseError>> {
 pub struct Parsed {
     pub stmts: Stmts,
     tokens: Tokens,
+    comments: Vec<Comment>,
+    parameters: Vec<Vec<Parameter>>,
     errors: Vec<ParseError>,
 }
 
@@ -27,6 +34,17 @@ impl Parsed {
         &self.tokens
     }
 
+    /// Returns all comments in source order.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Returns the bind parameters expected by the statement at `index` in
+    /// [`Parsed::stmts`], ordered by parameter index.
+    pub fn parameters(&self, index: usize) -> &[Parameter] {
+        self.parameters.get(index).map_or(&[], Vec::as_slice)
+    }
+
     /// Returns a list of syntax errors found during parsing.
     pub fn errors(&self) -> &[ParseError] {
         &self.errors
@@ -52,6 +70,32 @@ impl Parsed {
     }
 }
 
+/// A line or block comment kept from the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comment {
+    pub span: Span,
+    pub placement: CommentPlacement,
+}
+
+/// Where a comment sits relative to the surrounding statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentPlacement {
+    /// On its own line, attached to the code that follows it.
+    Leading,
+    /// After code on the same line, attached to the code before it.
+    Trailing,
+}
+
+/// A bind parameter expected by a statement. Parameters sharing an index
+/// are reported once.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameter {
+    /// 1-based index the value is bound to.
+    pub index: u32,
+    /// Name of the parameter including its prefix, for named parameters.
+    pub name: Option<Name>,
+}
+
 /// Tokens represents a vector of lexed [`Token`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tokens {
@@ -63,3 +107,11 @@ impl Tokens {
         Tokens { raw }
     }
 }
+
+impl Deref for Tokens {
+    type Target = [Token];
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
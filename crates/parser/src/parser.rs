@@ -2,14 +2,15 @@ use ast::{Stmt, Stmts};
 use source_index::location::Location;
 use source_index::span::{Span, Spanned};
 
-use crate::errors::{ParseError, ParseErrorKind};
+use crate::errors::{LexicalError, ParseError, ParseErrorKind};
 
 use crate::token_source::TokenSource;
 use crate::tokens::{TokenKind, TokenValue};
-use crate::{Parsed, Tokens};
+use crate::{Parameter, Parsed, Tokens};
 
 mod clause;
 mod expression;
+mod recovery;
 mod statement;
 
 pub struct Parser<'src> {
@@ -20,6 +21,12 @@ pub struct Parser<'src> {
 
     errors: Vec<ParseError>,
 
+    // bind parameters seen in the current statement, ordered by index
+    parameters: Vec<Parameter>,
+
+    // bind parameters of each finished statement
+    stmt_parameters: Vec<Vec<Parameter>>,
+
     prev_token_end: Location,
 
     start_offset: Location,
@@ -32,6 +39,8 @@ impl<'src> Parser<'src> {
         Self {
             source,
             errors: Vec::new(),
+            parameters: Vec::new(),
+            stmt_parameters: Vec::new(),
             tokens,
             prev_token_end: Location::new(0),
             start_offset: Location::new(0),
@@ -58,19 +67,18 @@ impl<'src> Parser<'src> {
         self.tokens.current_span()
     }
 
+    // Record an error. Errors found while recovering are independent of the
+    // ones before them, only a repeat of the last error is dropped.
     fn add_error<T>(&mut self, error: ParseErrorKind, span: T)
     where
         T: Spanned,
     {
-        let is_same_location = self
-            .errors
-            .last()
-            .is_some_and(|last| last.span.start() == span.start());
-        if !is_same_location {
-            self.errors.push(ParseError {
-                kind: error,
-                span: span.span(),
-            })
+        let error = ParseError {
+            kind: error,
+            span: span.span(),
+        };
+        if self.errors.last() != Some(&error) {
+            self.errors.push(error)
         }
     }
 
@@ -118,7 +126,22 @@ impl<'src> Parser<'src> {
             },
             self.current_token_span()
         );
-        return false;
+        false
+    }
+
+    /// Consume the current token if it is of given kind, otherwise return an
+    /// [`ParseErrorKind::ExpectedToken`] error spanning the current token.
+    fn expect_token(&mut self, kind: TokenKind) -> Result<(), ParseError> {
+        if self.eat(kind) {
+            return Ok(());
+        }
+        Err(ParseError {
+            kind: ParseErrorKind::ExpectedToken {
+                found: self.current_token_kind(),
+                expected: kind,
+            },
+            span: self.current_token_span(),
+        })
     }
 
     // consume tokens until the current kind is found.
@@ -140,14 +163,16 @@ impl<'src> Parser<'src> {
             "Parser should be at end of file!"
         );
 
-        let errors = self.errors;
-        let tokens = self.tokens.finish();
+        let (tokens, comments, lexical_errors) = self.tokens.finish();
+        let errors = merge_errors(lexical_errors, self.errors);
 
-        return Parsed {
+        Parsed {
             stmts,
             tokens: Tokens::new(tokens),
+            comments,
+            parameters: self.stmt_parameters,
             errors,
-        };
+        }
     }
 
     fn parse_stmts(&mut self) -> Stmts {
@@ -178,9 +203,28 @@ impl<'src> Parser<'src> {
     }
 }
 
+// Merge the lexer's errors into the parser's in source order. A parser error
+// at a malformed token, which lexes as `Unknown`, or within one only repeats
+// the lexical error that explains it.
+fn merge_errors(lexical: Vec<LexicalError>, parser: Vec<ParseError>) -> Vec<ParseError> {
+    let lexical: Vec<ParseError> = lexical.into_iter().map(ParseError::from).collect();
+    let mut errors: Vec<ParseError> = parser
+        .into_iter()
+        .filter(|error| {
+            error.kind.found() != Some(TokenKind::Unknown) && !lexical.iter().any(|lexical| {
+                lexical.span.start() <= error.span.start() && error.span.start() <= lexical.span.end()
+            })
+        })
+        .collect();
+    errors.extend(lexical);
+    errors.sort_by_key(|error| error.span.start());
+    errors
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{CommentPlacement, LexicalErrorKind};
 
     #[test]
     fn eof_ending() {
@@ -202,4 +246,372 @@ mod test {
             parser.current_token_kind()
         );
     }
+
+    #[test]
+    fn statement_parameters() {
+        let source = "SELECT * FROM t1 WHERE c1 = ? AND c2 = :name AND c3 = ?5 AND c4 = :name AND c5 = ?;
+            SELECT ?2;
+            SELECT 1;";
+        let parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid());
+        assert_eq!(
+            parsed.parameters(0),
+            [
+                Parameter { index: 1, name: None },
+                Parameter { index: 2, name: Some(ast::name::Name::new(":name".to_string())) },
+                Parameter { index: 5, name: None },
+                Parameter { index: 6, name: None },
+            ]
+        );
+        assert_eq!(parsed.parameters(1), [Parameter { index: 2, name: None }]);
+        assert!(parsed.parameters(2).is_empty());
+        assert!(parsed.parameters(3).is_empty());
+
+        let ast::Stmt::Select(select) = &parsed.stmts.body[0] else {
+            panic!("expected select statement");
+        };
+        let Some(ast::Expr::BoolOp(and)) = &select.where_clause else {
+            panic!("expected AND");
+        };
+        let ast::Expr::Compare(compare) = and.right.as_ref() else {
+            panic!("expected comparison");
+        };
+        assert!(matches!(
+            *compare.right,
+            ast::Expr::Parameter(ast::ExprParameter { kind: ast::ParameterKind::Anonymous, index: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn comment_placement() {
+        let source = "/* migration 42 */
+            -- create the table
+            BEGIN; -- start
+            SELECT c1, /* inline */ c2
+                -- between lines
+                FROM t1; /* done */ -- really
+            COMMIT;";
+        let parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid());
+        let placements: Vec<CommentPlacement> =
+            parsed.comments().iter().map(|comment| comment.placement).collect();
+        assert_eq!(
+            placements,
+            [
+                CommentPlacement::Leading,
+                CommentPlacement::Leading,
+                CommentPlacement::Trailing,
+                CommentPlacement::Trailing,
+                CommentPlacement::Leading,
+                CommentPlacement::Trailing,
+                CommentPlacement::Trailing,
+            ]
+        );
+        assert_eq!(parsed.comments()[0].span, Span::new(Location::new(0), Location::new(18)));
+    }
+
+    #[test]
+    fn visitor_walks_nested_nodes() {
+        use ast::visitor::{self, Visitor};
+
+        #[derive(Default)]
+        struct Collector {
+            columns: Vec<String>,
+            selects: usize,
+            parameters: usize,
+        }
+
+        impl Visitor for Collector {
+            fn visit_stmt_select(&mut self, stmt: &ast::StmtSelect) {
+                self.selects += 1;
+                visitor::walk_stmt_select(self, stmt);
+            }
+
+            fn visit_expr(&mut self, expr: &ast::Expr) {
+                if let ast::Expr::Column(column) = expr {
+                    self.columns.push(column.column.id.to_string());
+                }
+                visitor::walk_expr(self, expr);
+            }
+
+            fn visit_parameter(&mut self, _parameter: &ast::ExprParameter) {
+                self.parameters += 1;
+            }
+        }
+
+        let source = "WITH cte AS (SELECT c1 FROM t1 WHERE c2 = ?)
+            SELECT SUM(c3) OVER (PARTITION BY c4), CASE WHEN c5 THEN c6 END
+            FROM cte JOIN (SELECT c7 FROM t2) AS sub ON cte.c1 = sub.c7
+            WHERE c8 IN (SELECT c9 FROM t3 LIMIT :n);
+            UPDATE t1 SET c10 = (SELECT MAX(c11) FROM t4) RETURNING c12;";
+        let parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid());
+
+        let mut collector = Collector::default();
+        for stmt in &parsed.stmts.body {
+            collector.visit_stmt(stmt);
+        }
+        assert_eq!(
+            collector.columns,
+            [
+                "c1", "c2", "c3", "c4", "c5", "c6", "c7", "c1", "c7", "c8", "c9", "c11", "c12"
+            ]
+        );
+        assert_eq!(collector.selects, 5);
+        assert_eq!(collector.parameters, 2);
+    }
+
+    #[test]
+    fn visitor_mut_rewrite_round_trip() {
+        use ast::visitor_mut::{self, VisitorMut};
+
+        struct Normalise;
+
+        impl VisitorMut for Normalise {
+            fn visit_identifier(&mut self, identifier: &mut ast::Identifier) {
+                identifier.id = ast::name::Name::new(identifier.id.as_str().to_lowercase());
+            }
+        }
+
+        struct BindParameters(Vec<i64>);
+
+        impl VisitorMut for BindParameters {
+            fn visit_expr(&mut self, expr: &mut ast::Expr) {
+                if let ast::Expr::Parameter(parameter) = expr {
+                    let value = self.0[parameter.index as usize - 1];
+                    *expr = ast::Expr::LiteralValue(ast::LiteralValue::IntLiteral(
+                        ast::IntLiteral { span: parameter.span, value },
+                    ));
+                }
+                visitor_mut::walk_expr(self, expr);
+            }
+        }
+
+        let source = "WITH Recent AS (SELECT C1 FROM T1 WHERE C2 > ?)
+            SELECT COUNT(*) FROM Recent JOIN T2 ON Recent.C1 = T2.C1 WHERE T2.C3 IN (?2, ?);
+            UPDATE T1 SET C1 = ? WHERE C2 = (SELECT MAX(C2) FROM T1);";
+        let expected = "WITH recent AS (SELECT c1 FROM t1 WHERE c2 > 7)
+            SELECT count(*) FROM recent JOIN t2 ON recent.c1 = t2.c1 WHERE t2.c3 IN (88, 9);
+            UPDATE t1 SET c1 = 7 WHERE c2 = (SELECT max(c2) FROM t1);";
+
+        let mut parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid());
+        let mut bind = BindParameters(vec![7, 88, 9]);
+        for stmt in &mut parsed.stmts.body {
+            Normalise.visit_stmt(stmt);
+            bind.visit_stmt(stmt);
+        }
+
+        let reparsed = Parser::new(expected).parse();
+        assert!(reparsed.is_valid());
+        assert_eq!(parsed.stmts, reparsed.stmts);
+    }
+
+    // Debug output of a tree with every span removed.
+    fn without_spans(stmts: &ast::Stmts) -> String {
+        let mut debug = format!("{stmts:?}");
+        while let Some(start) = debug.find("Span { start: Location(") {
+            let end = start + debug[start..].find(") }").unwrap() + 3;
+            debug.replace_range(start..end, "_");
+        }
+        debug
+    }
+
+    #[test]
+    fn unparse_round_trip() {
+        let source = r#"BEGIN TRANSACTION;
+            ALTER TABLE t1 RENAME TO t2;
+            ALTER TABLE t1 RENAME c1 TO c2;
+            ALTER TABLE t1 ADD c3 VARCHAR(20) NOT NULL DEFAULT 'it''s';
+            ALTER TABLE t1 DROP COLUMN c3;
+            CREATE TABLE IF NOT EXISTS "order" (
+                id INTEGER CONSTRAINT pk PRIMARY KEY DESC ON CONFLICT ROLLBACK,
+                amount NUMERIC(10, 2) DEFAULT -1.5 CHECK (amount > 0),
+                qty INTEGER DEFAULT +3 UNIQUE ON CONFLICT IGNORE,
+                note CHAR DEFAULT NULL COLLATE nocase,
+                parent INTEGER REFERENCES "order" (id) ON DELETE SET NULL,
+                code SERIAL DEFAULT (1 + 2),
+                UNIQUE (amount COLLATE binary DESC, qty) ON CONFLICT FAIL,
+                CONSTRAINT fk FOREIGN KEY (parent, code) REFERENCES t2 (a, b) ON UPDATE NO ACTION,
+                CHECK (qty IS NOT DISTINCT FROM 3)
+            );
+            CREATE TABLE t3 AS SELECT * FROM t1;
+            CREATE UNIQUE INDEX IF NOT EXISTS i1 ON t1 (c1, lower(c2) ASC) WHERE c1 NOT NULL;
+            WITH RECURSIVE cnt (x) AS NOT MATERIALIZED (SELECT 1 UNION ALL SELECT x + 1 FROM cnt LIMIT 10)
+                SELECT DISTINCT t1.*, c1 AS "select", -c2 * (c3 - - 4), X'0aFF', 1e-7, 0.5, .25,
+                    0x7fffffffffffffff, 0xffffffffffffffff, TRUE, FALSE, NULL, ?, ?5, :name, @name,
+                    count(*) FILTER (WHERE c1 IS NULL), sum(DISTINCT c2) OVER w,
+                    row_number() OVER (w2 PARTITION BY c1, c2 ORDER BY c3 DESC NULLS LAST
+                        ROWS BETWEEN 3 PRECEDING AND CURRENT ROW EXCLUDE NO OTHERS),
+                    avg(c1) OVER (RANGE UNBOUNDED PRECEDING),
+                    CASE c1 WHEN 1 THEN 'a' WHEN 2 THEN 'b' ELSE 'c' END,
+                    CASE WHEN c1 BETWEEN 1 AND 2 THEN c1 NOT BETWEEN 3 AND 4 END,
+                    CAST(c1 AS INTEGER), "weird ""name""", t1."Mixed Case", "replace"(c1)
+                FROM t1 AS a INDEXED BY i1
+                    NATURAL LEFT OUTER JOIN t2 NOT INDEXED USING (c1, c2)
+                    CROSS JOIN (SELECT c1 FROM t3) AS sub
+                    , t4 JOIN (t5 INNER JOIN t6 ON t5.c1 = t6.c1) ON t4.c1 = t5.c1
+                WHERE NOT EXISTS (SELECT 1) AND c1 NOT IN (1, 2) OR c2 IN (SELECT c2 FROM t7)
+                    AND c3 IN t8 AND c4 NOT LIKE 'a%' AND c5 IN () AND (c1, c2) = (1, 2)
+                GROUP BY c1, c2 HAVING count(*) > 1
+                WINDOW w AS (ORDER BY c1), w2 AS (PARTITION BY c2 GROUPS 2 FOLLOWING EXCLUDE TIES)
+                INTERSECT SELECT 1 EXCEPT SELECT 2
+                ORDER BY c1 COLLATE nocase ASC NULLS FIRST, 2
+                LIMIT 10 OFFSET ?;
+            WITH w AS MATERIALIZED (SELECT 1)
+                INSERT INTO t1 AS x (c1, c2) VALUES (1, 'a'), (2, NULL)
+                ON CONFLICT (c1) WHERE c1 > 0 DO UPDATE SET c2 = excluded.c2, (c3, c4) = (1, 2) WHERE c2 != 0
+                RETURNING *, c1 + 1 AS next;
+            INSERT INTO t1 SELECT * FROM t2 WHERE TRUE ON CONFLICT DO NOTHING;
+            INSERT INTO t1 DEFAULT VALUES;
+            UPDATE OR REPLACE t1 AS a SET c1 = c1 % 2 FROM t2 WHERE a.c1 = t2.c1 RETURNING a.c1;
+            DELETE FROM t1 WHERE c1 <= 3 AND c2 >= 4 AND c3 < 5 AND c4 / 2 = 1 RETURNING c1;
+            DROP TABLE IF EXISTS t1;
+            DROP INDEX i1;
+            REINDEX;
+            REINDEX i1;
+            SAVEPOINT s1;
+            RELEASE s1;
+            ROLLBACK TRANSACTION TO s1;
+            ROLLBACK;
+            COMMIT;"#;
+        let parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid(), "{:?}", parsed.errors);
+
+        let unparsed = parsed.stmts.to_string();
+        let reparsed = Parser::new(&unparsed).parse();
+        assert!(reparsed.is_valid(), "{unparsed}\n{:?}", reparsed.errors);
+        assert_eq!(without_spans(&parsed.stmts), without_spans(&reparsed.stmts), "{unparsed}");
+        assert_eq!(reparsed.stmts.to_string(), unparsed);
+    }
+
+    #[test]
+    fn unparse_canonical_text() {
+        let source = "select  a.c1,count( * )from t1 as a
+            left outer join t2 on a.c1=t2.c1 where not c2 is null and c3 not null;
+            begin transaction; rollback transaction;";
+        let parsed = Parser::new(source).parse();
+        assert!(parsed.is_valid(), "{:?}", parsed.errors);
+        assert_eq!(
+            parsed.stmts.to_string(),
+            "SELECT a.c1, count(*) FROM t1 AS a LEFT JOIN t2 ON a.c1 = t2.c1 \
+             WHERE NOT c2 IS NULL AND c3 IS NOT NULL;\nBEGIN;\nROLLBACK;"
+        );
+    }
+
+    #[test]
+    fn unparse_quotes_every_keyword() {
+        // The unparser keeps its own copy of the keyword table.
+        for (keyword, _) in crate::tokens::KEYWORDS {
+            let lower = keyword.to_lowercase();
+            let name = ast::name::Name::new(lower.clone());
+            let id = ast::Identifier::new(name, Span::empty(Location::new(0)));
+            assert_eq!(id.to_string(), format!("\"{lower}\""), "{keyword}");
+        }
+    }
+
+    #[test]
+    fn missing_semicolon_resumes_at_next_statement() {
+        // The rest of a statement that ends early is skipped up to its `;`
+        let parsed = Parser::new("SELECT * FORM t1; SELECT 1;").parse();
+        assert_eq!(parsed.stmts.body.len(), 2);
+        assert_eq!(parsed.errors().len(), 1);
+        assert_eq!(parsed.errors()[0].kind, ParseErrorKind::MissingSemicolon);
+
+        // or up to the next statement when the `;` itself is missing
+        let parsed = Parser::new("BEGIN\nSELECT 1;").parse();
+        assert_eq!(parsed.stmts.body.len(), 2);
+        assert!(matches!(parsed.stmts.body[1], Stmt::Select(_)));
+        assert_eq!(parsed.errors().len(), 1);
+    }
+
+    #[test]
+    fn error_diagnostic_points_at_unclosed_paren() {
+        use source_index::diagnostic::{Renderer, Style};
+
+        let source = "SELECT c1 FROM t1\nWHERE c1 IN (1, (2), 3 ORDER BY c1;";
+        let parsed = Parser::new(source).parse();
+        let error = parsed.errors().first().expect("missing `)` should be an error");
+        let rendered = Renderer::new("query.sql", source, Style::Plain).render(&error.to_diagnostic(source));
+        assert_eq!(
+            rendered,
+            "error[P0004]: Expected `)`, found `ORDER` instead.
+ --> query.sql:2:24
+  |
+2 | WHERE c1 IN (1, (2), 3 ORDER BY c1;
+  |             - unclosed `(`
+  |                        ^^^^^ expected `)`
+  |
+  = help: add `)` to close the parenthesis
+"
+        );
+    }
+
+    #[test]
+    fn misspelled_keyword_suggestions() {
+        let cases = [
+            ("SELCT * FROM t1;", Some(TokenKind::Select)),
+            ("SELECT * FORM t1;", Some(TokenKind::From)),
+            ("SELECT c1 FROM t1 ORDER BYY c1;", Some(TokenKind::By)),
+            ("SELECT c1 FROM t1 LEFT JION t2 ON c1 = c2;", Some(TokenKind::Join)),
+            ("CREATE TABEL t1 (c1 INTEGER);", Some(TokenKind::Table)),
+            ("CREATE TABLE t1 (c1 INTEGER, c2 VARCHR(10));", Some(TokenKind::Varchar)),
+            ("UPDATE t1 SET c1 = 1 RETURNIN c1;", Some(TokenKind::Returning)),
+            ("SELECT c1 FROM t1 GROUP c1;", None),
+            ("SELECT c1 FROM t1 t2 t3;", None),
+        ];
+        for (source, expected) in cases {
+            let parsed = Parser::new(source).parse();
+            let error = parsed.errors().first().expect(source);
+            assert_eq!(error.suggestion(source), expected, "{source}");
+        }
+
+        let source = "SELCT * FROM t1;";
+        let parsed = Parser::new(source).parse();
+        let diagnostic = parsed.errors()[0].to_diagnostic(source);
+        assert_eq!(diagnostic.code.as_deref(), Some("P0007"));
+        assert_eq!(diagnostic.notes, ["help: did you mean `SELECT`?"]);
+    }
+
+    #[test]
+    fn lexical_errors_are_reported() {
+        let source = "SELECT 'abc FROM t;";
+        let errors = crate::parse_stmts(source).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::Lexical(LexicalErrorKind::UnterminatedString));
+        assert_eq!(&source[errors[0].span], "'abc FROM t;");
+        let diagnostic = errors[0].to_diagnostic(source);
+        assert_eq!(diagnostic.code.as_deref(), Some("P0108"));
+
+        // Lexical errors are kept in source order with the parser's own.
+        let source = "SELECT 1 FORM t; SELECT X'1';";
+        let errors = crate::parse_stmts(source).unwrap_err();
+        let codes: Vec<&str> = errors.iter().map(|error| error.kind.code()).collect();
+        assert_eq!(codes, ["P0011", "P0101"]);
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        let source = "SELECT 1 /* x FROM t;";
+        let errors = crate::parse_stmts(source).unwrap_err();
+        let kinds: Vec<&ParseErrorKind> = errors.iter().map(|error| &error.kind).collect();
+        assert_eq!(kinds, [&ParseErrorKind::Lexical(LexicalErrorKind::UnterminatedComment)]);
+        assert_eq!(&source[errors[0].span], "/* x FROM t;");
+        assert_eq!(errors[0].kind.code(), "P0106");
+        assert_eq!(errors[0].kind.hint().as_deref(), Some("close the comment with `*/`"));
+    }
+
+    #[test]
+    fn keyword_used_as_name_hint() {
+        let source = "CREATE TABLE t1 (select INTEGER);";
+        let parsed = Parser::new(source).parse();
+        let error = &parsed.errors()[0];
+        assert_eq!(error.kind, ParseErrorKind::ExpectedIdentifier { found: TokenKind::Select });
+        assert_eq!(&source[error.span], "select");
+        assert_eq!(error.kind.to_string(), "Expected an identifier, found `SELECT` instead.");
+        assert_eq!(
+            error.kind.hint().as_deref(),
+            Some("`SELECT` is a keyword, write it in double quotes to use it as a name: \"select\"")
+        );
+    }
 }
@@ -1,6 +1,8 @@
-use crate::{errors::{ParseError, ParseErrorKind}, tokens::TokenKind};
+use source_index::{location::Location, span::Span};
 
-use super::Parser;
+use crate::{errors::{ParseError, ParseErrorKind}, tokens::{TokenKind, TokenValue}};
+
+use super::{recovery, Parser};
 
 impl<'src> Parser<'src> {
 
@@ -28,7 +30,7 @@ impl<'src> Parser<'src> {
         let start = self.node_start();
         self.bump(TokenKind::Rename);
         if self.eat(TokenKind::To) {
-            let id = self.parse_identifier()?;
+            let id = self.parse_identifier_or_invalid(&[]);
             return Ok(ast::AlterTableActionKind::Rename(
                     ast::AlterTableRename {
                         span: self.node_span(start),
@@ -36,14 +38,9 @@ impl<'src> Parser<'src> {
                     }));
         }
         self.eat(TokenKind::Column);
-        let id_from = self.parse_identifier()?;
-        if !self.expect(TokenKind::To) {
-            return Err(ParseError {
-                kind: ParseErrorKind::ExpectedToken { found: self.current_token_kind(), expected: TokenKind::To, },
-                span: self.node_span(start),
-            });
-        }
-        let id_to = self.parse_identifier()?;
+        let id_from = self.parse_identifier_or_invalid(&[TokenKind::To]);
+        self.expect_token(TokenKind::To)?;
+        let id_to = self.parse_identifier_or_invalid(&[]);
         Ok(ast::AlterTableActionKind::Rename(
                 ast::AlterTableRename {
                     span: self.node_span(start),
@@ -69,7 +66,7 @@ impl<'src> Parser<'src> {
         let start = self.node_start();
         self.bump(TokenKind::Drop);
         self.eat(TokenKind::Column);
-        let id = self.parse_identifier()?;
+        let id = self.parse_identifier_or_invalid(&[]);
         Ok(ast::AlterTableActionKind::Drop(
                 ast::AlterTableDrop {
                     span: self.node_span(start),
@@ -80,9 +77,9 @@ impl<'src> Parser<'src> {
 
     fn parse_column_def(&mut self) -> Result<ast::ColumnDef, ParseError> {
         let start = self.node_start();
-        let id = self.parse_identifier()?;
+        let id = self.parse_identifier_or_invalid(recovery::COLUMN_NAME);
         let type_name = self.parse_type_name()?;
-        let constraint_list = self.parse_column_constraint_list()?;
+        let constraint_list = self.parse_column_constraint_list();
         Ok(ast::ColumnDef {
             span: self.node_span(start),
             id,
@@ -91,7 +88,7 @@ impl<'src> Parser<'src> {
         })
     }
 
-    fn parse_type_name(&mut self) -> Result<ast::TypeName, ParseError> {
+    pub(crate) fn parse_type_name(&mut self) -> Result<ast::TypeName, ParseError> {
         let start = self.node_start();
         let external_type = match self.current_token_kind() {
             TokenKind::Char => {
@@ -159,21 +156,28 @@ impl<'src> Parser<'src> {
         })
     }
 
-    fn parse_column_constraint_list(&mut self) -> Result<ast::ColumnConstraintList, ParseError> {
+    // A constraint that fails to parse is left out of the list.
+    fn parse_column_constraint_list(&mut self) -> ast::ColumnConstraintList {
         let start = self.node_start();
         let mut constraints: Vec<ast::ColumnConstraint> = Vec::new();
         while self.current_token_kind().is_column_constraint() {
-            let constraint = self.parse_column_constraint()?;
-            constraints.push(constraint);
+            match self.parse_column_constraint() {
+                Ok(constraint) => constraints.push(constraint),
+                Err(error) => self.recover(error, recovery::COLUMN_CONSTRAINT),
+            }
         }
-        Ok(ast::ColumnConstraintList {
+        ast::ColumnConstraintList {
             span: self.node_span(start),
             constraints,
-        })
+        }
     }
 
     fn parse_column_constraint(&mut self) -> Result<ast::ColumnConstraint, ParseError> {
         let start = self.node_start();
+        let mut name = None;
+        if self.eat(TokenKind::Constraint) {
+            name = Some(self.parse_identifier()?);
+        }
         match self.current_token_kind() {
             TokenKind::Primary => {
                 self.bump(TokenKind::Primary);
@@ -186,17 +190,7 @@ impl<'src> Parser<'src> {
                         }
                     })
                 }
-                let order = match self.current_token_kind() {
-                    TokenKind::Asc => { 
-                        self.bump(TokenKind::Asc);
-                        Some(ast::Order::Asc)
-                    }
-                    TokenKind::Desc => {
-                        self.bump(TokenKind::Desc);
-                        Some(ast::Order::Desc)
-                    }
-                    _ => None
-                };
+                let order = self.parse_order();
                 let mut conflict_action = None;
                 if self.eat(TokenKind::On) {
                     if self.eat(TokenKind::Conflict) {
@@ -211,8 +205,9 @@ impl<'src> Parser<'src> {
                         })
                     };
                 };
-                return Ok(ast::ColumnConstraint {
+                Ok(ast::ColumnConstraint {
                     span: self.node_span(start),
+                    name,
                     kind: ast::ColumnConstraintKind::PrimaryKey(
                         ast::ColumnConstraintPrimaryKey {
                             span: self.node_span(start),
@@ -220,7 +215,7 @@ impl<'src> Parser<'src> {
                             conflict_action,
                     }
                     )
-                });
+                })
             }
             TokenKind::Not => {
                 self.bump(TokenKind::Not);
@@ -247,8 +242,9 @@ impl<'src> Parser<'src> {
                         })
                     };
                 };
-                return Ok(ast::ColumnConstraint {
+                Ok(ast::ColumnConstraint {
                     span: self.node_span(start),
+                    name,
                     kind: ast::ColumnConstraintKind::NotNull(
                         conflict_action,
                     )
@@ -270,8 +266,9 @@ impl<'src> Parser<'src> {
                         })
                     };
                 };
-                return Ok(ast::ColumnConstraint {
+                Ok(ast::ColumnConstraint {
                     span: self.node_span(start),
+                    name,
                     kind: ast::ColumnConstraintKind::Unique(
                         conflict_action,
                     )
@@ -279,30 +276,55 @@ impl<'src> Parser<'src> {
             }
             TokenKind::Check => {
                 self.bump(TokenKind::Check);
-                unimplemented!()
+                self.expect_token(TokenKind::LParen)?;
+                let expr = self.parse_expr()?;
+                self.expect_token(TokenKind::RParen)?;
+                Ok(ast::ColumnConstraint {
+                    span: self.node_span(start),
+                    name,
+                    kind: ast::ColumnConstraintKind::Check(expr)
+                })
             }
             TokenKind::Default => {
                 self.bump(TokenKind::Default);
-                unimplemented!()
+                let default = match self.current_token_kind() {
+                    TokenKind::LParen => {
+                        self.bump(TokenKind::LParen);
+                        let expr = self.parse_expr()?;
+                        self.expect_token(TokenKind::RParen)?;
+                        ast::ColumnConstraintDefault::ParenExpr(expr)
+                    }
+                    TokenKind::Plus | TokenKind::Minus => {
+                        ast::ColumnConstraintDefault::SignedNumber(self.parse_signed_number()?)
+                    }
+                    _ => ast::ColumnConstraintDefault::LiteralValue(self.parse_literal()?)
+                };
+                Ok(ast::ColumnConstraint {
+                    span: self.node_span(start),
+                    name,
+                    kind: ast::ColumnConstraintKind::Default(default)
+                })
             }
             TokenKind::Collate => {
                 self.bump(TokenKind::Collate);
                 let id = self.parse_identifier()?;
-                return Ok(ast::ColumnConstraint {
+                Ok(ast::ColumnConstraint {
                     span: self.node_span(start),
+                    name,
                     kind: ast::ColumnConstraintKind::Collate(id)
-                });
+                })
             }
             TokenKind::References => {
                 self.bump(TokenKind::References);
                 let foreign_key_clause = self.parse_foreign_key_clause()?;
-                return Ok(ast::ColumnConstraint {
+                Ok(ast::ColumnConstraint {
                     span: self.node_span(start),
+                    name,
                     kind: ast::ColumnConstraintKind::Foreign(foreign_key_clause)
-                });
+                })
             }
             _ => {
-                return Err(ParseError {
+                Err(ParseError {
                     span: self.node_span(start),
                     kind: ParseErrorKind::UnexpectedToken {
                         found: self.current_token_kind()
@@ -316,27 +338,12 @@ impl<'src> Parser<'src> {
     fn parse_foreign_key_clause(&mut self) -> Result<ast::ForeignKeyClause, ParseError> {
         let start = self.node_start();
         let id = self.parse_identifier()?;
-        if !self.eat(TokenKind::LParen) {
-            return Err(ParseError {
-                span: self.node_span(start),
-                kind: ParseErrorKind::ExpectedToken {
-                    found: self.current_token_kind(),
-                    expected: TokenKind::LParen,
-                }
-            })
-        };
-
-        let column_names = self.parse_column_name_list()?;
-
-        if !self.eat(TokenKind::RParen) {
-            return Err(ParseError {
-                span: self.node_span(start),
-                kind: ParseErrorKind::ExpectedToken {
-                    found: self.current_token_kind(),
-                    expected: TokenKind::RParen,
-                }
-            })
-        };
+        // The referenced columns default to the primary key of the foreign table
+        let mut column_names = Vec::new();
+        if self.eat(TokenKind::LParen) {
+            column_names = self.parse_column_name_list()?;
+            self.expect_token(TokenKind::RParen)?;
+        }
 
         let mut foreign_key_clause_on = None;
         if self.eat(TokenKind::On) {
@@ -374,11 +381,14 @@ impl<'src> Parser<'src> {
             TokenKind::Set => {
                 self.bump(TokenKind::Set);
                 match self.current_token_kind() {
-                    TokenKind::Null => ast::ForeignKeyClauseActions::Set(
-                        ast::ForeignKeyClauseActionSet::Null),
-                    TokenKind::Default => ast::ForeignKeyClauseActions::Set(
-                        ast::ForeignKeyClauseActionSet::Default
-                    ),
+                    TokenKind::Null => {
+                        self.bump(TokenKind::Null);
+                        ast::ForeignKeyClauseActions::Set(ast::ForeignKeyClauseActionSet::Null)
+                    }
+                    TokenKind::Default => {
+                        self.bump(TokenKind::Default);
+                        ast::ForeignKeyClauseActions::Set(ast::ForeignKeyClauseActionSet::Default)
+                    }
                     _ => {
                         return Err(ParseError {
                             span: self.node_span(start),
@@ -450,7 +460,7 @@ impl<'src> Parser<'src> {
                 Ok(ast::ConflictAction::Replace)
             }
             _ => {
-                return Err(ParseError {
+                Err(ParseError {
                     span: self.node_span(start),
                     kind: ParseErrorKind::UnexpectedToken {
                         found: self.current_token_kind()
@@ -473,5 +483,1020 @@ impl<'src> Parser<'src> {
         Ok(name_list)
     }
 
+    /// Parse a full select statement, including any compound selects and the
+    /// trailing ORDER BY and LIMIT clauses.
+    pub(crate) fn parse_select(&mut self) -> Result<ast::StmtSelect, ParseError> {
+        let start = self.node_start();
+        let mut with = None;
+        if self.at(TokenKind::With) {
+            with = Some(self.parse_with_clause()?);
+        }
+        let mut select = self.parse_select_core()?;
+        select.with = with;
+        if self.eat(TokenKind::Order) {
+            self.expect_token(TokenKind::By)?;
+            select.order_by = self.parse_ordering_term_list();
+        }
+        if self.at(TokenKind::Limit) {
+            select.limit = Some(self.parse_limit_clause());
+        }
+        select.span = self.node_span(start);
+        Ok(select)
+    }
+
+    /// Parse a statement beginning with a WITH clause. The clause is attached
+    /// to the SELECT, INSERT, UPDATE or DELETE that follows it.
+    pub(crate) fn parse_with_stmt(&mut self) -> Result<ast::Stmt, ParseError> {
+        let start = self.node_start();
+        let with = Some(self.parse_with_clause()?);
+        let stmt = match self.current_token_kind() {
+            TokenKind::Select => {
+                let mut select = self.parse_select()?;
+                select.with = with;
+                select.span = self.node_span(start);
+                ast::Stmt::Select(Box::new(select))
+            }
+            TokenKind::Insert => {
+                let mut insert = self.parse_insert()?;
+                insert.with = with;
+                insert.span = self.node_span(start);
+                ast::Stmt::Insert(insert)
+            }
+            TokenKind::Update => {
+                let mut update = self.parse_update()?;
+                update.with = with;
+                update.span = self.node_span(start);
+                ast::Stmt::Update(update)
+            }
+            TokenKind::Delete => {
+                let mut delete = self.parse_delete()?;
+                delete.with = with;
+                delete.span = self.node_span(start);
+                ast::Stmt::Delete(delete)
+            }
+            _ => {
+                return Err(ParseError {
+                    span: self.current_token_span(),
+                    kind: ParseErrorKind::InvalidWithTarget,
+                })
+            }
+        };
+        Ok(stmt)
+    }
+
+    fn parse_with_clause(&mut self) -> Result<ast::WithClause, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::With);
+        let recursive = self.eat(TokenKind::Recursive);
+        let mut ctes = vec![self.parse_common_table_expr()?];
+        while self.eat(TokenKind::Comma) {
+            ctes.push(self.parse_common_table_expr()?);
+        }
+        Ok(ast::WithClause {
+            span: self.node_span(start),
+            recursive,
+            ctes,
+        })
+    }
+
+    fn parse_common_table_expr(&mut self) -> Result<ast::CommonTableExpr, ParseError> {
+        let start = self.node_start();
+        let id = self.parse_identifier()?;
+        let mut columns = Vec::new();
+        if self.eat(TokenKind::LParen) {
+            columns = self.parse_column_name_list()?;
+            self.expect_token(TokenKind::RParen)?;
+        }
+        self.expect_token(TokenKind::As)?;
+        let mut materialized = None;
+        if self.eat(TokenKind::Materialized) {
+            materialized = Some(ast::MaterializeHint::Materialized);
+        } else if self.eat(TokenKind::Not) {
+            self.expect_token(TokenKind::Materialized)?;
+            materialized = Some(ast::MaterializeHint::NotMaterialized);
+        }
+        self.expect_token(TokenKind::LParen)?;
+        let select = self.parse_select()?;
+        self.expect_token(TokenKind::RParen)?;
+        Ok(ast::CommonTableExpr {
+            span: self.node_span(start),
+            id,
+            columns,
+            materialized,
+            select: Box::new(select),
+        })
+    }
 
+    // Parse a select up to and including any compound operators. ORDER BY and
+    // LIMIT are left to the caller since they apply to the whole compound select.
+    fn parse_select_core(&mut self) -> Result<ast::StmtSelect, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::Select)?;
+        let distinct = self.eat(TokenKind::Distinct);
+        let columns = self.parse_result_column_list()?;
+
+        let mut from = None;
+        if self.at(TokenKind::From) {
+            from = Some(self.parse_from_clause()?);
+        }
+        let mut where_clause = None;
+        if self.eat(TokenKind::Where) {
+            where_clause = Some(self.parse_expr_or_invalid(recovery::CLAUSE));
+        }
+        let mut group_by = Vec::new();
+        if self.eat(TokenKind::Group) {
+            self.expect_token(TokenKind::By)?;
+            group_by = self.parse_expr_list_or_invalid(recovery::CLAUSE_ITEM);
+        }
+        let mut having = None;
+        if self.eat(TokenKind::Having) {
+            having = Some(self.parse_expr_or_invalid(recovery::CLAUSE));
+        }
+        let mut window = Vec::new();
+        if self.at(TokenKind::Window) {
+            window = self.parse_window_clause()?;
+        }
+        let mut compound = None;
+        if self.current_token_kind().is_compound_operator() {
+            compound = Some(self.parse_compound_select()?);
+        }
+        Ok(ast::StmtSelect {
+            span: self.node_span(start),
+            with: None,
+            distinct,
+            columns,
+            from,
+            where_clause,
+            group_by,
+            having,
+            window,
+            compound,
+            order_by: Vec::new(),
+            limit: None,
+        })
+    }
+
+    fn parse_compound_select(&mut self) -> Result<ast::CompoundSelect, ParseError> {
+        let start = self.node_start();
+        let op = match self.current_token_kind() {
+            TokenKind::Union => {
+                self.bump(TokenKind::Union);
+                if self.eat(TokenKind::All) {
+                    ast::CompoundOperator::UnionAll
+                } else {
+                    ast::CompoundOperator::Union
+                }
+            }
+            TokenKind::Intersect => {
+                self.bump(TokenKind::Intersect);
+                ast::CompoundOperator::Intersect
+            }
+            TokenKind::Except => {
+                self.bump(TokenKind::Except);
+                ast::CompoundOperator::Except
+            }
+            _ => {
+                return Err(ParseError {
+                    span: self.current_token_span(),
+                    kind: ParseErrorKind::UnexpectedToken {
+                        found: self.current_token_kind()
+                    }
+                })
+            }
+        };
+        let select = self.parse_select_core()?;
+        Ok(ast::CompoundSelect {
+            span: self.node_span(start),
+            op,
+            select: Box::new(select),
+        })
+    }
+
+    fn parse_result_column_list(&mut self) -> Result<Vec<ast::ResultColumn>, ParseError> {
+        let mut columns = vec![self.parse_result_column()?];
+        while self.eat(TokenKind::Comma) {
+            columns.push(self.parse_result_column()?);
+        }
+        Ok(columns)
+    }
+
+    fn parse_result_column(&mut self) -> Result<ast::ResultColumn, ParseError> {
+        let start = self.node_start();
+        let kind = match self.current_token_kind() {
+            TokenKind::Star => {
+                self.bump(TokenKind::Star);
+                ast::ResultColumnKind::All
+            }
+            TokenKind::TableAll => {
+                let TokenValue::TableAll(name) = self.bump_value(TokenKind::TableAll) else {
+                    return Err(ParseError {
+                        span: self.node_span(start),
+                        kind: ParseErrorKind::ExpectedValue,
+                    });
+                };
+                // Span of the table name without the trailing `.*`
+                let span = self.node_span(start);
+                let id = ast::Identifier::new(
+                    name,
+                    Span::new(span.start(), span.end() - Location::new(2)),
+                );
+                ast::ResultColumnKind::TableAll(id)
+            }
+            _ => {
+                let expr = self.parse_expr_or_invalid(recovery::CLAUSE_ITEM);
+                let alias = self.parse_alias();
+                ast::ResultColumnKind::Expr(expr, alias)
+            }
+        };
+        Ok(ast::ResultColumn {
+            span: self.node_span(start),
+            kind,
+        })
+    }
+
+    /// Parse an optional `[AS] alias` of a result column, table or derived
+    /// table.
+    fn parse_alias(&mut self) -> Option<ast::Identifier> {
+        if self.at(TokenKind::Name) {
+            return self.parse_identifier().ok();
+        }
+        if self.eat(TokenKind::As) {
+            return Some(self.parse_identifier_or_invalid(recovery::TABLE_NAME));
+        }
+        None
+    }
+
+    fn parse_from_clause(&mut self) -> Result<ast::FromClause, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::From);
+        let item = self.parse_join_clause()?;
+        Ok(ast::FromClause {
+            span: self.node_span(start),
+            item,
+        })
+    }
+
+    // Parse a chain of joins into a left-deep tree. A lone table-or-subquery
+    // is returned without a join.
+    fn parse_join_clause(&mut self) -> Result<ast::FromItem, ParseError> {
+        let start = self.node_start();
+        let mut left = self.parse_table_or_subquery()?;
+        while self.current_token_kind().is_join_operator() {
+            let op = self.parse_join_operator()?;
+            let right = self.parse_table_or_subquery()?;
+            let constraint = match self.current_token_kind() {
+                TokenKind::On => {
+                    self.bump(TokenKind::On);
+                    Some(ast::JoinConstraint::On(self.parse_expr()?))
+                }
+                TokenKind::Using => {
+                    self.bump(TokenKind::Using);
+                    self.expect_token(TokenKind::LParen)?;
+                    let columns = self.parse_column_name_list()?;
+                    self.expect_token(TokenKind::RParen)?;
+                    Some(ast::JoinConstraint::Using(columns))
+                }
+                _ => None,
+            };
+            let span = self.node_span(start);
+            left = ast::FromItem {
+                span,
+                kind: ast::FromItemKind::Join(ast::JoinClause {
+                    span,
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                    constraint,
+                }),
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_join_operator(&mut self) -> Result<ast::JoinOperator, ParseError> {
+        let start = self.node_start();
+        if self.eat(TokenKind::Comma) {
+            return Ok(ast::JoinOperator {
+                span: self.node_span(start),
+                natural: false,
+                kind: ast::JoinKind::Comma,
+            });
+        }
+        if self.eat(TokenKind::Cross) {
+            self.expect_token(TokenKind::Join)?;
+            return Ok(ast::JoinOperator {
+                span: self.node_span(start),
+                natural: false,
+                kind: ast::JoinKind::Cross,
+            });
+        }
+        let natural = self.eat(TokenKind::Natural);
+        let kind = match self.current_token_kind() {
+            TokenKind::Left | TokenKind::Right | TokenKind::Full => {
+                let kind = match self.current_token_kind() {
+                    TokenKind::Left => ast::JoinKind::Left,
+                    TokenKind::Right => ast::JoinKind::Right,
+                    _ => ast::JoinKind::Full,
+                };
+                self.bump_any();
+                self.eat(TokenKind::Outer);
+                kind
+            }
+            _ => {
+                self.eat(TokenKind::Inner);
+                ast::JoinKind::Inner
+            }
+        };
+        self.expect_token(TokenKind::Join)?;
+        Ok(ast::JoinOperator {
+            span: self.node_span(start),
+            natural,
+            kind,
+        })
+    }
+
+    // Parse a table, a derived table, or a parenthesised join.
+    fn parse_table_or_subquery(&mut self) -> Result<ast::FromItem, ParseError> {
+        let start = self.node_start();
+        if self.eat(TokenKind::LParen) {
+            if self.current_token_kind().is_select_start() {
+                let select = self.parse_select()?;
+                self.expect_token(TokenKind::RParen)?;
+                let alias = self.parse_alias();
+                let span = self.node_span(start);
+                return Ok(ast::FromItem {
+                    span,
+                    kind: ast::FromItemKind::Subquery(ast::FromSubquery {
+                        span,
+                        select: Box::new(select),
+                        alias,
+                    }),
+                });
+            }
+            let item = self.parse_join_clause()?;
+            self.expect_token(TokenKind::RParen)?;
+            return Ok(item);
+        }
+        let table = self.parse_quantified_table_name()?;
+        Ok(ast::FromItem {
+            span: self.node_span(start),
+            kind: ast::FromItemKind::Table(table),
+        })
+    }
+
+    /// Parse `table-name [[AS] alias]?`
+    pub(crate) fn parse_table_name(&mut self) -> Result<ast::TableName, ParseError> {
+        let start = self.node_start();
+        let id = self.parse_identifier_or_invalid(recovery::TABLE_NAME);
+        let alias = self.parse_alias();
+        Ok(ast::TableName {
+            span: self.node_span(start),
+            id,
+            alias,
+        })
+    }
+
+    fn parse_ordering_term_list(&mut self) -> Vec<ast::OrderingTerm> {
+        let mut terms = vec![self.parse_ordering_term()];
+        while self.eat(TokenKind::Comma) {
+            terms.push(self.parse_ordering_term());
+        }
+        terms
+    }
+
+    // A term that fails to parse keeps its place in the list with an invalid
+    // expression.
+    fn parse_ordering_term(&mut self) -> ast::OrderingTerm {
+        let start = self.node_start();
+        let (expr, collation) = split_collation(self.parse_expr_or_invalid(recovery::CLAUSE_ITEM));
+        let order = self.parse_order();
+        let mut nulls = None;
+        if self.eat(TokenKind::Nulls) {
+            if self.eat(TokenKind::First) {
+                nulls = Some(ast::NullsOrder::First);
+            } else if let Err(error) = self.expect_token(TokenKind::Last) {
+                self.recover(error, recovery::CLAUSE_ITEM);
+            } else {
+                nulls = Some(ast::NullsOrder::Last);
+            }
+        }
+        ast::OrderingTerm {
+            span: self.node_span(start),
+            expr,
+            collation,
+            order,
+            nulls,
+        }
+    }
+
+    fn parse_window_clause(&mut self) -> Result<Vec<ast::NamedWindow>, ParseError> {
+        self.bump(TokenKind::Window);
+        let mut windows = vec![self.parse_named_window()?];
+        while self.eat(TokenKind::Comma) {
+            windows.push(self.parse_named_window()?);
+        }
+        Ok(windows)
+    }
+
+    fn parse_named_window(&mut self) -> Result<ast::NamedWindow, ParseError> {
+        let start = self.node_start();
+        let id = self.parse_identifier()?;
+        self.expect_token(TokenKind::As)?;
+        let spec = self.parse_window_spec()?;
+        Ok(ast::NamedWindow {
+            span: self.node_span(start),
+            id,
+            spec,
+        })
+    }
+
+    /// Parse a parenthesised window definition.
+    pub(crate) fn parse_window_spec(&mut self) -> Result<ast::WindowSpec, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::LParen)?;
+        let mut base = None;
+        if self.at(TokenKind::Name) {
+            base = Some(self.parse_identifier()?);
+        }
+        let mut partition_by = Vec::new();
+        if self.eat(TokenKind::Partition) {
+            self.expect_token(TokenKind::By)?;
+            partition_by = self.parse_expr_list()?;
+        }
+        let mut order_by = Vec::new();
+        if self.eat(TokenKind::Order) {
+            self.expect_token(TokenKind::By)?;
+            order_by = self.parse_ordering_term_list();
+        }
+        let mut frame = None;
+        if matches!(
+            self.current_token_kind(),
+            TokenKind::Range | TokenKind::Rows | TokenKind::Groups
+        ) {
+            frame = Some(self.parse_window_frame()?);
+        }
+        self.expect_token(TokenKind::RParen)?;
+        Ok(ast::WindowSpec {
+            span: self.node_span(start),
+            base,
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+
+    fn parse_window_frame(&mut self) -> Result<ast::WindowFrame, ParseError> {
+        let start = self.node_start();
+        let units = match self.current_token_kind() {
+            TokenKind::Range => ast::FrameUnits::Range,
+            TokenKind::Rows => ast::FrameUnits::Rows,
+            _ => ast::FrameUnits::Groups,
+        };
+        self.bump_any();
+        let (frame_start, end) = if self.eat(TokenKind::Between) {
+            let frame_start = self.parse_frame_bound()?;
+            self.expect_token(TokenKind::And)?;
+            (frame_start, Some(self.parse_frame_bound()?))
+        } else {
+            (self.parse_frame_bound()?, None)
+        };
+        let mut exclude = None;
+        if self.eat(TokenKind::Exclude) {
+            exclude = Some(match self.current_token_kind() {
+                TokenKind::No => {
+                    self.bump(TokenKind::No);
+                    self.expect_token(TokenKind::Others)?;
+                    ast::FrameExclude::NoOthers
+                }
+                TokenKind::Current => {
+                    self.bump(TokenKind::Current);
+                    self.expect_token(TokenKind::Row)?;
+                    ast::FrameExclude::CurrentRow
+                }
+                TokenKind::Group => {
+                    self.bump(TokenKind::Group);
+                    ast::FrameExclude::Group
+                }
+                _ => {
+                    self.expect_token(TokenKind::Ties)?;
+                    ast::FrameExclude::Ties
+                }
+            });
+        }
+        Ok(ast::WindowFrame {
+            span: self.node_span(start),
+            units,
+            start: frame_start,
+            end,
+            exclude,
+        })
+    }
+
+    // Parse `UNBOUNDED [PRECEDING | FOLLOWING] | CURRENT ROW | <expr> [PRECEDING | FOLLOWING]`
+    fn parse_frame_bound(&mut self) -> Result<ast::FrameBound, ParseError> {
+        if self.eat(TokenKind::Unbounded) {
+            if self.eat(TokenKind::Preceding) {
+                return Ok(ast::FrameBound::UnboundedPreceding);
+            }
+            self.expect_token(TokenKind::Following)?;
+            return Ok(ast::FrameBound::UnboundedFollowing);
+        }
+        if self.eat(TokenKind::Current) {
+            self.expect_token(TokenKind::Row)?;
+            return Ok(ast::FrameBound::CurrentRow);
+        }
+        let expr = self.parse_expr()?;
+        if self.eat(TokenKind::Preceding) {
+            return Ok(ast::FrameBound::Preceding(Box::new(expr)));
+        }
+        self.expect_token(TokenKind::Following)?;
+        Ok(ast::FrameBound::Following(Box::new(expr)))
+    }
+
+    /// Parse `<expr> [COLLATE collation-name]?`. A trailing COLLATE is parsed
+    /// as part of the expression, so it is lifted back out of the expression tree.
+    pub(crate) fn parse_expr_with_collation(
+        &mut self,
+    ) -> Result<(ast::Expr, Option<ast::Identifier>), ParseError> {
+        Ok(split_collation(self.parse_expr()?))
+    }
+
+    /// Parse an optional `ASC` or `DESC`.
+    pub(crate) fn parse_order(&mut self) -> Option<ast::Order> {
+        if self.eat(TokenKind::Asc) {
+            Some(ast::Order::Asc)
+        } else if self.eat(TokenKind::Desc) {
+            Some(ast::Order::Desc)
+        } else {
+            None
+        }
+    }
+
+    fn parse_limit_clause(&mut self) -> ast::LimitClause {
+        let start = self.node_start();
+        self.bump(TokenKind::Limit);
+        let limit = self.parse_expr_or_invalid(recovery::CLAUSE);
+        let mut offset = None;
+        if self.eat(TokenKind::Offset) {
+            offset = Some(self.parse_expr_or_invalid(recovery::CLAUSE));
+        }
+        ast::LimitClause {
+            span: self.node_span(start),
+            limit,
+            offset,
+        }
+    }
+
+    pub(crate) fn parse_insert(&mut self) -> Result<ast::StmtInsert, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::Insert)?;
+        self.expect_token(TokenKind::Into)?;
+        let table = self.parse_table_name()?;
+        let mut columns = Vec::new();
+        if self.eat(TokenKind::LParen) {
+            columns = self.parse_column_name_list()?;
+            self.expect_token(TokenKind::RParen)?;
+        }
+        let action = self.parse_insert_action()?;
+        let mut returning = None;
+        if self.at(TokenKind::Returning) {
+            returning = Some(self.parse_returning_clause()?);
+        }
+        Ok(ast::StmtInsert {
+            span: self.node_span(start),
+            with: None,
+            table,
+            columns,
+            action,
+            returning,
+        })
+    }
+
+    fn parse_insert_action(&mut self) -> Result<ast::InsertAction, ParseError> {
+        let start = self.node_start();
+        let kind = match self.current_token_kind() {
+            TokenKind::Values => {
+                self.bump(TokenKind::Values);
+                let mut rows = vec![self.parse_values_row()?];
+                while self.eat(TokenKind::Comma) {
+                    rows.push(self.parse_values_row()?);
+                }
+                let mut upsert = None;
+                if self.at(TokenKind::On) {
+                    upsert = Some(self.parse_upsert_clause()?);
+                }
+                ast::InsertActionKind::Values(ast::InsertValues {
+                    span: self.node_span(start),
+                    rows,
+                    upsert,
+                })
+            }
+            TokenKind::Select | TokenKind::With => {
+                let select = self.parse_select()?;
+                let mut upsert = None;
+                if self.at(TokenKind::On) {
+                    upsert = Some(self.parse_upsert_clause()?);
+                }
+                ast::InsertActionKind::Select(ast::InsertSelect {
+                    span: self.node_span(start),
+                    select: Box::new(select),
+                    upsert,
+                })
+            }
+            TokenKind::Default => {
+                self.bump(TokenKind::Default);
+                self.expect_token(TokenKind::Values)?;
+                ast::InsertActionKind::DefaultValues
+            }
+            found => {
+                return Err(ParseError {
+                    span: self.current_token_span(),
+                    kind: ParseErrorKind::UnexpectedToken { found }
+                })
+            }
+        };
+        Ok(ast::InsertAction {
+            span: self.node_span(start),
+            kind,
+        })
+    }
+
+    fn parse_values_row(&mut self) -> Result<Vec<ast::Expr>, ParseError> {
+        self.expect_token(TokenKind::LParen)?;
+        let row = self.parse_expr_list_or_invalid(recovery::LIST_ITEM);
+        self.expect_token(TokenKind::RParen)?;
+        Ok(row)
+    }
+
+    fn parse_upsert_clause(&mut self) -> Result<ast::UpsertClause, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::On);
+        self.expect_token(TokenKind::Conflict)?;
+        let mut target = None;
+        if self.at(TokenKind::LParen) {
+            let target_start = self.node_start();
+            self.bump(TokenKind::LParen);
+            let columns = self.parse_indexed_column_list()?;
+            self.expect_token(TokenKind::RParen)?;
+            let mut where_clause = None;
+            if self.eat(TokenKind::Where) {
+                where_clause = Some(self.parse_expr()?);
+            }
+            target = Some(ast::UpsertTarget {
+                span: self.node_span(target_start),
+                columns,
+                where_clause,
+            });
+        }
+        self.expect_token(TokenKind::Do)?;
+        let action = if self.eat(TokenKind::Nothing) {
+            ast::UpsertAction::Nothing
+        } else {
+            let update_start = self.node_start();
+            self.expect_token(TokenKind::Update)?;
+            self.expect_token(TokenKind::Set)?;
+            let assignments = self.parse_assignment_list()?;
+            let mut where_clause = None;
+            if self.eat(TokenKind::Where) {
+                where_clause = Some(self.parse_expr()?);
+            }
+            ast::UpsertAction::Update(ast::UpsertUpdate {
+                span: self.node_span(update_start),
+                assignments,
+                where_clause,
+            })
+        };
+        Ok(ast::UpsertClause {
+            span: self.node_span(start),
+            target,
+            action,
+        })
+    }
+
+    pub(crate) fn parse_assignment_list(&mut self) -> Result<Vec<ast::Assignment>, ParseError> {
+        let mut assignments = vec![self.parse_assignment()?];
+        while self.eat(TokenKind::Comma) {
+            assignments.push(self.parse_assignment()?);
+        }
+        Ok(assignments)
+    }
+
+    fn parse_assignment(&mut self) -> Result<ast::Assignment, ParseError> {
+        let start = self.node_start();
+        let target = if self.eat(TokenKind::LParen) {
+            let columns = self.parse_column_name_list()?;
+            self.expect_token(TokenKind::RParen)?;
+            ast::AssignmentTarget::ColumnList(columns)
+        } else {
+            ast::AssignmentTarget::Column(self.parse_identifier()?)
+        };
+        self.expect_token(TokenKind::Equals)?;
+        let expr = self.parse_expr_or_invalid(recovery::CLAUSE_ITEM);
+        Ok(ast::Assignment {
+            span: self.node_span(start),
+            target,
+            expr,
+        })
+    }
+
+    pub(crate) fn parse_returning_clause(&mut self) -> Result<ast::ReturningClause, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::Returning);
+        let columns = self.parse_result_column_list()?;
+        Ok(ast::ReturningClause {
+            span: self.node_span(start),
+            columns,
+        })
+    }
+
+    pub(crate) fn parse_indexed_column_list(&mut self) -> Result<Vec<ast::IndexedColumn>, ParseError> {
+        let mut columns = vec![self.parse_indexed_column()?];
+        while self.eat(TokenKind::Comma) {
+            columns.push(self.parse_indexed_column()?);
+        }
+        Ok(columns)
+    }
+
+    fn parse_indexed_column(&mut self) -> Result<ast::IndexedColumn, ParseError> {
+        let start = self.node_start();
+        let (expr, collation) = self.parse_expr_with_collation()?;
+        let order = self.parse_order();
+        Ok(ast::IndexedColumn {
+            span: self.node_span(start),
+            expr,
+            collation,
+            order,
+        })
+    }
+
+    pub(crate) fn parse_update(&mut self) -> Result<ast::StmtUpdate, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::Update)?;
+        let mut conflict_action = None;
+        if self.eat(TokenKind::Or) {
+            conflict_action = Some(self.parse_conflict_action()?);
+        }
+        let table = self.parse_quantified_table_name()?;
+        self.expect_token(TokenKind::Set)?;
+        let assignments = self.parse_assignment_list()?;
+        let mut from = None;
+        if self.at(TokenKind::From) {
+            from = Some(self.parse_from_clause()?);
+        }
+        let mut where_clause = None;
+        if self.eat(TokenKind::Where) {
+            where_clause = Some(self.parse_expr_or_invalid(recovery::CLAUSE));
+        }
+        let mut returning = None;
+        if self.at(TokenKind::Returning) {
+            returning = Some(self.parse_returning_clause()?);
+        }
+        Ok(ast::StmtUpdate {
+            span: self.node_span(start),
+            with: None,
+            conflict_action,
+            table,
+            assignments,
+            from,
+            where_clause,
+            returning,
+        })
+    }
+
+    pub(crate) fn parse_delete(&mut self) -> Result<ast::StmtDelete, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::Delete)?;
+        self.expect_token(TokenKind::From)?;
+        let table = self.parse_quantified_table_name()?;
+        let mut where_clause = None;
+        if self.eat(TokenKind::Where) {
+            where_clause = Some(self.parse_expr_or_invalid(recovery::CLAUSE));
+        }
+        let mut returning = None;
+        if self.at(TokenKind::Returning) {
+            returning = Some(self.parse_returning_clause()?);
+        }
+        Ok(ast::StmtDelete {
+            span: self.node_span(start),
+            with: None,
+            table,
+            where_clause,
+            returning,
+        })
+    }
+
+    /// Parse `table-name [[AS] alias]? [INDEXED BY index-name | NOT INDEXED]?`
+    pub(crate) fn parse_quantified_table_name(&mut self) -> Result<ast::QuantifiedTableName, ParseError> {
+        let start = self.node_start();
+        let id = self.parse_identifier_or_invalid(recovery::TABLE_NAME);
+        let alias = self.parse_alias();
+        let mut indexed = None;
+        if self.eat(TokenKind::Indexed) {
+            self.expect_token(TokenKind::By)?;
+            indexed = Some(ast::IndexedHint::IndexedBy(self.parse_identifier()?));
+        } else if self.eat(TokenKind::Not) {
+            self.expect_token(TokenKind::Indexed)?;
+            indexed = Some(ast::IndexedHint::NotIndexed);
+        }
+        Ok(ast::QuantifiedTableName {
+            span: self.node_span(start),
+            id,
+            alias,
+            indexed,
+        })
+    }
+
+    pub(crate) fn parse_create(&mut self) -> Result<ast::StmtCreate, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::Create)?;
+        let (exist_check, id, kind) = match self.current_token_kind() {
+            TokenKind::Table => {
+                self.bump(TokenKind::Table);
+                let exist_check = self.parse_not_exists_check()?;
+                let id = self.parse_identifier_or_invalid(&[TokenKind::LParen, TokenKind::As]);
+                (exist_check, id, ast::CreateKind::Table(self.parse_create_table()?))
+            }
+            TokenKind::Unique | TokenKind::Index => {
+                let index_start = self.node_start();
+                let unique = self.eat(TokenKind::Unique);
+                self.expect_token(TokenKind::Index)?;
+                let exist_check = self.parse_not_exists_check()?;
+                let id = self.parse_identifier_or_invalid(&[TokenKind::On]);
+                let index = self.parse_create_index(index_start, unique)?;
+                (exist_check, id, ast::CreateKind::Index(index))
+            }
+            _ => {
+                return Err(ParseError {
+                    span: self.current_token_span(),
+                    kind: ParseErrorKind::InvalidCreateTarget,
+                })
+            }
+        };
+        Ok(ast::StmtCreate {
+            span: self.node_span(start),
+            exist_check,
+            id,
+            kind,
+        })
+    }
+
+    // Parse `ON table-name (<indexed-column-list>) [WHERE <expr>]?`, the
+    // remainder of a create index statement following the index name.
+    fn parse_create_index(
+        &mut self,
+        start: Location,
+        unique: bool,
+    ) -> Result<ast::CreateIndex, ParseError> {
+        self.expect_token(TokenKind::On)?;
+        let table = self.parse_identifier()?;
+        self.expect_token(TokenKind::LParen)?;
+        let columns = self.parse_indexed_column_list()?;
+        self.expect_token(TokenKind::RParen)?;
+        let mut where_clause = None;
+        if self.eat(TokenKind::Where) {
+            where_clause = Some(self.parse_expr()?);
+        }
+        Ok(ast::CreateIndex {
+            span: self.node_span(start),
+            unique,
+            table,
+            columns,
+            where_clause,
+        })
+    }
+
+    // Parse an optional `IF NOT EXISTS`
+    fn parse_not_exists_check(&mut self) -> Result<bool, ParseError> {
+        if !self.eat(TokenKind::If) {
+            return Ok(false);
+        }
+        self.expect_token(TokenKind::Not)?;
+        self.expect_token(TokenKind::Exists)?;
+        Ok(true)
+    }
+
+    fn parse_create_table(&mut self) -> Result<ast::CreateTable, ParseError> {
+        let start = self.node_start();
+        let source = if self.eat(TokenKind::As) {
+            ast::CreateTableSource::Select(Box::new(self.parse_select()?))
+        } else {
+            ast::CreateTableSource::Columns(self.parse_create_table_columns()?)
+        };
+        Ok(ast::CreateTable {
+            span: self.node_span(start),
+            source,
+        })
+    }
+
+    fn parse_create_table_columns(&mut self) -> Result<ast::CreateTableColumns, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::LParen)?;
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+        let mut first = true;
+        loop {
+            // The first element is a column. Once the first table constraint
+            // is found, only table constraints may follow. Elements that fail
+            // to parse are left out.
+            let parsed = if !first
+                && (!constraints.is_empty() || self.current_token_kind().is_table_constraint())
+            {
+                self.parse_table_constraint().map(|constraint| constraints.push(constraint))
+            } else {
+                self.parse_column_def().map(|column| columns.push(column))
+            };
+            if let Err(error) = parsed {
+                self.recover(error, recovery::TABLE_ELEMENT);
+            }
+            first = false;
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenKind::RParen);
+        Ok(ast::CreateTableColumns {
+            span: self.node_span(start),
+            columns,
+            constraints,
+        })
+    }
+
+    fn parse_table_constraint(&mut self) -> Result<ast::TableConstraint, ParseError> {
+        let start = self.node_start();
+        let mut name = None;
+        if self.eat(TokenKind::Constraint) {
+            name = Some(self.parse_identifier()?);
+        }
+        let kind = match self.current_token_kind() {
+            TokenKind::Primary => {
+                self.bump(TokenKind::Primary);
+                self.expect_token(TokenKind::Key)?;
+                ast::TableConstraintKind::PrimaryKey(self.parse_table_constraint_key()?)
+            }
+            TokenKind::Unique => {
+                self.bump(TokenKind::Unique);
+                ast::TableConstraintKind::Unique(self.parse_table_constraint_key()?)
+            }
+            TokenKind::Check => {
+                self.bump(TokenKind::Check);
+                self.expect_token(TokenKind::LParen)?;
+                let expr = self.parse_expr()?;
+                self.expect_token(TokenKind::RParen)?;
+                ast::TableConstraintKind::Check(expr)
+            }
+            TokenKind::Foreign => {
+                let foreign_start = self.node_start();
+                self.bump(TokenKind::Foreign);
+                self.expect_token(TokenKind::Key)?;
+                self.expect_token(TokenKind::LParen)?;
+                let columns = self.parse_column_name_list()?;
+                self.expect_token(TokenKind::RParen)?;
+                self.expect_token(TokenKind::References)?;
+                let clause = self.parse_foreign_key_clause()?;
+                ast::TableConstraintKind::ForeignKey(ast::TableConstraintForeignKey {
+                    span: self.node_span(foreign_start),
+                    columns,
+                    clause,
+                })
+            }
+            found => {
+                return Err(ParseError {
+                    span: self.current_token_span(),
+                    kind: ParseErrorKind::UnexpectedToken { found }
+                })
+            }
+        };
+        Ok(ast::TableConstraint {
+            span: self.node_span(start),
+            name,
+            kind,
+        })
+    }
+
+    // Parse `(<indexed-column-list>) <conflict-clause>`
+    fn parse_table_constraint_key(&mut self) -> Result<ast::TableConstraintKey, ParseError> {
+        let start = self.node_start();
+        self.expect_token(TokenKind::LParen)?;
+        let columns = self.parse_indexed_column_list()?;
+        self.expect_token(TokenKind::RParen)?;
+        let mut conflict_action = None;
+        if self.eat(TokenKind::On) {
+            self.expect_token(TokenKind::Conflict)?;
+            conflict_action = Some(self.parse_conflict_action()?);
+        }
+        Ok(ast::TableConstraintKey {
+            span: self.node_span(start),
+            columns,
+            conflict_action,
+        })
+    }
+}
+
+// Split the collation off an expression, which the parser reads as a COLLATE
+// expression.
+fn split_collation(expr: ast::Expr) -> (ast::Expr, Option<ast::Identifier>) {
+    match expr {
+        ast::Expr::Collate(collate) => (*collate.expr, Some(collate.collation)),
+        expr => (expr, None),
+    }
 }
@@ -1,29 +1,557 @@
+use ast::name::Name;
+use source_index::location::Location;
+use source_index::span::Span;
+
 use super::Parser;
 use crate::errors::{ParseError, ParseErrorKind};
 use crate::tokens::{TokenKind, TokenValue};
+use crate::Parameter;
 
 impl<'src> Parser<'src> {
+    /// Parse an expression using precedence climbing.
+    pub(crate) fn parse_expr(&mut self) -> Result<ast::Expr, ParseError> {
+        self.parse_expr_with_precedence(Precedence::Lowest)
+    }
+
+    pub(crate) fn parse_expr_list(&mut self) -> Result<Vec<ast::Expr>, ParseError> {
+        let mut exprs = vec![self.parse_expr()?];
+        while self.eat(TokenKind::Comma) {
+            exprs.push(self.parse_expr()?);
+        }
+        Ok(exprs)
+    }
+
+    // Parse an expression whose infix operators all bind tighter than `min`.
+    fn parse_expr_with_precedence(&mut self, min: Precedence) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        let mut lhs = self.parse_prefix_expr()?;
+        loop {
+            let precedence = self.current_infix_precedence();
+            if precedence <= min {
+                break;
+            }
+            lhs = self.parse_infix_expr(lhs, start, precedence)?;
+        }
+        Ok(lhs)
+    }
+
+    // The binding power of the current token when it follows an expression.
+    fn current_infix_precedence(&mut self) -> Precedence {
+        match self.current_token_kind() {
+            TokenKind::Or => Precedence::Or,
+            TokenKind::And => Precedence::And,
+            TokenKind::Equals
+            | TokenKind::NotEqual
+            | TokenKind::Is
+            | TokenKind::In
+            | TokenKind::Like
+            | TokenKind::Between => Precedence::Equality,
+            // NOT only continues an expression as part of NOT NULL, NOT LIKE,
+            // NOT BETWEEN or NOT IN.
+            TokenKind::Not => match self.peek() {
+                TokenKind::Null | TokenKind::Like | TokenKind::Between | TokenKind::In => {
+                    Precedence::Equality
+                }
+                _ => Precedence::Lowest,
+            },
+            TokenKind::Greater
+            | TokenKind::GreaterEqual
+            | TokenKind::Less
+            | TokenKind::LessEqual => Precedence::Comparison,
+            TokenKind::Plus | TokenKind::Minus => Precedence::Additive,
+            TokenKind::Star | TokenKind::Slash | TokenKind::Percent => Precedence::Multiplicative,
+            TokenKind::Collate => Precedence::Collate,
+            _ => Precedence::Lowest,
+        }
+    }
+
+    fn parse_prefix_expr(&mut self) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        match self.current_token_kind() {
+            kind if kind.is_name() && self.peek() == TokenKind::LParen => self.parse_function_call(),
+            kind if kind.is_name() || kind == TokenKind::TableCol => {
+                Ok(ast::Expr::Column(self.parse_column_ref()?))
+            }
+            TokenKind::Not => {
+                self.bump(TokenKind::Not);
+                if self.at(TokenKind::Exists) {
+                    return self.parse_exists_expr(start, true);
+                }
+                let operand = self.parse_expr_with_precedence(Precedence::Not)?;
+                Ok(ast::Expr::UnaryOp(ast::ExprUnaryOp {
+                    span: self.node_span(start),
+                    op: ast::UnaryOperator::Not,
+                    operand: Box::new(operand),
+                }))
+            }
+            TokenKind::Minus | TokenKind::Plus => {
+                let op = if self.eat(TokenKind::Minus) {
+                    ast::UnaryOperator::Negative
+                } else {
+                    self.bump(TokenKind::Plus);
+                    ast::UnaryOperator::Positive
+                };
+                let operand = self.parse_expr_with_precedence(Precedence::Unary)?;
+                Ok(ast::Expr::UnaryOp(ast::ExprUnaryOp {
+                    span: self.node_span(start),
+                    op,
+                    operand: Box::new(operand),
+                }))
+            }
+            TokenKind::Exists => self.parse_exists_expr(start, false),
+            TokenKind::Parameter => self.parse_parameter(),
+            TokenKind::Case => self.parse_case_expr(),
+            TokenKind::Cast => self.parse_cast_expr(),
+            TokenKind::LParen => {
+                self.bump(TokenKind::LParen);
+                if self.current_token_kind().is_select_start() {
+                    let select = self.parse_select()?;
+                    self.expect_token(TokenKind::RParen)?;
+                    return Ok(ast::Expr::Subquery(ast::ExprSubquery {
+                        span: self.node_span(start),
+                        select: Box::new(select),
+                    }));
+                }
+                let exprs = self.parse_expr_list()?;
+                self.expect_token(TokenKind::RParen)?;
+                Ok(ast::Expr::List(ast::ExprList {
+                    span: self.node_span(start),
+                    exprs,
+                }))
+            }
+            _ => Ok(ast::Expr::LiteralValue(self.parse_literal()?)),
+        }
+    }
+
+    // Parse `function-name([[DISTINCT]? <expr-list> | *]?)` followed by
+    // optional FILTER and OVER clauses.
+    fn parse_function_call(&mut self) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        let name = self.parse_identifier()?;
+        self.bump(TokenKind::LParen);
+        let mut distinct = false;
+        let args = if self.eat(TokenKind::Star) {
+            ast::FunctionArgs::Star
+        } else if self.at(TokenKind::RParen) {
+            ast::FunctionArgs::List(Vec::new())
+        } else {
+            distinct = self.eat(TokenKind::Distinct);
+            ast::FunctionArgs::List(self.parse_expr_list()?)
+        };
+        self.expect_token(TokenKind::RParen)?;
+        let mut filter = None;
+        if self.eat(TokenKind::Filter) {
+            self.expect_token(TokenKind::LParen)?;
+            self.expect_token(TokenKind::Where)?;
+            filter = Some(Box::new(self.parse_expr()?));
+            self.expect_token(TokenKind::RParen)?;
+        }
+        let mut over = None;
+        if self.eat(TokenKind::Over) {
+            over = Some(if self.at(TokenKind::LParen) {
+                ast::OverClause::Window(Box::new(self.parse_window_spec()?))
+            } else {
+                ast::OverClause::Named(self.parse_identifier()?)
+            });
+        }
+        Ok(ast::Expr::Function(ast::ExprFunction {
+            span: self.node_span(start),
+            name,
+            distinct,
+            args,
+            filter,
+            over,
+        }))
+    }
+
+    fn parse_case_expr(&mut self) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::Case);
+        let mut operand = None;
+        if !self.at(TokenKind::When) {
+            operand = Some(Box::new(self.parse_expr()?));
+        }
+        let mut branches = Vec::new();
+        while self.at(TokenKind::When) || branches.is_empty() {
+            let branch_start = self.node_start();
+            self.expect_token(TokenKind::When)?;
+            let condition = self.parse_expr()?;
+            self.expect_token(TokenKind::Then)?;
+            let result = self.parse_expr()?;
+            branches.push(ast::CaseBranch {
+                span: self.node_span(branch_start),
+                condition,
+                result,
+            });
+        }
+        let mut else_expr = None;
+        if self.eat(TokenKind::Else) {
+            else_expr = Some(Box::new(self.parse_expr()?));
+        }
+        self.expect_token(TokenKind::End)?;
+        Ok(ast::Expr::Case(ast::ExprCase {
+            span: self.node_span(start),
+            operand,
+            branches,
+            else_expr,
+        }))
+    }
+
+    fn parse_cast_expr(&mut self) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        self.bump(TokenKind::Cast);
+        self.expect_token(TokenKind::LParen)?;
+        let expr = self.parse_expr()?;
+        self.expect_token(TokenKind::As)?;
+        let type_name = self.parse_type_name()?;
+        self.expect_token(TokenKind::RParen)?;
+        Ok(ast::Expr::Cast(ast::ExprCast {
+            span: self.node_span(start),
+            expr: Box::new(expr),
+            type_name,
+        }))
+    }
+
+    fn parse_parameter(&mut self) -> Result<ast::Expr, ParseError> {
+        let start = self.node_start();
+        let TokenValue::Parameter(kind) = self.bump_value(TokenKind::Parameter) else {
+            return Err(ParseError {
+                span: self.node_span(start),
+                kind: ParseErrorKind::ExpectedValue,
+            });
+        };
+        let index = self.register_parameter(&kind);
+        Ok(ast::Expr::Parameter(ast::ExprParameter {
+            span: self.node_span(start),
+            kind,
+            index,
+        }))
+    }
+
+    // Assign an index to a parameter the same way SQLite does and record it
+    // for the current statement.
+    fn register_parameter(&mut self, kind: &ast::ParameterKind) -> u32 {
+        let next = self.parameters.last().map_or(1, |last| last.index + 1);
+        let (index, name) = match kind {
+            ast::ParameterKind::Anonymous => (next, None),
+            ast::ParameterKind::Numbered(number) => (*number, None),
+            ast::ParameterKind::Named(name) => {
+                let existing = self
+                    .parameters
+                    .iter()
+                    .find(|param| param.name.as_ref() == Some(name));
+                match existing {
+                    Some(param) => return param.index,
+                    None => (next, Some(name.clone())),
+                }
+            }
+        };
+        if let Err(position) = self.parameters.binary_search_by_key(&index, |param| param.index) {
+            self.parameters.insert(position, Parameter { index, name });
+        }
+        index
+    }
+
+    // Parse `EXISTS (<select-stmt>)`. A leading NOT has already been consumed
+    // by the caller when `negated` is set.
+    fn parse_exists_expr(
+        &mut self,
+        start: Location,
+        negated: bool,
+    ) -> Result<ast::Expr, ParseError> {
+        self.bump(TokenKind::Exists);
+        self.expect_token(TokenKind::LParen)?;
+        let select = self.parse_select()?;
+        self.expect_token(TokenKind::RParen)?;
+        Ok(ast::Expr::Exists(ast::ExprExists {
+            span: self.node_span(start),
+            negated,
+            select: Box::new(select),
+        }))
+    }
+
+    fn parse_infix_expr(
+        &mut self,
+        lhs: ast::Expr,
+        start: Location,
+        precedence: Precedence,
+    ) -> Result<ast::Expr, ParseError> {
+        let left = Box::new(lhs);
+        match self.current_token_kind() {
+            TokenKind::Or | TokenKind::And => {
+                let op = if self.eat(TokenKind::Or) {
+                    ast::BooleanOperator::Or
+                } else {
+                    self.bump(TokenKind::And);
+                    ast::BooleanOperator::And
+                };
+                let right = self.parse_expr_with_precedence(precedence)?;
+                Ok(ast::Expr::BoolOp(ast::ExprBoolOp {
+                    span: self.node_span(start),
+                    left,
+                    op,
+                    right: Box::new(right),
+                }))
+            }
+            kind @ (TokenKind::Equals
+            | TokenKind::NotEqual
+            | TokenKind::Greater
+            | TokenKind::GreaterEqual
+            | TokenKind::Less
+            | TokenKind::LessEqual) => {
+                self.bump(kind);
+                let op = match kind {
+                    TokenKind::Equals => ast::CmpOperator::Eq,
+                    TokenKind::NotEqual => ast::CmpOperator::NotEq,
+                    TokenKind::Greater => ast::CmpOperator::Gt,
+                    TokenKind::GreaterEqual => ast::CmpOperator::GtE,
+                    TokenKind::Less => ast::CmpOperator::Lt,
+                    _ => ast::CmpOperator::LtE,
+                };
+                let right = self.parse_expr_with_precedence(precedence)?;
+                Ok(ast::Expr::Compare(ast::ExprCompare {
+                    span: self.node_span(start),
+                    left,
+                    op,
+                    right: Box::new(right),
+                }))
+            }
+            kind @ (TokenKind::Plus
+            | TokenKind::Minus
+            | TokenKind::Star
+            | TokenKind::Slash
+            | TokenKind::Percent) => {
+                self.bump(kind);
+                let op = match kind {
+                    TokenKind::Plus => ast::BinaryOperator::Add,
+                    TokenKind::Minus => ast::BinaryOperator::Sub,
+                    TokenKind::Star => ast::BinaryOperator::Mult,
+                    TokenKind::Slash => ast::BinaryOperator::Div,
+                    _ => ast::BinaryOperator::Mod,
+                };
+                let right = self.parse_expr_with_precedence(precedence)?;
+                Ok(ast::Expr::BinOp(ast::ExprBinaryOp {
+                    span: self.node_span(start),
+                    left,
+                    op,
+                    right: Box::new(right),
+                }))
+            }
+            TokenKind::Collate => {
+                self.bump(TokenKind::Collate);
+                let collation = self.parse_identifier()?;
+                Ok(ast::Expr::Collate(ast::ExprCollate {
+                    span: self.node_span(start),
+                    expr: left,
+                    collation,
+                }))
+            }
+            TokenKind::Is => {
+                self.bump(TokenKind::Is);
+                let negated = self.eat(TokenKind::Not);
+                if self.eat(TokenKind::Null) {
+                    return Ok(ast::Expr::IsNull(ast::ExprIsNull {
+                        span: self.node_span(start),
+                        expr: left,
+                        negated,
+                    }));
+                }
+                let distinct_from = self.eat(TokenKind::Distinct);
+                if distinct_from {
+                    self.expect_token(TokenKind::From)?;
+                }
+                let right = self.parse_expr_with_precedence(precedence)?;
+                Ok(ast::Expr::Is(ast::ExprIs {
+                    span: self.node_span(start),
+                    left,
+                    negated,
+                    distinct_from,
+                    right: Box::new(right),
+                }))
+            }
+            _ => {
+                let negated = self.eat(TokenKind::Not);
+                match self.current_token_kind() {
+                    TokenKind::Null => {
+                        self.bump(TokenKind::Null);
+                        Ok(ast::Expr::IsNull(ast::ExprIsNull {
+                            span: self.node_span(start),
+                            expr: left,
+                            negated: true,
+                        }))
+                    }
+                    TokenKind::Like => {
+                        self.bump(TokenKind::Like);
+                        let pattern = self.parse_expr_with_precedence(precedence)?;
+                        Ok(ast::Expr::Like(ast::ExprLike {
+                            span: self.node_span(start),
+                            expr: left,
+                            negated,
+                            pattern: Box::new(pattern),
+                        }))
+                    }
+                    TokenKind::Between => {
+                        self.bump(TokenKind::Between);
+                        let low = self.parse_expr_with_precedence(precedence)?;
+                        self.expect_token(TokenKind::And)?;
+                        let high = self.parse_expr_with_precedence(precedence)?;
+                        Ok(ast::Expr::Between(ast::ExprBetween {
+                            span: self.node_span(start),
+                            expr: left,
+                            negated,
+                            low: Box::new(low),
+                            high: Box::new(high),
+                        }))
+                    }
+                    TokenKind::In => {
+                        self.bump(TokenKind::In);
+                        let kind = self.parse_in_target()?;
+                        Ok(ast::Expr::In(ast::ExprIn {
+                            span: self.node_span(start),
+                            expr: left,
+                            negated,
+                            kind,
+                        }))
+                    }
+                    found => Err(ParseError {
+                        span: self.current_token_span(),
+                        kind: ParseErrorKind::UnexpectedToken { found },
+                    }),
+                }
+            }
+        }
+    }
+
+    // Parse the right hand side of IN, `table-name` or `([<select-stmt> | <expr-list>]?)`
+    fn parse_in_target(&mut self) -> Result<ast::ExprInKind, ParseError> {
+        if !self.eat(TokenKind::LParen) {
+            return Ok(ast::ExprInKind::Table(self.parse_identifier()?));
+        }
+        let kind = if self.current_token_kind().is_select_start() {
+            ast::ExprInKind::Select(Box::new(self.parse_select()?))
+        } else if self.at(TokenKind::RParen) {
+            ast::ExprInKind::List(Vec::new())
+        } else {
+            ast::ExprInKind::List(self.parse_expr_list()?)
+        };
+        self.expect_token(TokenKind::RParen)?;
+        Ok(kind)
+    }
+
+    /// Parse a column reference, `[table-name.]?column-name`.
+    pub(crate) fn parse_column_ref(&mut self) -> Result<ast::ExprColumn, ParseError> {
+        let start = self.node_start();
+        if self.at(TokenKind::TableCol) {
+            let TokenValue::TableCol(table, column) = self.bump_value(TokenKind::TableCol) else {
+                return Err(ParseError {
+                    span: self.node_span(start),
+                    kind: ParseErrorKind::ExpectedValue,
+                });
+            };
+            // The lexer produces a single token for `table.column`, split the
+            // span so that each identifier points at its own text.
+            let span = self.node_span(start);
+            let text = &self.source[span];
+            let table_end = *span.start() + quoted_len(table.as_str(), text.chars().next());
+            let column_start = *span.end() - quoted_len(column.as_str(), text.chars().last());
+            return Ok(ast::ExprColumn {
+                span,
+                table: Some(ast::Identifier::new(
+                    table,
+                    Span::new(span.start(), Location::new(table_end)),
+                )),
+                column: ast::Identifier::new(
+                    column,
+                    Span::new(Location::new(column_start), span.end()),
+                ),
+            });
+        }
+        let column = self.parse_identifier()?;
+        Ok(ast::ExprColumn {
+            span: self.node_span(start),
+            table: None,
+            column,
+        })
+    }
+
     /// Parse an identifier.
     ///
     /// For invalid identifiers, the 'id' field will be an empty string.
+    /// Non-reserved keywords are names as spelled in the source.
     pub(crate) fn parse_identifier(&mut self) -> Result<ast::Identifier, ParseError> {
         let start = self.node_start();
+        let kind = self.current_token_kind();
 
-        if self.at(TokenKind::Name) {
+        if kind == TokenKind::Name {
             let TokenValue::Name(name) = self.bump_value(TokenKind::Name) else {
                 unreachable!();
             };
             Ok(ast::Identifier {id: name, span: self.node_span(start)})
+        } else if kind.is_non_reserved() {
+            let name = Name::new(self.source[self.current_token_span()].to_string());
+            self.bump(kind);
+            Ok(ast::Identifier {id: name, span: self.node_span(start)})
         } else {
             Err(ParseError {
                 kind: ParseErrorKind::ExpectedIdentifier { found: self.current_token_kind() },
-                span: self.node_span(start),
+                span: self.current_token_span(),
             })
         }
     }
 
     pub(crate) fn parse_literal(&mut self) -> Result<ast::LiteralValue, ParseError> {
-
+        let start = self.node_start();
+        let kind = self.current_token_kind();
+        match kind {
+            TokenKind::Int | TokenKind::Float | TokenKind::String | TokenKind::Blob => {
+                let literal = match self.bump_value(kind) {
+                    TokenValue::Int(value) => ast::LiteralValue::IntLiteral(ast::IntLiteral {
+                        span: self.node_span(start),
+                        value,
+                    }),
+                    TokenValue::Float(value) => {
+                        ast::LiteralValue::FloatLiteral(ast::FloatLiteral {
+                            span: self.node_span(start),
+                            value,
+                        })
+                    }
+                    TokenValue::String(value) => {
+                        ast::LiteralValue::StringLiteral(ast::StringLiteral {
+                            span: self.node_span(start),
+                            value,
+                        })
+                    }
+                    TokenValue::Blob(value) => ast::LiteralValue::Blob(ast::BlobLiteral {
+                        span: self.node_span(start),
+                        value,
+                    }),
+                    _ => {
+                        return Err(ParseError {
+                            span: self.node_span(start),
+                            kind: ParseErrorKind::ExpectedValue,
+                        })
+                    }
+                };
+                Ok(literal)
+            }
+            TokenKind::Null => {
+                self.bump(TokenKind::Null);
+                Ok(ast::LiteralValue::NullLiteral(ast::NullLiteral {
+                    span: self.node_span(start),
+                }))
+            }
+            TokenKind::True | TokenKind::False => {
+                self.bump(kind);
+                Ok(ast::LiteralValue::BoolLiteral(ast::BoolLiteral {
+                    span: self.node_span(start),
+                    value: kind == TokenKind::True,
+                }))
+            }
+            _ => Err(ParseError {
+                span: self.current_token_span(),
+                kind: ParseErrorKind::ExpectedExpression { found: kind },
+            }),
+        }
     }
 
     pub(crate) fn parse_signed_number(&mut self) -> Result<ast::SignedNumber, ParseError> {
@@ -43,10 +571,10 @@ impl<'src> Parser<'src> {
                         value: negation * inner_value
                     }));
                 }
-                return Err(ParseError {
+                Err(ParseError {
                     span: self.node_span(start),
                     kind: ParseErrorKind::ExpectedValue
-                });
+                })
 
 
             }
@@ -58,13 +586,13 @@ impl<'src> Parser<'src> {
                         value: negation as f64 * inner_value
                     }));
                 }
-                return Err(ParseError {
+                Err(ParseError {
                     span: self.node_span(start),
                     kind: ParseErrorKind::ExpectedValue
-                });
+                })
             }
             _ => {
-                return Err(ParseError {
+                Err(ParseError {
                     span: self.node_span(start),
                     kind: ParseErrorKind::ExpectedNumeric {
                         found: self.current_token_kind()
@@ -74,3 +602,308 @@ impl<'src> Parser<'src> {
         }
     }
 }
+
+// Length of an identifier in the source, given the character at its outer
+// edge. Quoted identifiers have their surrounding and doubled quotes restored.
+fn quoted_len(name: &str, edge: Option<char>) -> usize {
+    match edge {
+        Some(quote @ ('"' | '`')) => name.len() + 2 + name.matches(quote).count(),
+        _ => name.len(),
+    }
+}
+
+/// Binding power of infix and prefix operators, from loosest to tightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Lowest,
+    Or,
+    And,
+    Not,
+    // =, !=, IS, IN, LIKE, BETWEEN
+    Equality,
+    // <, <=, >, >=
+    Comparison,
+    Additive,
+    Multiplicative,
+    Unary,
+    Collate,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(source: &str) -> ast::Expr {
+        let mut parser = Parser::new(source);
+        let expr = parser.parse_expr().expect("expression should parse");
+        assert!(parser.errors.is_empty(), "unexpected errors {:?}", parser.errors);
+        assert_eq!(parser.current_token_kind(), TokenKind::EndOfFile);
+        expr
+    }
+
+    #[test]
+    fn arithmetic_precedence() {
+        let ast::Expr::BinOp(add) = parse("1 + 2 * 3") else {
+            panic!("expected addition at the root");
+        };
+        assert_eq!(add.op, ast::BinaryOperator::Add);
+        assert_eq!(add.span, Span::new(Location::new(0), Location::new(9)));
+        let ast::Expr::BinOp(ref mult) = *add.right else {
+            panic!("expected multiplication on the right");
+        };
+        assert_eq!(mult.op, ast::BinaryOperator::Mult);
+        assert_eq!(mult.span, Span::new(Location::new(4), Location::new(9)));
+
+        // left associative
+        let ast::Expr::BinOp(sub) = parse("5 - 3 - 1") else {
+            panic!("expected subtraction at the root");
+        };
+        assert!(matches!(*sub.left, ast::Expr::BinOp(_)));
+        assert!(matches!(*sub.right, ast::Expr::LiteralValue(_)));
+    }
+
+    #[test]
+    fn unary_precedence() {
+        let ast::Expr::BinOp(mult) = parse("-a * b") else {
+            panic!("expected multiplication at the root");
+        };
+        assert!(matches!(
+            *mult.left,
+            ast::Expr::UnaryOp(ast::ExprUnaryOp { op: ast::UnaryOperator::Negative, .. })
+        ));
+
+        let ast::Expr::UnaryOp(not) = parse("NOT a = b") else {
+            panic!("expected NOT at the root");
+        };
+        assert!(matches!(*not.operand, ast::Expr::Compare(_)));
+    }
+
+    #[test]
+    fn boolean_precedence() {
+        let ast::Expr::BoolOp(or) = parse("a OR b AND c > 1") else {
+            panic!("expected OR at the root");
+        };
+        assert_eq!(or.op, ast::BooleanOperator::Or);
+        let ast::Expr::BoolOp(ref and) = *or.right else {
+            panic!("expected AND on the right");
+        };
+        assert_eq!(and.op, ast::BooleanOperator::And);
+        assert!(matches!(
+            *and.right,
+            ast::Expr::Compare(ast::ExprCompare { op: ast::CmpOperator::Gt, .. })
+        ));
+    }
+
+    #[test]
+    fn between_binds_its_and() {
+        let ast::Expr::BoolOp(and) = parse("x NOT BETWEEN 1 AND 2 + 3 AND y") else {
+            panic!("expected AND at the root");
+        };
+        let ast::Expr::Between(ref between) = *and.left else {
+            panic!("expected BETWEEN on the left");
+        };
+        assert!(between.negated);
+        assert!(matches!(*between.high, ast::Expr::BinOp(_)));
+    }
+
+    #[test]
+    fn null_checks() {
+        assert!(matches!(
+            parse("c IS NULL"),
+            ast::Expr::IsNull(ast::ExprIsNull { negated: false, .. })
+        ));
+        assert!(matches!(
+            parse("c IS NOT NULL"),
+            ast::Expr::IsNull(ast::ExprIsNull { negated: true, .. })
+        ));
+        assert!(matches!(
+            parse("c NOT NULL"),
+            ast::Expr::IsNull(ast::ExprIsNull { negated: true, .. })
+        ));
+        assert!(matches!(
+            parse("c IS NOT DISTINCT FROM d"),
+            ast::Expr::Is(ast::ExprIs { negated: true, distinct_from: true, .. })
+        ));
+    }
+
+    #[test]
+    fn like_and_collate() {
+        let ast::Expr::Like(like) = parse("t.name COLLATE nocase NOT LIKE 'a%'") else {
+            panic!("expected LIKE at the root");
+        };
+        assert!(like.negated);
+        let ast::Expr::Collate(ref collate) = *like.expr else {
+            panic!("expected COLLATE on the left");
+        };
+        assert_eq!(collate.collation.id.as_str(), "nocase");
+        assert!(matches!(*collate.expr, ast::Expr::Column(ast::ExprColumn { table: Some(_), .. })));
+    }
+
+    #[test]
+    fn in_targets() {
+        let ast::Expr::In(in_list) = parse("c IN (1, 2, 3)") else {
+            panic!("expected IN");
+        };
+        assert!(!in_list.negated);
+        assert!(matches!(in_list.kind, ast::ExprInKind::List(ref exprs) if exprs.len() == 3));
+
+        let ast::Expr::In(in_select) = parse("c NOT IN (SELECT c FROM t1)") else {
+            panic!("expected IN");
+        };
+        assert!(in_select.negated);
+        assert!(matches!(in_select.kind, ast::ExprInKind::Select(_)));
+
+        let ast::Expr::In(in_table) = parse("c IN t1") else {
+            panic!("expected IN");
+        };
+        assert!(matches!(in_table.kind, ast::ExprInKind::Table(_)));
+    }
+
+    #[test]
+    fn parenthesised_exprs() {
+        let ast::Expr::BinOp(mult) = parse("(1 + 2) * 3") else {
+            panic!("expected multiplication at the root");
+        };
+        assert!(matches!(*mult.left, ast::Expr::List(ref list) if list.exprs.len() == 1));
+        assert!(matches!(parse("(a, b)"), ast::Expr::List(ref list) if list.exprs.len() == 2));
+        assert!(matches!(parse("(SELECT 1)"), ast::Expr::Subquery(_)));
+        assert!(matches!(
+            parse("NOT EXISTS (SELECT * FROM t1)"),
+            ast::Expr::Exists(ast::ExprExists { negated: true, .. })
+        ));
+    }
+
+    #[test]
+    fn missing_operand() {
+        let mut parser = Parser::new("1 +");
+        let error = parser.parse_expr().unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ExpectedExpression { found: TokenKind::EndOfFile });
+    }
+
+    #[test]
+    fn function_call() {
+        let ast::Expr::Function(func) = parse("lower(c1 + 1, 2)") else {
+            panic!("expected function call");
+        };
+        assert_eq!(func.name.id.as_str(), "lower");
+        assert!(matches!(func.args, ast::FunctionArgs::List(ref args) if args.len() == 2));
+        assert_eq!(func.span, Span::new(Location::new(0), Location::new(16)));
+
+        let ast::Expr::Function(func) = parse("random()") else {
+            panic!("expected function call");
+        };
+        assert_eq!(func.args, ast::FunctionArgs::List(Vec::new()));
+        assert!(func.over.is_none());
+    }
+
+    #[test]
+    fn window_function() {
+        let source = "sum(c1) FILTER (WHERE c1 > 1) OVER (PARTITION BY c2, c3 ORDER BY c4 DESC NULLS LAST
+            ROWS BETWEEN 2 PRECEDING AND CURRENT ROW EXCLUDE TIES)";
+        let ast::Expr::Function(func) = parse(source) else {
+            panic!("expected function call");
+        };
+        assert!(matches!(func.filter.as_deref(), Some(ast::Expr::Compare(_))));
+        let Some(ast::OverClause::Window(spec)) = func.over else {
+            panic!("expected inline window");
+        };
+        assert!(spec.base.is_none());
+        assert_eq!(spec.partition_by.len(), 2);
+        assert_eq!(spec.order_by[0].order, Some(ast::Order::Desc));
+        assert_eq!(spec.order_by[0].nulls, Some(ast::NullsOrder::Last));
+        let frame = spec.frame.unwrap();
+        assert_eq!(frame.units, ast::FrameUnits::Rows);
+        assert!(matches!(frame.start, ast::FrameBound::Preceding(_)));
+        assert_eq!(frame.end, Some(ast::FrameBound::CurrentRow));
+        assert_eq!(frame.exclude, Some(ast::FrameExclude::Ties));
+
+        let ast::Expr::Function(func) = parse("row_number() OVER w") else {
+            panic!("expected function call");
+        };
+        assert!(matches!(func.over, Some(ast::OverClause::Named(_))));
+
+        let ast::Expr::Function(func) = parse("count(c1) OVER (w RANGE UNBOUNDED PRECEDING)") else {
+            panic!("expected function call");
+        };
+        let Some(ast::OverClause::Window(spec)) = func.over else {
+            panic!("expected inline window");
+        };
+        assert_eq!(spec.base.unwrap().id.as_str(), "w");
+        let frame = spec.frame.unwrap();
+        assert_eq!(frame.start, ast::FrameBound::UnboundedPreceding);
+        assert!(frame.end.is_none());
+    }
+
+    #[test]
+    fn aggregate_args() {
+        let ast::Expr::Function(func) = parse("count(*)") else {
+            panic!("expected function call");
+        };
+        assert_eq!(func.args, ast::FunctionArgs::Star);
+        assert!(!func.distinct);
+
+        let ast::Expr::Function(func) = parse("count(DISTINCT c1)") else {
+            panic!("expected function call");
+        };
+        assert!(func.distinct);
+        assert!(matches!(func.args, ast::FunctionArgs::List(ref args) if args.len() == 1));
+    }
+
+    #[test]
+    fn case_expr() {
+        let ast::Expr::Case(case) = parse("CASE WHEN c1 > 1 THEN 'big' WHEN c1 < -1 THEN 'small' ELSE NULL END") else {
+            panic!("expected case expression");
+        };
+        assert!(case.operand.is_none());
+        assert_eq!(case.branches.len(), 2);
+        assert!(matches!(case.branches[0].condition, ast::Expr::Compare(_)));
+        assert_eq!(case.branches[0].span, Span::new(Location::new(5), Location::new(27)));
+        assert!(case.else_expr.is_some());
+
+        let ast::Expr::Case(case) = parse("CASE c1 WHEN 1 THEN 2 END") else {
+            panic!("expected case expression");
+        };
+        assert!(matches!(case.operand.as_deref(), Some(ast::Expr::Column(_))));
+        assert!(case.else_expr.is_none());
+
+        let mut parser = Parser::new("CASE c1 ELSE 2 END");
+        let error = parser.parse_expr().unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::ExpectedToken { found: TokenKind::Else, expected: TokenKind::When }
+        );
+    }
+
+    #[test]
+    fn cast_expr() {
+        let ast::Expr::Cast(cast) = parse("CAST(c1 + 1 AS NUMERIC(10, 2))") else {
+            panic!("expected cast expression");
+        };
+        assert!(matches!(*cast.expr, ast::Expr::BinOp(_)));
+        assert_eq!(cast.type_name.external_type, ast::ExternalType::Numeric);
+        let number_field = cast.type_name.number_field.unwrap();
+        assert!(number_field.second.is_some());
+        assert_eq!(cast.span, Span::new(Location::new(0), Location::new(30)));
+    }
+
+    #[test]
+    fn quoted_column_ref() {
+        let ast::Expr::Column(column) = parse("\"my\"\"t\".`Order`") else {
+            panic!("expected column reference");
+        };
+        let table = column.table.unwrap();
+        assert_eq!(table.id.as_str(), "my\"t");
+        assert_eq!(table.span, Span::new(Location::new(0), Location::new(7)));
+        assert_eq!(column.column.id.as_str(), "Order");
+        assert_eq!(column.column.span, Span::new(Location::new(8), Location::new(15)));
+    }
+
+    #[test]
+    fn blob_literal() {
+        let ast::Expr::LiteralValue(ast::LiteralValue::Blob(blob)) = parse("x'CAFE'") else {
+            panic!("expected blob literal");
+        };
+        assert_eq!(blob.value, vec![0xca, 0xfe]);
+        assert_eq!(blob.span, Span::new(Location::new(0), Location::new(7)));
+    }
+}
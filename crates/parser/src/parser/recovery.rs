@@ -0,0 +1,202 @@
+//! Error recovery.
+//!
+//! A clause that fails to parse records its error and skips ahead to a token
+//! of its recovery set, where the enclosing clause picks up again. The parts
+//! of the tree that did parse are kept and the broken part is dropped, or
+//! replaced by an [`ast::ExprInvalid`] or [`ast::Identifier::invalid`]
+//! placeholder where the tree needs a node. A `;` and the end of input end
+//! every recovery set.
+
+use crate::{errors::ParseError, tokens::TokenKind};
+
+use super::Parser;
+
+/// Tokens that begin a statement.
+pub(super) const STMT: &[TokenKind] = &[
+    TokenKind::Alter,
+    TokenKind::Begin,
+    TokenKind::Commit,
+    TokenKind::Create,
+    TokenKind::Delete,
+    TokenKind::Drop,
+    TokenKind::Insert,
+    TokenKind::Reindex,
+    TokenKind::Release,
+    TokenKind::Rollback,
+    TokenKind::Savepoint,
+    TokenKind::Select,
+    TokenKind::Update,
+    TokenKind::With,
+];
+
+/// Tokens that begin the next clause of a SELECT, UPDATE or DELETE, or end a
+/// subquery.
+pub(super) const CLAUSE: &[TokenKind] = &[
+    TokenKind::From,
+    TokenKind::Where,
+    TokenKind::Group,
+    TokenKind::Having,
+    TokenKind::Window,
+    TokenKind::Order,
+    TokenKind::Limit,
+    TokenKind::Offset,
+    TokenKind::Union,
+    TokenKind::Intersect,
+    TokenKind::Except,
+    TokenKind::Returning,
+    TokenKind::RParen,
+];
+
+/// [`CLAUSE`] and the comma before the next item of a list in a clause.
+pub(super) const CLAUSE_ITEM: &[TokenKind] = &[
+    TokenKind::Comma,
+    TokenKind::From,
+    TokenKind::Where,
+    TokenKind::Group,
+    TokenKind::Having,
+    TokenKind::Window,
+    TokenKind::Order,
+    TokenKind::Limit,
+    TokenKind::Offset,
+    TokenKind::Union,
+    TokenKind::Intersect,
+    TokenKind::Except,
+    TokenKind::Returning,
+    TokenKind::RParen,
+];
+
+/// Tokens that may follow a table name in a FROM clause or the target of an
+/// INSERT, UPDATE or DELETE.
+pub(super) const TABLE_NAME: &[TokenKind] = &[
+    TokenKind::As,
+    TokenKind::Indexed,
+    TokenKind::Not,
+    TokenKind::Comma,
+    TokenKind::Cross,
+    TokenKind::Natural,
+    TokenKind::Left,
+    TokenKind::Right,
+    TokenKind::Full,
+    TokenKind::Inner,
+    TokenKind::Join,
+    TokenKind::On,
+    TokenKind::Using,
+    TokenKind::Set,
+    TokenKind::LParen,
+    TokenKind::Values,
+    TokenKind::Default,
+    TokenKind::Select,
+    TokenKind::With,
+    TokenKind::Where,
+    TokenKind::Group,
+    TokenKind::Having,
+    TokenKind::Window,
+    TokenKind::Order,
+    TokenKind::Limit,
+    TokenKind::Union,
+    TokenKind::Intersect,
+    TokenKind::Except,
+    TokenKind::Returning,
+    TokenKind::RParen,
+];
+
+/// Tokens that begin the next column constraint or end the column definition.
+pub(super) const COLUMN_CONSTRAINT: &[TokenKind] = &[
+    TokenKind::Constraint,
+    TokenKind::Primary,
+    TokenKind::Not,
+    TokenKind::Unique,
+    TokenKind::Check,
+    TokenKind::Default,
+    TokenKind::Collate,
+    TokenKind::References,
+    TokenKind::Comma,
+    TokenKind::RParen,
+];
+
+/// Tokens that follow a column name in a column definition.
+pub(super) const COLUMN_NAME: &[TokenKind] = &[
+    TokenKind::Char,
+    TokenKind::Integer,
+    TokenKind::Numeric,
+    TokenKind::Serial,
+    TokenKind::Varchar,
+    TokenKind::Comma,
+    TokenKind::RParen,
+];
+
+/// Tokens that end a column definition or table constraint of a CREATE TABLE.
+pub(super) const TABLE_ELEMENT: &[TokenKind] = &[TokenKind::Comma, TokenKind::RParen];
+
+/// Tokens that end an expression in a parenthesised list.
+pub(super) const LIST_ITEM: &[TokenKind] = &[TokenKind::Comma, TokenKind::RParen];
+
+impl<'src> Parser<'src> {
+    /// Record `error` and skip to the next token of `recovery`.
+    pub(super) fn recover(&mut self, error: ParseError, recovery: &[TokenKind]) {
+        self.add_error(error.kind, error.span);
+        self.skip_to(recovery);
+    }
+
+    /// Skip tokens up to the next one of `recovery`, a `;` or the end of
+    /// input. Parentheses opened while skipping are skipped with their
+    /// contents, so the `)` of a nested call does not end a clause early.
+    pub(super) fn skip_to(&mut self, recovery: &[TokenKind]) {
+        let mut depth = 0usize;
+        loop {
+            match self.current_token_kind() {
+                TokenKind::Semicolon | TokenKind::EndOfFile => break,
+                TokenKind::RParen if depth > 0 => depth -= 1,
+                kind if depth == 0 && recovery.contains(&kind) => break,
+                TokenKind::LParen => depth += 1,
+                _ => {}
+            }
+            self.bump_any();
+        }
+    }
+
+    /// Parse an expression, or record the error and return an
+    /// [`ast::ExprInvalid`] spanning the tokens up to the next one of
+    /// `recovery`.
+    pub(super) fn parse_expr_or_invalid(&mut self, recovery: &[TokenKind]) -> ast::Expr {
+        let start = self.node_start();
+        match self.parse_expr() {
+            Ok(expr) => expr,
+            Err(error) => {
+                self.recover(error, recovery);
+                ast::Expr::Invalid(ast::ExprInvalid {
+                    span: self.node_span(start),
+                })
+            }
+        }
+    }
+
+    /// Parse a comma separated list of expressions, replacing the ones that
+    /// fail to parse with placeholders.
+    pub(super) fn parse_expr_list_or_invalid(&mut self, recovery: &[TokenKind]) -> Vec<ast::Expr> {
+        let mut exprs = vec![self.parse_expr_or_invalid(recovery)];
+        while self.eat(TokenKind::Comma) {
+            exprs.push(self.parse_expr_or_invalid(recovery));
+        }
+        exprs
+    }
+
+    /// Parse an identifier, or record the error and return an
+    /// [`ast::Identifier::invalid`] placeholder. A keyword used as a name is
+    /// taken as the placeholder unless it is one of `recovery`, which the
+    /// caller expects after the name.
+    pub(super) fn parse_identifier_or_invalid(&mut self, recovery: &[TokenKind]) -> ast::Identifier {
+        let start = self.node_start();
+        match self.parse_identifier() {
+            Ok(id) => id,
+            Err(error) => {
+                self.add_error(error.kind, error.span);
+                let kind = self.current_token_kind();
+                if kind.is_keyword() && !recovery.contains(&kind) {
+                    self.bump_any();
+                }
+                ast::Identifier::invalid(self.node_span(start))
+            }
+        }
+    }
+}
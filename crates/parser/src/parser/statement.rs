@@ -1,8 +1,8 @@
-use ast::{name::Name, Identifier, Stmt};
+use ast::{Identifier, Stmt};
 
 use crate::{errors::ParseErrorKind, tokens::TokenKind};
 
-use super::Parser;
+use super::{recovery, Parser};
 
 impl<'src> Parser<'src> {
     pub fn parse_statement(&mut self) -> Stmt {
@@ -10,16 +10,18 @@ impl<'src> Parser<'src> {
             TokenKind::Alter => self.parse_alter_table_statement(),
             TokenKind::Begin => self.parse_begin_statement(),
             TokenKind::Commit => self.parse_commit_statement(),
+            TokenKind::Create => self.parse_create_statement(),
+            TokenKind::Delete => self.parse_delete_statement(),
             TokenKind::Drop => self.parse_drop_statement(),
+            TokenKind::Insert => self.parse_insert_statement(),
             TokenKind::Reindex => self.parse_reindex_statement(),
             TokenKind::Release => self.parse_release_statement(),
             TokenKind::Rollback => self.parse_rollback_statement(),
             TokenKind::Savepoint => self.parse_savepoint_statement(),
-            _ => {
-                println!("Tokenkind {}", self.current_token_kind());
-                println!("Current Span {}", self.current_token_span());
-                self.parse_invalid_statement()
-            }
+            TokenKind::Select => self.parse_select_statement(),
+            TokenKind::Update => self.parse_update_statement(),
+            TokenKind::With => self.parse_with_statement(),
+            _ => self.parse_invalid_statement(),
         };
         let stmt = match stmt_res {
             Ok(stmt) => stmt,
@@ -29,8 +31,18 @@ impl<'src> Parser<'src> {
             }
         };
         if !self.eat(TokenKind::Semicolon) {
-            self.add_error(ParseErrorKind::MissingSemicolon, self.current_token_span());
+            // An error at this token already explains why the statement
+            // stopped here.
+            let span = self.current_token_span();
+            if self.errors.last().is_none_or(|last| last.span.end() < span.start()) {
+                self.add_error(ParseErrorKind::MissingSemicolon, span);
+            }
+            // Resume at the end of the statement or the next one.
+            self.skip_to(recovery::STMT);
+            self.eat(TokenKind::Semicolon);
         };
+        let parameters = std::mem::take(&mut self.parameters);
+        self.stmt_parameters.push(parameters);
         stmt
 
     }
@@ -41,11 +53,9 @@ impl<'src> Parser<'src> {
         if !self.expect(TokenKind::Table) {
             return Err(ast::StmtInvalid {span: self.node_span(start)});
         };
-        let id = self.parse_identifier()
-            .map_err(|error| {
-            self.add_error(error.kind, error.span);
-            ast::StmtInvalid {span: self.node_span(start)}
-        })?;
+        let id = self.parse_identifier_or_invalid(
+            &[TokenKind::Rename, TokenKind::Add, TokenKind::Drop]
+        );
         let action = self.parse_alter_table_action()
             .map_err(|error| {
                 self.add_error(error.kind, error.span);
@@ -78,11 +88,23 @@ impl<'src> Parser<'src> {
     }
 
     pub fn parse_create_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
-        unimplemented!()
+        let start = self.node_start();
+        let create = self.parse_create()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })?;
+        Ok(Stmt::Create(create))
     }
 
     pub fn parse_delete_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
-        unimplemented!()
+        let start = self.node_start();
+        let delete = self.parse_delete()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })?;
+        Ok(Stmt::Delete(delete))
     }
 
     pub fn parse_drop_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
@@ -105,11 +127,7 @@ impl<'src> Parser<'src> {
             };
             exist_check = true;
         }
-        let id = self.parse_identifier()
-            .map_err(|error| {
-            self.add_error(error.kind, error.span);
-            ast::StmtInvalid {span: self.node_span(start)}
-        })?;
+        let id = self.parse_identifier_or_invalid(&[]);
 
         Ok(Stmt::Drop(ast::StmtDrop {
             span: self.node_span(start),
@@ -120,22 +138,27 @@ impl<'src> Parser<'src> {
     }
 
     pub fn parse_insert_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
-        unimplemented!()
+        let start = self.node_start();
+        let insert = self.parse_insert()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })?;
+        Ok(Stmt::Insert(insert))
     }
 
     pub fn parse_reindex_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
         let start = self.node_start();
         self.bump(TokenKind::Reindex);
-        let id;
-        if self.current_token_kind() == TokenKind::Name {
-            id = Some(self.parse_identifier()
+        let id = if self.current_token_kind().is_name() {
+            Some(self.parse_identifier()
                 .map_err(|error| {
                     self.add_error(error.kind, error.span);
                     ast::StmtInvalid {span: self.node_span(start)}
-                })?);
+                })?)
         } else {
-            id = None
-        }
+            None
+        };
         Ok(Stmt::Reindex(ast::StmtReindex {
             span: self.node_span(start),
             id,
@@ -162,17 +185,16 @@ impl<'src> Parser<'src> {
         let start = self.node_start();
         self.bump(TokenKind::Rollback);
         self.eat(TokenKind::Transaction);
-        let id: Option<Identifier>;
-        if self.eat(TokenKind::To) {
+        let id: Option<Identifier> = if self.eat(TokenKind::To) {
             self.eat(TokenKind::Savepoint);
-            id = Some(self.parse_identifier()
+            Some(self.parse_identifier()
                 .map_err(|error| {
                 self.add_error(error.kind, error.span);
                 ast::StmtInvalid {span: self.node_span(start)}
-            })?);
+            })?)
         } else {
-            id = None;
-        }
+            None
+        };
         Ok(Stmt::Rollback(ast::StmtRollback {
             span: self.node_span(start),
             id,
@@ -194,11 +216,32 @@ impl<'src> Parser<'src> {
     }
 
     pub fn parse_select_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
-        unimplemented!()
+        let start = self.node_start();
+        let select = self.parse_select()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })?;
+        Ok(Stmt::Select(Box::new(select)))
     }
 
     pub fn parse_update_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
-        unimplemented!()
+        let start = self.node_start();
+        let update = self.parse_update()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })?;
+        Ok(Stmt::Update(update))
+    }
+
+    pub fn parse_with_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
+        let start = self.node_start();
+        self.parse_with_stmt()
+            .map_err(|error| {
+            self.add_error(error.kind, error.span);
+            ast::StmtInvalid {span: self.node_span(start)}
+        })
     }
 
     pub fn parse_vacuum_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
@@ -207,12 +250,10 @@ impl<'src> Parser<'src> {
 
     pub fn parse_invalid_statement(&mut self) -> Result<Stmt, ast::StmtInvalid> {
         let start = self.node_start();
+        let found = self.current_token_kind();
         // Bump whatever invalid token started this invalid statement
         self.bump_any();
-        self.add_error(
-            ParseErrorKind::UnexpectedToken { found: self.current_token_kind() },
-            self.node_span(start)
-        );
+        self.add_error(ParseErrorKind::UnexpectedToken { found }, self.node_span(start));
         self.eat_until(TokenKind::Semicolon);
         Err(ast::StmtInvalid {
             span: self.node_span(start),
@@ -287,7 +328,7 @@ mod test {
         let expected_span = Span::new(Location::new(0), Location::new(12));
         let expected_id_span = Span::new(Location::new(10), Location::new(12));
         let expected_id = ast::Identifier::new(Name::new("s1".to_string()), expected_id_span);
-        assert!(parser.errors.len() == 0);
+        assert!(parser.errors.is_empty());
         assert_eq!(
             stmt,
             Stmt::Savepoint(ast::StmtSavepoint {
@@ -468,6 +509,7 @@ mod test {
                                 constraints: vec!(
                                     ast::ColumnConstraint {
                                         span: constraint_span,
+                                        name: None,
                                         kind: ColumnConstraintKind::Unique(Some(
                                                 ast::ConflictAction::Abort
                                         ))
@@ -495,4 +537,990 @@ mod test {
         );
         assert_eq!(parser.errors.len(), 1);
     }
+
+    #[test]
+    fn select_stmt() {
+        let source = "SELECT t1.c1, c2 AS x FROM t1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let table_span = Span::new(Location::new(7), Location::new(9));
+        let table = ast::Identifier::new(Name::new("t1".to_string()), table_span);
+        let col1_span = Span::new(Location::new(10), Location::new(12));
+        let col1 = ast::Identifier::new(Name::new("c1".to_string()), col1_span);
+        let col2_span = Span::new(Location::new(14), Location::new(16));
+        let col2 = ast::Identifier::new(Name::new("c2".to_string()), col2_span);
+        let alias_span = Span::new(Location::new(20), Location::new(21));
+        let alias = ast::Identifier::new(Name::new("x".to_string()), alias_span);
+        let from_id_span = Span::new(Location::new(27), Location::new(29));
+        let from_id = ast::Identifier::new(Name::new("t1".to_string()), from_id_span);
+        assert_eq!(
+            stmt,
+            Stmt::Select(Box::new(ast::StmtSelect {
+                span: Span::new(Location::new(0), Location::new(29)),
+                with: None,
+                distinct: false,
+                columns: vec![
+                    ast::ResultColumn {
+                        span: Span::new(Location::new(7), Location::new(12)),
+                        kind: ast::ResultColumnKind::Expr(
+                            ast::Expr::Column(ast::ExprColumn {
+                                span: Span::new(Location::new(7), Location::new(12)),
+                                table: Some(table),
+                                column: col1,
+                            }),
+                            None
+                        )
+                    },
+                    ast::ResultColumn {
+                        span: Span::new(Location::new(14), Location::new(21)),
+                        kind: ast::ResultColumnKind::Expr(
+                            ast::Expr::Column(ast::ExprColumn {
+                                span: col2_span,
+                                table: None,
+                                column: col2,
+                            }),
+                            Some(alias)
+                        )
+                    },
+                ],
+                from: Some(ast::FromClause {
+                    span: Span::new(Location::new(22), Location::new(29)),
+                    item: ast::FromItem {
+                        span: from_id_span,
+                        kind: ast::FromItemKind::Table(ast::QuantifiedTableName {
+                            span: from_id_span,
+                            id: from_id,
+                            alias: None,
+                            indexed: None,
+                        })
+                    }
+                }),
+                where_clause: None,
+                group_by: vec![],
+                having: None,
+                window: vec![],
+                compound: None,
+                order_by: vec![],
+                limit: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn select_stmt_clauses() {
+        let source = "SELECT DISTINCT * FROM t1, t2 AS b WHERE TRUE GROUP BY c1, c2 HAVING c1
+            ORDER BY c1 COLLATE nocase DESC, c2 LIMIT 10 OFFSET 5;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert!(select.distinct);
+        assert!(matches!(select.columns[0].kind, ast::ResultColumnKind::All));
+        assert!(matches!(
+            select.from.unwrap().item.kind,
+            ast::FromItemKind::Join(ast::JoinClause {
+                op: ast::JoinOperator { kind: ast::JoinKind::Comma, .. },
+                ..
+            })
+        ));
+        assert!(matches!(
+            select.where_clause,
+            Some(ast::Expr::LiteralValue(ast::LiteralValue::BoolLiteral(ast::BoolLiteral { value: true, .. })))
+        ));
+        assert_eq!(select.group_by.len(), 2);
+        assert!(select.having.is_some());
+        assert_eq!(select.order_by.len(), 2);
+        assert_eq!(select.order_by[0].order, Some(ast::Order::Desc));
+        assert_eq!(select.order_by[0].collation.as_ref().unwrap().id.as_str(), "nocase");
+        assert_eq!(select.order_by[1].order, None);
+        let limit = select.limit.unwrap();
+        assert!(matches!(
+            limit.limit,
+            ast::Expr::LiteralValue(ast::LiteralValue::IntLiteral(ast::IntLiteral { value: 10, .. }))
+        ));
+        assert!(limit.offset.is_some());
+    }
+
+    #[test]
+    fn select_stmt_compound() {
+        let source = "SELECT c1 FROM t1 UNION ALL SELECT c1 FROM t2 EXCEPT SELECT t3.* FROM t3 ORDER BY c1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert_eq!(select.order_by.len(), 1);
+        let compound = select.compound.unwrap();
+        assert_eq!(compound.op, ast::CompoundOperator::UnionAll);
+        assert!(compound.select.order_by.is_empty());
+        let compound = compound.select.compound.unwrap();
+        assert_eq!(compound.op, ast::CompoundOperator::Except);
+        let ast::ResultColumnKind::TableAll(ref table) = compound.select.columns[0].kind else {
+            panic!("expected table.* result column");
+        };
+        assert_eq!(table.id.as_str(), "t3");
+        assert_eq!(table.span, Span::new(Location::new(60), Location::new(62)));
+    }
+
+    #[test]
+    fn select_error() {
+        let source = "SELECT FROM t1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 1);
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::ExpectedExpression { found: TokenKind::From }
+        )));
+        // The missing column is a placeholder and the FROM clause is kept
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert!(matches!(
+            select.columns[0].kind,
+            ast::ResultColumnKind::Expr(ast::Expr::Invalid(_), None)
+        ));
+        assert!(select.from.is_some());
+    }
+
+    #[test]
+    fn select_stmt_joins() {
+        let source = "SELECT * FROM t1 AS a NATURAL LEFT OUTER JOIN t2 INDEXED BY i2
+            CROSS JOIN t3 JOIN t4 USING (c1, c2) INNER JOIN t5 ON a.c1 = t5.c1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        // Joins are left-deep, so the last join is at the root.
+        let ast::FromItemKind::Join(inner) = select.from.unwrap().item.kind else {
+            panic!("expected join");
+        };
+        assert_eq!(inner.op.kind, ast::JoinKind::Inner);
+        assert!(matches!(inner.constraint, Some(ast::JoinConstraint::On(ast::Expr::Compare(_)))));
+        let ast::FromItemKind::Join(using) = inner.left.kind else {
+            panic!("expected join");
+        };
+        let Some(ast::JoinConstraint::Using(columns)) = using.constraint else {
+            panic!("expected USING constraint");
+        };
+        assert_eq!(columns.len(), 2);
+        let ast::FromItemKind::Join(cross) = using.left.kind else {
+            panic!("expected join");
+        };
+        assert_eq!(cross.op.kind, ast::JoinKind::Cross);
+        assert!(cross.constraint.is_none());
+        let ast::FromItemKind::Join(natural) = cross.left.kind else {
+            panic!("expected join");
+        };
+        assert!(natural.op.natural);
+        assert_eq!(natural.op.kind, ast::JoinKind::Left);
+        assert_eq!(natural.op.span, Span::new(Location::new(22), Location::new(45)));
+        let ast::FromItemKind::Table(right) = natural.right.kind else {
+            panic!("expected table");
+        };
+        assert!(matches!(right.indexed, Some(ast::IndexedHint::IndexedBy(_))));
+        let ast::FromItemKind::Table(left) = natural.left.kind else {
+            panic!("expected table");
+        };
+        assert_eq!(left.alias.unwrap().id.as_str(), "a");
+    }
+
+    #[test]
+    fn select_stmt_nested_from() {
+        let source = "SELECT * FROM (SELECT c1 FROM t1) AS s, (t2 RIGHT JOIN t3 ON TRUE);";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        let ast::FromItemKind::Join(comma) = select.from.unwrap().item.kind else {
+            panic!("expected join");
+        };
+        assert_eq!(comma.op.kind, ast::JoinKind::Comma);
+        let ast::FromItemKind::Subquery(subquery) = comma.left.kind else {
+            panic!("expected subquery");
+        };
+        assert_eq!(subquery.span, Span::new(Location::new(14), Location::new(38)));
+        assert_eq!(subquery.alias.unwrap().id.as_str(), "s");
+        assert!(subquery.select.from.is_some());
+        let ast::FromItemKind::Join(right) = comma.right.kind else {
+            panic!("expected parenthesised join");
+        };
+        assert_eq!(right.op.kind, ast::JoinKind::Right);
+        assert!(matches!(right.constraint, Some(ast::JoinConstraint::On(_))));
+    }
+
+    #[test]
+    fn select_stmt_alias_without_as() {
+        let source = "SELECT * FROM t1 a JOIN (SELECT c1 FROM t2) s ON a.c1 = s.c1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        let ast::FromItemKind::Join(join) = select.from.unwrap().item.kind else {
+            panic!("expected join");
+        };
+        let ast::FromItemKind::Table(left) = join.left.kind else {
+            panic!("expected table");
+        };
+        let alias = left.alias.unwrap();
+        assert_eq!(alias.id.as_str(), "a");
+        assert_eq!(alias.span, Span::new(Location::new(17), Location::new(18)));
+        let ast::FromItemKind::Subquery(right) = join.right.kind else {
+            panic!("expected subquery");
+        };
+        assert_eq!(right.alias.unwrap().id.as_str(), "s");
+
+        let source = "SELECT id x, name AS n FROM users;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        let aliases: Vec<&str> = select
+            .columns
+            .iter()
+            .map(|column| match &column.kind {
+                ast::ResultColumnKind::Expr(_, Some(alias)) => alias.id.as_str(),
+                kind => panic!("expected an aliased column, got {kind:?}"),
+            })
+            .collect();
+        assert_eq!(aliases, ["x", "n"]);
+    }
+
+    #[test]
+    fn non_reserved_keywords_as_names() {
+        let source = "ALTER TABLE t ADD COLUMN first INTEGER;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Alter(alter) = stmt else {
+            panic!("expected alter statement, got {:?}", stmt);
+        };
+        let ast::AlterTableActionKind::Add(add) = alter.action.kind else {
+            panic!("expected add column");
+        };
+        assert_eq!(add.column.id.id.as_str(), "first");
+        assert_eq!(add.column.id.span, Span::new(Location::new(25), Location::new(30)));
+
+        let source = "CREATE TABLE t (last VARCHAR(10));";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Create(create) = stmt else {
+            panic!("expected create statement, got {:?}", stmt);
+        };
+        let ast::CreateKind::Table(ast::CreateTable {
+            source: ast::CreateTableSource::Columns(table), ..
+        }) = create.kind else {
+            panic!("expected table columns");
+        };
+        assert_eq!(table.columns[0].id.id.as_str(), "last");
+
+        let source = "SELECT row, range FROM t;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        let columns: Vec<&str> = select
+            .columns
+            .iter()
+            .map(|column| match &column.kind {
+                ast::ResultColumnKind::Expr(ast::Expr::Column(column), None) => {
+                    column.column.id.as_str()
+                }
+                kind => panic!("expected a column reference, got {kind:?}"),
+            })
+            .collect();
+        assert_eq!(columns, ["row", "range"]);
+
+        // The same words are still keywords where a window expects them.
+        let source = "SELECT sum(x) OVER (PARTITION BY first ORDER BY last NULLS FIRST \
+                      ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM t;";
+        let mut parser = Parser::new(source);
+        parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn select_join_error() {
+        let source = "SELECT * FROM t1 CROSS t2;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert!(matches!(stmt, Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::ExpectedToken { expected: TokenKind::Join, .. }
+        )));
+    }
+
+    #[test]
+    fn select_stmt_window() {
+        let source = "SELECT c1, rank() OVER w FROM t1
+            WINDOW w AS (PARTITION BY c2 ORDER BY c1), w2 AS (w GROUPS 1 FOLLOWING)
+            ORDER BY c1 NULLS FIRST;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert!(matches!(
+            select.columns[1].kind,
+            ast::ResultColumnKind::Expr(ast::Expr::Function(_), None)
+        ));
+        assert_eq!(select.window.len(), 2);
+        assert_eq!(select.window[0].id.id.as_str(), "w");
+        assert_eq!(select.window[0].spec.partition_by.len(), 1);
+        assert_eq!(select.window[1].spec.base.as_ref().unwrap().id.as_str(), "w");
+        assert!(matches!(
+            select.window[1].spec.frame,
+            Some(ast::WindowFrame { units: ast::FrameUnits::Groups, start: ast::FrameBound::Following(_), .. })
+        ));
+        assert_eq!(select.order_by[0].nulls, Some(ast::NullsOrder::First));
+    }
+
+    #[test]
+    fn with_select_recursive() {
+        let source = "WITH RECURSIVE tree(id, parent) AS (
+                SELECT id, parent FROM nodes WHERE parent IS NULL
+                UNION ALL
+                SELECT n.id, n.parent FROM nodes AS n JOIN tree ON n.parent = tree.id
+            ), leaves AS NOT MATERIALIZED (SELECT id FROM tree)
+            SELECT * FROM leaves;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert_eq!(select.span.start(), Location::new(0));
+        let with = select.with.unwrap();
+        assert!(with.recursive);
+        assert_eq!(with.ctes.len(), 2);
+        let tree = &with.ctes[0];
+        assert_eq!(tree.id.id.as_str(), "tree");
+        assert_eq!(tree.columns.len(), 2);
+        assert_eq!(tree.materialized, None);
+        assert!(matches!(
+            tree.select.compound,
+            Some(ast::CompoundSelect { op: ast::CompoundOperator::UnionAll, .. })
+        ));
+        let leaves = &with.ctes[1];
+        assert!(leaves.columns.is_empty());
+        assert_eq!(leaves.materialized, Some(ast::MaterializeHint::NotMaterialized));
+    }
+
+    #[test]
+    fn with_dml_stmts() {
+        let source = "WITH old AS MATERIALIZED (SELECT id FROM t2) DELETE FROM t1 WHERE id IN old;
+            WITH v AS (SELECT 1) INSERT INTO t1 SELECT * FROM v;
+            WITH v AS (SELECT 1) UPDATE t1 SET c1 = 2 WHERE id IN (WITH w AS (SELECT 3) SELECT * FROM w);";
+        let mut parser = Parser::new(source);
+        let Stmt::Delete(delete) = parser.parse_statement() else {
+            panic!("expected delete statement");
+        };
+        let with = delete.with.unwrap();
+        assert!(!with.recursive);
+        assert_eq!(with.ctes[0].materialized, Some(ast::MaterializeHint::Materialized));
+        assert_eq!(delete.span.start(), Location::new(0));
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        assert!(insert.with.is_some());
+        let Stmt::Update(update) = parser.parse_statement() else {
+            panic!("expected update statement");
+        };
+        assert!(update.with.is_some());
+        let Some(ast::Expr::In(ast::ExprIn { kind: ast::ExprInKind::Select(select), .. })) =
+            update.where_clause
+        else {
+            panic!("expected IN subquery");
+        };
+        assert!(select.with.is_some());
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn with_error() {
+        let source = "WITH v AS (SELECT 1) DROP TABLE v;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert!(matches!(stmt, Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::InvalidWithTarget
+        )));
+    }
+
+    #[test]
+    fn alter_stmt_add_check_default() {
+        let source = "ALTER TABLE t1 ADD c1 INTEGER DEFAULT -1 CHECK (c1 >= -1 AND c1 < 10) NOT NULL;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let Stmt::Alter(alter) = stmt else {
+            panic!("expected alter statement, got {:?}", stmt);
+        };
+        let ast::AlterTableActionKind::Add(add) = alter.action.kind else {
+            panic!("expected ADD action");
+        };
+        let constraints = add.column.constraint_list.constraints;
+        assert_eq!(constraints.len(), 3);
+        assert!(matches!(
+            constraints[0].kind,
+            ColumnConstraintKind::Default(ast::ColumnConstraintDefault::SignedNumber(
+                ast::SignedNumber::Integer(ast::IntLiteral { value: -1, .. })
+            ))
+        ));
+        assert!(matches!(
+            constraints[1].kind,
+            ColumnConstraintKind::Check(ast::Expr::BoolOp(_))
+        ));
+        assert!(matches!(constraints[2].kind, ColumnConstraintKind::NotNull(None)));
+    }
+
+    #[test]
+    fn insert_stmt_values() {
+        let source = "INSERT INTO t1 (c1, c2) VALUES (1, 'a');";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let table_span = Span::new(Location::new(12), Location::new(14));
+        let table_id = ast::Identifier::new(Name::new("t1".to_string()), table_span);
+        let c1 = ast::Identifier::new(
+            Name::new("c1".to_string()),
+            Span::new(Location::new(16), Location::new(18)),
+        );
+        let c2 = ast::Identifier::new(
+            Name::new("c2".to_string()),
+            Span::new(Location::new(20), Location::new(22)),
+        );
+        let values_span = Span::new(Location::new(24), Location::new(39));
+        assert_eq!(
+            stmt,
+            Stmt::Insert(ast::StmtInsert {
+                span: Span::new(Location::new(0), Location::new(39)),
+                with: None,
+                table: ast::TableName {
+                    span: table_span,
+                    id: table_id,
+                    alias: None,
+                },
+                columns: vec![c1, c2],
+                action: ast::InsertAction {
+                    span: values_span,
+                    kind: ast::InsertActionKind::Values(ast::InsertValues {
+                        span: values_span,
+                        rows: vec![vec![
+                            ast::Expr::LiteralValue(ast::LiteralValue::IntLiteral(ast::IntLiteral {
+                                span: Span::new(Location::new(32), Location::new(33)),
+                                value: 1,
+                            })),
+                            ast::Expr::LiteralValue(ast::LiteralValue::StringLiteral(
+                                ast::StringLiteral {
+                                    span: Span::new(Location::new(35), Location::new(38)),
+                                    value: "a".to_string(),
+                                }
+                            )),
+                        ]],
+                        upsert: None,
+                    })
+                },
+                returning: None,
+            })
+        );
+    }
+
+    #[test]
+    fn insert_stmt_actions() {
+        let source = "INSERT INTO t1 AS t DEFAULT VALUES RETURNING *, c1 + 1 AS next;
+            INSERT INTO t1 SELECT * FROM t2;
+            INSERT INTO t1 VALUES (1), (2), (3);";
+        let mut parser = Parser::new(source);
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        assert_eq!(insert.table.alias.unwrap().id.as_str(), "t");
+        assert_eq!(insert.action.kind, ast::InsertActionKind::DefaultValues);
+        assert_eq!(insert.returning.unwrap().columns.len(), 2);
+
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        assert!(matches!(insert.action.kind, ast::InsertActionKind::Select(_)));
+
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        let ast::InsertActionKind::Values(values) = insert.action.kind else {
+            panic!("expected VALUES action");
+        };
+        assert_eq!(values.rows.len(), 3);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn insert_stmt_upsert() {
+        let source = "INSERT INTO t1 (id, n) VALUES (1, 1)
+            ON CONFLICT (id COLLATE binary DESC) WHERE id > 1
+            DO UPDATE SET n = n + 1, (a, b) = (1, 2) WHERE n < 10
+            RETURNING id;
+            INSERT INTO t1 VALUES (1) ON CONFLICT DO NOTHING;";
+        let mut parser = Parser::new(source);
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        assert_eq!(parser.errors.len(), 0);
+        assert!(insert.returning.is_some());
+        let ast::InsertActionKind::Values(values) = insert.action.kind else {
+            panic!("expected VALUES action");
+        };
+        let upsert = values.upsert.unwrap();
+        let target = upsert.target.unwrap();
+        assert_eq!(target.columns.len(), 1);
+        assert_eq!(target.columns[0].collation.as_ref().unwrap().id.as_str(), "binary");
+        assert_eq!(target.columns[0].order, Some(ast::Order::Desc));
+        assert!(target.where_clause.is_some());
+        let ast::UpsertAction::Update(update) = upsert.action else {
+            panic!("expected DO UPDATE");
+        };
+        assert_eq!(update.assignments.len(), 2);
+        assert!(matches!(update.assignments[0].target, ast::AssignmentTarget::Column(_)));
+        assert!(matches!(
+            update.assignments[1].target,
+            ast::AssignmentTarget::ColumnList(ref columns) if columns.len() == 2
+        ));
+        assert!(update.where_clause.is_some());
+
+        let Stmt::Insert(insert) = parser.parse_statement() else {
+            panic!("expected insert statement");
+        };
+        let ast::InsertActionKind::Values(values) = insert.action.kind else {
+            panic!("expected VALUES action");
+        };
+        let upsert = values.upsert.unwrap();
+        assert!(upsert.target.is_none());
+        assert_eq!(upsert.action, ast::UpsertAction::Nothing);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn insert_error() {
+        let source = "INSERT INTO t1 DEFAULT;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert!(matches!(stmt, Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::ExpectedToken { expected: TokenKind::Values, found: TokenKind::Semicolon }
+        )));
+    }
+
+    #[test]
+    fn delete_stmt() {
+        let source = "DELETE FROM t1 AS t INDEXED BY i1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let table_span = Span::new(Location::new(12), Location::new(33));
+        assert_eq!(
+            stmt,
+            Stmt::Delete(ast::StmtDelete {
+                span: Span::new(Location::new(0), Location::new(33)),
+                with: None,
+                table: ast::QuantifiedTableName {
+                    span: table_span,
+                    id: ast::Identifier::new(
+                        Name::new("t1".to_string()),
+                        Span::new(Location::new(12), Location::new(14))
+                    ),
+                    alias: Some(ast::Identifier::new(
+                        Name::new("t".to_string()),
+                        Span::new(Location::new(18), Location::new(19))
+                    )),
+                    indexed: Some(ast::IndexedHint::IndexedBy(ast::Identifier::new(
+                        Name::new("i1".to_string()),
+                        Span::new(Location::new(31), Location::new(33))
+                    ))),
+                },
+                where_clause: None,
+                returning: None,
+            })
+        );
+
+        let source = "DELETE FROM t1 NOT INDEXED WHERE c1 = 1 RETURNING *;";
+        let mut parser = Parser::new(source);
+        let Stmt::Delete(delete) = parser.parse_statement() else {
+            panic!("expected delete statement");
+        };
+        assert_eq!(parser.errors.len(), 0);
+        assert_eq!(delete.table.indexed, Some(ast::IndexedHint::NotIndexed));
+        assert!(delete.where_clause.is_some());
+        assert!(delete.returning.is_some());
+    }
+
+    #[test]
+    fn update_stmt() {
+        let source = "UPDATE OR REPLACE t1 NOT INDEXED SET c1 = c1 + 1, (c2, c3) = (SELECT a, b FROM t2)
+            FROM t2 WHERE t1.id = t2.id RETURNING c1;";
+        let mut parser = Parser::new(source);
+        let Stmt::Update(update) = parser.parse_statement() else {
+            panic!("expected update statement");
+        };
+        assert_eq!(parser.errors.len(), 0);
+        assert_eq!(update.conflict_action, Some(ast::ConflictAction::Replace));
+        assert_eq!(update.table.id.id.as_str(), "t1");
+        assert_eq!(update.table.indexed, Some(ast::IndexedHint::NotIndexed));
+        assert_eq!(update.assignments.len(), 2);
+        assert!(matches!(update.assignments[1].expr, ast::Expr::Subquery(_)));
+        assert!(update.from.is_some());
+        assert!(update.where_clause.is_some());
+        assert!(update.returning.is_some());
+    }
+
+    #[test]
+    fn update_error() {
+        let source = "UPDATE t1 WHERE c1 = 1;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert!(matches!(stmt, Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::ExpectedToken { expected: TokenKind::Set, found: TokenKind::Where }
+        )));
+    }
+
+    #[test]
+    fn create_table_stmt() {
+        let source = "CREATE TABLE t1 (c1 INTEGER);";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let source_span = Span::new(Location::new(16), Location::new(28));
+        let column_span = Span::new(Location::new(17), Location::new(27));
+        assert_eq!(
+            stmt,
+            Stmt::Create(ast::StmtCreate {
+                span: Span::new(Location::new(0), Location::new(28)),
+                exist_check: false,
+                id: ast::Identifier::new(
+                    Name::new("t1".to_string()),
+                    Span::new(Location::new(13), Location::new(15))
+                ),
+                kind: ast::CreateKind::Table(ast::CreateTable {
+                    span: source_span,
+                    source: ast::CreateTableSource::Columns(ast::CreateTableColumns {
+                        span: source_span,
+                        columns: vec![ast::ColumnDef {
+                            span: column_span,
+                            id: ast::Identifier::new(
+                                Name::new("c1".to_string()),
+                                Span::new(Location::new(17), Location::new(19))
+                            ),
+                            type_name: ast::TypeName {
+                                span: Span::new(Location::new(20), Location::new(27)),
+                                external_type: ast::ExternalType::Integer,
+                                number_field: None,
+                            },
+                            constraint_list: ast::ColumnConstraintList {
+                                span: Span::empty(Location::new(27)),
+                                constraints: vec![],
+                            }
+                        }],
+                        constraints: vec![],
+                    })
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn create_table_constraints() {
+        let source = "CREATE TABLE IF NOT EXISTS orders (
+                id SERIAL CONSTRAINT pk PRIMARY KEY,
+                customer INTEGER REFERENCES customers ON DELETE SET NULL,
+                total NUMERIC(10, 2) DEFAULT 0.0 NOT NULL,
+                code VARCHAR(8),
+                CONSTRAINT uq_code UNIQUE (code COLLATE nocase, customer DESC) ON CONFLICT FAIL,
+                CHECK (total >= 1),
+                FOREIGN KEY (customer, code) REFERENCES customers (id, code) ON UPDATE CASCADE,
+                PRIMARY KEY (id)
+            );";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0, "{:?}", parser.errors);
+        let Stmt::Create(create) = stmt else {
+            panic!("expected create statement");
+        };
+        assert!(create.exist_check);
+        assert_eq!(create.kind.target(), ast::DdlTargetKind::Table);
+        let ast::CreateKind::Table(table) = create.kind else {
+            panic!("expected create table");
+        };
+        let ast::CreateTableSource::Columns(definition) = table.source else {
+            panic!("expected column definitions");
+        };
+        assert_eq!(definition.columns.len(), 4);
+        let pk = &definition.columns[0].constraint_list.constraints[0];
+        assert_eq!(pk.name.as_ref().unwrap().id.as_str(), "pk");
+        assert!(matches!(pk.kind, ColumnConstraintKind::PrimaryKey(_)));
+        let ColumnConstraintKind::Foreign(ref foreign) =
+            definition.columns[1].constraint_list.constraints[0].kind else {
+            panic!("expected foreign key constraint");
+        };
+        assert!(foreign.column_names.is_empty());
+        assert_eq!(
+            foreign.foreign_key_clause_on.as_ref().unwrap().action,
+            ast::ForeignKeyClauseActions::Set(ast::ForeignKeyClauseActionSet::Null)
+        );
+
+        let constraints = definition.constraints;
+        assert_eq!(constraints.len(), 4);
+        assert_eq!(constraints[0].name.as_ref().unwrap().id.as_str(), "uq_code");
+        let ast::TableConstraintKind::Unique(ref unique) = constraints[0].kind else {
+            panic!("expected unique constraint");
+        };
+        assert_eq!(unique.columns.len(), 2);
+        assert!(unique.columns[0].collation.is_some());
+        assert_eq!(unique.columns[1].order, Some(ast::Order::Desc));
+        assert_eq!(unique.conflict_action, Some(ast::ConflictAction::Fail));
+        assert!(matches!(constraints[1].kind, ast::TableConstraintKind::Check(_)));
+        let ast::TableConstraintKind::ForeignKey(ref foreign) = constraints[2].kind else {
+            panic!("expected foreign key constraint");
+        };
+        assert_eq!(foreign.columns.len(), 2);
+        assert_eq!(foreign.clause.column_names.len(), 2);
+        assert!(matches!(constraints[3].kind, ast::TableConstraintKind::PrimaryKey(_)));
+    }
+
+    #[test]
+    fn create_table_as_select() {
+        let source = "CREATE TABLE t2 AS SELECT * FROM t1;";
+        let mut parser = Parser::new(source);
+        let Stmt::Create(create) = parser.parse_statement() else {
+            panic!("expected create statement");
+        };
+        assert_eq!(parser.errors.len(), 0);
+        let ast::CreateKind::Table(table) = create.kind else {
+            panic!("expected create table");
+        };
+        assert!(matches!(table.source, ast::CreateTableSource::Select(_)));
+    }
+
+    #[test]
+    fn create_table_errors() {
+        let source = "CREATE VIEW v1;";
+        let mut parser = Parser::new(source);
+        assert!(matches!(parser.parse_statement(), Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::InvalidCreateTarget
+        )));
+
+        // Column definitions may not follow table constraints
+        let source = "CREATE TABLE t1 (c1 INTEGER, PRIMARY KEY (c1), c2 INTEGER);";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 1);
+        let Stmt::Create(ast::StmtCreate {
+            kind: ast::CreateKind::Table(ast::CreateTable {
+                source: ast::CreateTableSource::Columns(table),
+                ..
+            }),
+            ..
+        }) = stmt
+        else {
+            panic!("expected create table statement, got {:?}", stmt);
+        };
+        assert_eq!(table.columns.len(), 1);
+        assert_eq!(table.constraints.len(), 1);
+    }
+
+    #[test]
+    fn alter_add_bad_constraint_recovers() {
+        let source = "ALTER TABLE t1 ADD COLUMN c1 INTEGER NOT NUL UNIQUE;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 1);
+        assert!(matches!(
+            parser.errors[0].kind,
+            ParseErrorKind::ExpectedToken { expected: TokenKind::Null, found: TokenKind::Name }
+        ));
+        let Stmt::Alter(alter) = stmt else {
+            panic!("expected alter statement, got {:?}", stmt);
+        };
+        let ast::AlterTableActionKind::Add(add) = alter.action.kind else {
+            panic!("expected add column action");
+        };
+        assert_eq!(add.column.id.id.as_str(), "c1");
+        let constraints = add.column.constraint_list.constraints;
+        assert_eq!(constraints.len(), 1);
+        assert!(matches!(constraints[0].kind, ColumnConstraintKind::Unique(None)));
+    }
+
+    #[test]
+    fn create_table_recovers_per_column() {
+        let source = "CREATE TABLE t1 (c1 INTEGER CHECK (, c2 FOO, c3 VARCHAR(10) DEFAULT 'x');";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        let kinds: Vec<_> = parser.errors.iter().map(|error| &error.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &ParseErrorKind::ExpectedExpression { found: TokenKind::Comma },
+                &ParseErrorKind::ExpectedType { found: TokenKind::Name },
+            ]
+        );
+        let Stmt::Create(ast::StmtCreate {
+            kind: ast::CreateKind::Table(ast::CreateTable {
+                source: ast::CreateTableSource::Columns(table),
+                ..
+            }),
+            ..
+        }) = stmt
+        else {
+            panic!("expected create table statement, got {:?}", stmt);
+        };
+        let names: Vec<_> = table.columns.iter().map(|column| column.id.id.as_str()).collect();
+        assert_eq!(names, ["c1", "c3"]);
+        assert!(table.columns[0].constraint_list.constraints.is_empty());
+        assert_eq!(table.columns[1].constraint_list.constraints.len(), 1);
+    }
+
+    #[test]
+    fn select_recovers_per_clause() {
+        let source = "SELECT c1, FROM t1 WHERE ORDER BY c1 LIMIT 10;";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 2);
+        assert!(matches!(
+            parser.errors[0].kind,
+            ParseErrorKind::ExpectedExpression { found: TokenKind::From }
+        ));
+        assert!(matches!(
+            parser.errors[1].kind,
+            ParseErrorKind::ExpectedExpression { found: TokenKind::Order }
+        ));
+        let Stmt::Select(select) = stmt else {
+            panic!("expected select statement, got {:?}", stmt);
+        };
+        assert_eq!(select.columns.len(), 2);
+        assert!(select.from.is_some());
+        let Some(ast::Expr::Invalid(invalid)) = select.where_clause else {
+            panic!("expected an invalid where clause");
+        };
+        assert_eq!(invalid.span, Span::empty(Location::new(25)));
+        assert_eq!(select.order_by.len(), 1);
+        assert!(select.limit.is_some());
+    }
+
+    #[test]
+    fn identifier_placeholders() {
+        let source = "DROP TABLE;";
+        let mut parser = Parser::new(source);
+        let Stmt::Drop(drop) = parser.parse_statement() else {
+            panic!("expected drop statement");
+        };
+        assert!(!drop.id.is_valid());
+        assert_eq!(drop.id.span, Span::empty(Location::new(10)));
+        assert_eq!(parser.errors.len(), 1);
+
+        // A keyword used as a name is taken as the placeholder
+        let source = "CREATE TABLE order (c1 INTEGER);";
+        let mut parser = Parser::new(source);
+        let Stmt::Create(create) = parser.parse_statement() else {
+            panic!("expected create statement");
+        };
+        assert!(!create.id.is_valid());
+        assert_eq!(create.id.span, Span::new(Location::new(13), Location::new(18)));
+        assert!(matches!(create.kind, ast::CreateKind::Table(_)));
+        assert_eq!(parser.errors.len(), 1);
+    }
+
+    #[test]
+    fn create_index_stmt() {
+        let source = "CREATE INDEX i1 ON t1 (c1);";
+        let mut parser = Parser::new(source);
+        let stmt = parser.parse_statement();
+        assert_eq!(parser.errors.len(), 0);
+        let column_span = Span::new(Location::new(23), Location::new(25));
+        assert_eq!(
+            stmt,
+            Stmt::Create(ast::StmtCreate {
+                span: Span::new(Location::new(0), Location::new(26)),
+                exist_check: false,
+                id: ast::Identifier::new(
+                    Name::new("i1".to_string()),
+                    Span::new(Location::new(13), Location::new(15))
+                ),
+                kind: ast::CreateKind::Index(ast::CreateIndex {
+                    span: Span::new(Location::new(7), Location::new(26)),
+                    unique: false,
+                    table: ast::Identifier::new(
+                        Name::new("t1".to_string()),
+                        Span::new(Location::new(19), Location::new(21))
+                    ),
+                    columns: vec![ast::IndexedColumn {
+                        span: column_span,
+                        expr: ast::Expr::Column(ast::ExprColumn {
+                            span: column_span,
+                            table: None,
+                            column: ast::Identifier::new(Name::new("c1".to_string()), column_span),
+                        }),
+                        collation: None,
+                        order: None,
+                    }],
+                    where_clause: None,
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn create_unique_partial_index() {
+        let source = "CREATE UNIQUE INDEX IF NOT EXISTS i1 ON t1 (
+                c1 COLLATE nocase ASC, c2 * 2 DESC
+            ) WHERE c3 IS NOT NULL;";
+        let mut parser = Parser::new(source);
+        let Stmt::Create(create) = parser.parse_statement() else {
+            panic!("expected create statement");
+        };
+        assert_eq!(parser.errors.len(), 0);
+        assert!(create.exist_check);
+        assert_eq!(create.kind.target(), ast::DdlTargetKind::Index);
+        let ast::CreateKind::Index(index) = create.kind else {
+            panic!("expected create index");
+        };
+        assert!(index.unique);
+        assert_eq!(index.columns.len(), 2);
+        assert_eq!(index.columns[0].collation.as_ref().unwrap().id.as_str(), "nocase");
+        assert_eq!(index.columns[0].order, Some(ast::Order::Asc));
+        assert!(matches!(index.columns[1].expr, ast::Expr::BinOp(_)));
+        assert_eq!(index.columns[1].order, Some(ast::Order::Desc));
+        assert!(matches!(index.where_clause, Some(ast::Expr::IsNull(_))));
+    }
+
+    #[test]
+    fn create_unique_requires_index() {
+        let source = "CREATE UNIQUE TABLE t1 (c1 INTEGER);";
+        let mut parser = Parser::new(source);
+        assert!(matches!(parser.parse_statement(), Stmt::Invalid(_)));
+        assert!(parser.errors.first().is_some_and(|first| matches!(
+            first.kind,
+            ParseErrorKind::ExpectedToken { expected: TokenKind::Index, found: TokenKind::Table }
+        )));
+    }
 }
@@ -1,24 +1,33 @@
 use source_index::span::Span;
 
 use crate::{
+    errors::LexicalError,
     lexer::Lexer,
     tokens::{Token, TokenKind, TokenValue},
+    Comment, CommentPlacement,
 };
 
 pub struct TokenSource<'src> {
+    source: &'src str,
+
     // underlying lexer for the tokens
     lexer: Lexer<'src>,
 
     // vector containing all tokens after parser has finished.
     tokens: Vec<Token>,
+
+    // comments in the order they were lexed
+    comments: Vec<Comment>,
 }
 
 impl<'src> TokenSource<'src> {
     pub fn new(source: &'src str) -> Self {
         let lexer = Lexer::new(source);
         let mut token_source = Self {
+            source,
             lexer,
             tokens: Vec::new(),
+            comments: Vec::new(),
         };
         // Move to first token.
         token_source.do_bump();
@@ -30,20 +39,41 @@ impl<'src> TokenSource<'src> {
         loop {
             let kind = self.lexer.next_token();
             if matches!(kind, TokenKind::Comment) {
-                self.tokens.push(Token::new(kind, self.current_span()));
+                let span = self.current_span();
+                self.tokens.push(Token::new(kind, span));
+                self.comments.push(Comment {
+                    span,
+                    placement: self.comment_placement(span),
+                });
                 continue;
             }
             break;
         }
     }
 
+    // A comment is trailing when it shares a line with the token before it,
+    // otherwise it leads whatever follows.
+    fn comment_placement(&self, span: Span) -> CommentPlacement {
+        let previous = self
+            .tokens
+            .iter()
+            .rev()
+            .find(|token| token.kind() != TokenKind::Comment);
+        match previous {
+            Some(token) if !self.source[*token.end()..*span.start()].contains('\n') => {
+                CommentPlacement::Trailing
+            }
+            _ => CommentPlacement::Leading,
+        }
+    }
+
     pub fn bump(&mut self, kind: TokenKind) {
         self.tokens.push(Token::new(kind, self.current_span()));
         self.do_bump();
     }
 
     pub fn bump_any(&mut self) {
-        self.lexer.next_token();
+        self.bump(self.current_token_kind());
     }
 
     /// Calls underlying lexer's [`take_value`]
@@ -52,16 +82,10 @@ impl<'src> TokenSource<'src> {
     }
 
     // bumps any token as if `[bump]` was called.
-    // Stops on stop token
+    // Stops on stop token or end of file
     pub fn skip_bump(&mut self, stop_token: TokenKind) {
-        loop {
-            if self.lexer.current_kind() == stop_token {
-                return;
-            }
-            let kind = self.lexer.next_token();
-            if kind == stop_token {
-                return;
-            }
+        while !matches!(self.current_token_kind(), kind if kind == stop_token || kind == TokenKind::EndOfFile) {
+            self.bump_any();
         }
     }
 
@@ -90,7 +114,7 @@ impl<'src> TokenSource<'src> {
         }
     }
 
-    pub fn finish(mut self) -> Vec<Token> {
+    pub fn finish(mut self) -> (Vec<Token>, Vec<Comment>, Vec<LexicalError>) {
         assert_eq!(
             self.current_token_kind(),
             TokenKind::EndOfFile,
@@ -101,6 +125,6 @@ impl<'src> TokenSource<'src> {
             assert_eq!(last.kind(), TokenKind::EndOfFile);
         }
 
-        self.tokens
+        (self.tokens, self.comments, self.lexer.finish())
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use ast::name::Name;
+use ast::{name::Name, ParameterKind};
 use source_index::{location::Location, span::Span};
 
 #[derive(PartialEq, Clone, Debug, Eq, Copy)]
@@ -38,6 +38,8 @@ pub enum TokenKind {
     Name,
     TableAll,
     TableCol,
+    // Bind parameter: ?, ?NNN, $NNN, :name, @name, $name
+    Parameter,
 
     // Keywords -------------
     Abort,
@@ -52,6 +54,8 @@ pub enum TokenKind {
     Between,
     By,
     Cascade,
+    Case,
+    Cast,
     Check,
     Collate,
     Column,
@@ -60,17 +64,28 @@ pub enum TokenKind {
     Constraint,
     Create,
     Cross,
+    Current,
     Default,
     Delete,
     Desc,
     Distinct,
+    Do,
     Drop,
+    Else,
+    End,
     Except,
+    Exclude,
     Exists,
     Fail,
+    False,
+    Filter,
+    First,
+    Following,
+    Foreign,
     From,
     Full,
     Group,
+    Groups,
     Having,
     If,
     Ignore,
@@ -79,43 +94,65 @@ pub enum TokenKind {
     Indexed,
     Inner,
     Insert,
+    Intersect,
+    Into,
     Is,
     Join,
     Key,
+    Last,
     Left,
     Like,
     Limit,
+    Materialized,
     Natural,
     No,
     Not,
+    Nothing,
     Null,
+    Nulls,
     Offset,
     On,
     Or,
     Order,
+    Others,
     Outer,
+    Over,
+    Partition,
+    Preceding,
     Primary,
+    Range,
+    Recursive,
     References,
     Reindex,
     Release,
     Rename,
     Replace,
     Restrict,
+    Returning,
     Right,
     Rollback,
+    Row,
+    Rows,
     Savepoint,
     Select,
     Set,
     Table,
+    Then,
+    Ties,
     To,
     Transaction,
+    True,
+    Unbounded,
     Union,
     Unique,
     Update,
     Using,
     Vacuum,
     Values,
+    When,
     Where,
+    Window,
+    With,
 
     // matches '('
     LParen,
@@ -158,6 +195,7 @@ pub enum TokenKind {
     Varchar,
 
     // Interior Types
+    Blob,
     Float,
     Int,
     String,
@@ -167,7 +205,180 @@ pub enum TokenKind {
     EndOfFile,
 }
 
+/// Every keyword with the token it lexes to, sorted by spelling so it can be
+/// searched. Keywords are matched case insensitively.
+pub(crate) const KEYWORDS: &[(&str, TokenKind)] = &[
+    ("ABORT", TokenKind::Abort),
+    ("ACTION", TokenKind::Action),
+    ("ADD", TokenKind::Add),
+    ("ALL", TokenKind::All),
+    ("ALTER", TokenKind::Alter),
+    ("AND", TokenKind::And),
+    ("AS", TokenKind::As),
+    ("ASC", TokenKind::Asc),
+    ("BEGIN", TokenKind::Begin),
+    ("BETWEEN", TokenKind::Between),
+    ("BY", TokenKind::By),
+    ("CASCADE", TokenKind::Cascade),
+    ("CASE", TokenKind::Case),
+    ("CAST", TokenKind::Cast),
+    ("CHAR", TokenKind::Char),
+    ("CHECK", TokenKind::Check),
+    ("COLLATE", TokenKind::Collate),
+    ("COLUMN", TokenKind::Column),
+    ("COMMIT", TokenKind::Commit),
+    ("CONFLICT", TokenKind::Conflict),
+    ("CONSTRAINT", TokenKind::Constraint),
+    ("CREATE", TokenKind::Create),
+    ("CROSS", TokenKind::Cross),
+    ("CURRENT", TokenKind::Current),
+    ("DEFAULT", TokenKind::Default),
+    ("DELETE", TokenKind::Delete),
+    ("DESC", TokenKind::Desc),
+    ("DISTINCT", TokenKind::Distinct),
+    ("DO", TokenKind::Do),
+    ("DROP", TokenKind::Drop),
+    ("ELSE", TokenKind::Else),
+    ("END", TokenKind::End),
+    ("EXCEPT", TokenKind::Except),
+    ("EXCLUDE", TokenKind::Exclude),
+    ("EXISTS", TokenKind::Exists),
+    ("FAIL", TokenKind::Fail),
+    ("FALSE", TokenKind::False),
+    ("FILTER", TokenKind::Filter),
+    ("FIRST", TokenKind::First),
+    ("FOLLOWING", TokenKind::Following),
+    ("FOREIGN", TokenKind::Foreign),
+    ("FROM", TokenKind::From),
+    ("FULL", TokenKind::Full),
+    ("GROUP", TokenKind::Group),
+    ("GROUPS", TokenKind::Groups),
+    ("HAVING", TokenKind::Having),
+    ("IF", TokenKind::If),
+    ("IGNORE", TokenKind::Ignore),
+    ("IN", TokenKind::In),
+    ("INDEX", TokenKind::Index),
+    ("INDEXED", TokenKind::Indexed),
+    ("INNER", TokenKind::Inner),
+    ("INSERT", TokenKind::Insert),
+    ("INTEGER", TokenKind::Integer),
+    ("INTERSECT", TokenKind::Intersect),
+    ("INTO", TokenKind::Into),
+    ("IS", TokenKind::Is),
+    ("JOIN", TokenKind::Join),
+    ("KEY", TokenKind::Key),
+    ("LAST", TokenKind::Last),
+    ("LEFT", TokenKind::Left),
+    ("LIKE", TokenKind::Like),
+    ("LIMIT", TokenKind::Limit),
+    ("MATERIALIZED", TokenKind::Materialized),
+    ("NATURAL", TokenKind::Natural),
+    ("NO", TokenKind::No),
+    ("NOT", TokenKind::Not),
+    ("NOTHING", TokenKind::Nothing),
+    ("NULL", TokenKind::Null),
+    ("NULLS", TokenKind::Nulls),
+    ("NUMERIC", TokenKind::Numeric),
+    ("OFFSET", TokenKind::Offset),
+    ("ON", TokenKind::On),
+    ("OR", TokenKind::Or),
+    ("ORDER", TokenKind::Order),
+    ("OTHERS", TokenKind::Others),
+    ("OUTER", TokenKind::Outer),
+    ("OVER", TokenKind::Over),
+    ("PARTITION", TokenKind::Partition),
+    ("PRECEDING", TokenKind::Preceding),
+    ("PRIMARY", TokenKind::Primary),
+    ("RANGE", TokenKind::Range),
+    ("RECURSIVE", TokenKind::Recursive),
+    ("REFERENCES", TokenKind::References),
+    ("REINDEX", TokenKind::Reindex),
+    ("RELEASE", TokenKind::Release),
+    ("RENAME", TokenKind::Rename),
+    ("REPLACE", TokenKind::Replace),
+    ("RESTRICT", TokenKind::Restrict),
+    ("RETURNING", TokenKind::Returning),
+    ("RIGHT", TokenKind::Right),
+    ("ROLLBACK", TokenKind::Rollback),
+    ("ROW", TokenKind::Row),
+    ("ROWS", TokenKind::Rows),
+    ("SAVEPOINT", TokenKind::Savepoint),
+    ("SELECT", TokenKind::Select),
+    ("SERIAL", TokenKind::Serial),
+    ("SET", TokenKind::Set),
+    ("TABLE", TokenKind::Table),
+    ("THEN", TokenKind::Then),
+    ("TIES", TokenKind::Ties),
+    ("TO", TokenKind::To),
+    ("TRANSACTION", TokenKind::Transaction),
+    ("TRUE", TokenKind::True),
+    ("UNBOUNDED", TokenKind::Unbounded),
+    ("UNION", TokenKind::Union),
+    ("UNIQUE", TokenKind::Unique),
+    ("UPDATE", TokenKind::Update),
+    ("USING", TokenKind::Using),
+    ("VACUUM", TokenKind::Vacuum),
+    ("VALUES", TokenKind::Values),
+    ("VARCHAR", TokenKind::Varchar),
+    ("WHEN", TokenKind::When),
+    ("WHERE", TokenKind::Where),
+    ("WINDOW", TokenKind::Window),
+    ("WITH", TokenKind::With),
+];
+
 impl TokenKind {
+    /// The keyword spelled by `text`, in any case.
+    pub fn from_keyword(text: &str) -> Option<TokenKind> {
+        let upper = text.to_ascii_uppercase();
+        KEYWORDS
+            .binary_search_by(|(keyword, _)| (*keyword).cmp(upper.as_str()))
+            .ok()
+            .map(|i| KEYWORDS[i].1)
+    }
+
+    /// The upper case spelling of a keyword token.
+    pub fn keyword_text(self) -> Option<&'static str> {
+        KEYWORDS
+            .iter()
+            .find(|(_, kind)| *kind == self)
+            .map(|(keyword, _)| *keyword)
+    }
+
+    /// Whether the token is a keyword, including the type name keywords.
+    pub fn is_keyword(self) -> bool {
+        // Keywords are declared in one block from `Abort` to `With`.
+        (TokenKind::Abort as u8..=TokenKind::With as u8).contains(&(self as u8)) || self.is_type()
+    }
+
+    /// Whether the keyword only has a meaning in window definitions and
+    /// ordering terms, and can otherwise be used as a name.
+    pub fn is_non_reserved(self) -> bool {
+        matches!(
+            self,
+            TokenKind::First
+            | TokenKind::Last
+            | TokenKind::Row
+            | TokenKind::Rows
+            | TokenKind::Range
+            | TokenKind::Groups
+            | TokenKind::Filter
+            | TokenKind::Current
+            | TokenKind::Others
+            | TokenKind::Ties
+            | TokenKind::Preceding
+            | TokenKind::Following
+            | TokenKind::Unbounded
+            | TokenKind::Partition
+            | TokenKind::Exclude
+            | TokenKind::Nulls
+            | TokenKind::Over
+        )
+    }
+
+    /// Whether the token can be parsed as an identifier.
+    pub fn is_name(self) -> bool {
+        self == TokenKind::Name || self.is_non_reserved()
+    }
 
     pub fn is_type(self) -> bool {
         matches!(
@@ -190,13 +401,85 @@ impl TokenKind {
             | TokenKind::Default
             | TokenKind::Collate
             | TokenKind::References
+            | TokenKind::Constraint
+        )
+    }
+
+    pub fn is_table_constraint(self) -> bool {
+        matches!(
+            self,
+            TokenKind::Constraint
+            | TokenKind::Primary
+            | TokenKind::Unique
+            | TokenKind::Check
+            | TokenKind::Foreign
+        )
+    }
+
+    /// Whether the token can begin a `<select-stmt>`.
+    pub fn is_select_start(self) -> bool {
+        matches!(self, TokenKind::Select | TokenKind::With)
+    }
+
+    pub fn is_join_operator(self) -> bool {
+        matches!(
+            self,
+            TokenKind::Comma
+            | TokenKind::Cross
+            | TokenKind::Natural
+            | TokenKind::Inner
+            | TokenKind::Left
+            | TokenKind::Right
+            | TokenKind::Full
+            | TokenKind::Join
+        )
+    }
+
+    pub fn is_compound_operator(self) -> bool {
+        matches!(
+            self,
+            TokenKind::Union
+            | TokenKind::Intersect
+            | TokenKind::Except
         )
     }
 }
 
+/// Describes the token the way it is written, for error messages.
 impl Display for TokenKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        if let Some(keyword) = self.keyword_text() {
+            return write!(f, "`{keyword}`");
+        }
+        match self {
+            TokenKind::Name => f.write_str("an identifier"),
+            TokenKind::TableAll => f.write_str("`table.*`"),
+            TokenKind::TableCol => f.write_str("a qualified column name"),
+            TokenKind::Parameter => f.write_str("a bind parameter"),
+            TokenKind::LParen => f.write_str("`(`"),
+            TokenKind::RParen => f.write_str("`)`"),
+            TokenKind::Semicolon => f.write_str("`;`"),
+            TokenKind::Minus => f.write_str("`-`"),
+            TokenKind::Plus => f.write_str("`+`"),
+            TokenKind::Slash => f.write_str("`/`"),
+            TokenKind::Star => f.write_str("`*`"),
+            TokenKind::Percent => f.write_str("`%`"),
+            TokenKind::Equals => f.write_str("`=`"),
+            TokenKind::Greater => f.write_str("`>`"),
+            TokenKind::GreaterEqual => f.write_str("`>=`"),
+            TokenKind::Less => f.write_str("`<`"),
+            TokenKind::LessEqual => f.write_str("`<=`"),
+            TokenKind::Exclamation => f.write_str("`!`"),
+            TokenKind::NotEqual => f.write_str("`!=`"),
+            TokenKind::Comma => f.write_str("`,`"),
+            TokenKind::Blob => f.write_str("a blob literal"),
+            TokenKind::Float | TokenKind::Int => f.write_str("a number"),
+            TokenKind::String => f.write_str("a string literal"),
+            TokenKind::Comment => f.write_str("a comment"),
+            TokenKind::Unknown => f.write_str("an invalid token"),
+            TokenKind::EndOfFile => f.write_str("the end of input"),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
@@ -209,7 +492,9 @@ pub enum TokenValue {
     TableAll(Name),
     TableCol(Name, Name),
 
+    Blob(Vec<u8>),
     Float(f64),
     Int(i64),
     String(String),
+    Parameter(ParameterKind),
 }
@@ -0,0 +1,402 @@
+//! The textual plan dump: one operator per line, inputs indented below it,
+//! then the plan of every subquery.
+//!
+//! Expressions print as SQL with columns as `relation.column`. Relations
+//! without a name, like the groups of an [`crate::plan::Aggregate`], print
+//! as `#id`, which is also how a subquery refers to its plan.
+
+use std::fmt::{Display, Formatter, Result, Write};
+
+use ast::{BinaryOperator, BooleanOperator, UnaryOperator};
+use binder::bound::{
+    BoundAssignment, BoundExpr, BoundFunction, BoundInKind, BoundOrderingTerm, BoundResultColumn, BoundUpsertAction,
+    Relation, RelationId,
+};
+use binder::ColumnId;
+
+use crate::plan::{JoinKind, LogicalPlan, Plan, ScanSource};
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let printer = Printer { relations: &self.relations };
+        printer.plan(f, &self.root, 0)?;
+        for (id, subquery) in &self.subqueries {
+            writeln!(f, "Subquery #{}:", id.0)?;
+            printer.plan(f, subquery, 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for JoinKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            JoinKind::Inner => f.write_str("INNER"),
+            JoinKind::Left => f.write_str("LEFT"),
+            JoinKind::Right => f.write_str("RIGHT"),
+            JoinKind::Full => f.write_str("FULL"),
+            JoinKind::Semi => f.write_str("SEMI"),
+            JoinKind::Anti => f.write_str("ANTI"),
+        }
+    }
+}
+
+/// The text of `expr` with every relation printed by id, equal for
+/// expressions that only differ in their spans.
+pub(crate) fn expr_key(expr: &BoundExpr) -> String {
+    Printer { relations: &[] }.expr(expr)
+}
+
+/// The text of `expr` as in the dump of a plan with `relations`.
+pub(crate) fn expr_text(expr: &BoundExpr, relations: &[Relation]) -> String {
+    Printer { relations }.expr(expr)
+}
+
+struct Printer<'a> {
+    relations: &'a [Relation],
+}
+
+impl Printer<'_> {
+    fn plan(&self, f: &mut Formatter<'_>, plan: &LogicalPlan, depth: usize) -> Result {
+        write!(f, "{:indent$}", "", indent = depth * 2)?;
+        match plan {
+            LogicalPlan::Scan(scan) => {
+                write!(f, "Scan: {}", self.relation(scan.relation))?;
+                let all = self.relations.get(scan.relation.0 as usize).map(|relation| relation.columns.len());
+                if all != Some(scan.columns.len()) {
+                    let columns: Vec<String> = scan
+                        .columns
+                        .iter()
+                        .map(|column| self.column(scan.relation, *column))
+                        .collect();
+                    write!(f, " ({})", columns.join(", "))?;
+                }
+                if let ScanSource::Cte(_) = scan.source {
+                    f.write_str(" (recursive)")?;
+                }
+            }
+            LogicalPlan::Filter(filter) => write!(f, "Filter: {}", self.expr(&filter.predicate))?,
+            LogicalPlan::Project(project) => write!(f, "Project: {}", self.results(&project.columns))?,
+            LogicalPlan::Join(join) => {
+                write!(f, "Join: {}", join.kind)?;
+                if let Some(condition) = &join.condition {
+                    write!(f, " ON {}", self.expr(condition))?;
+                }
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                f.write_str("Aggregate:")?;
+                if !aggregate.group_by.is_empty() {
+                    write!(f, " group by [{}]", self.exprs(&aggregate.group_by))?;
+                }
+                if !aggregate.aggregates.is_empty() {
+                    let aggregates: Vec<String> = aggregate.aggregates.iter().map(|a| self.function(a)).collect();
+                    write!(f, " aggregates [{}]", aggregates.join(", "))?;
+                }
+            }
+            LogicalPlan::Sort(sort) => {
+                let terms: Vec<String> = sort.terms.iter().map(|term| self.ordering_term(term)).collect();
+                write!(f, "Sort: {}", terms.join(", "))?;
+            }
+            LogicalPlan::Limit(limit) => {
+                write!(f, "Limit: {}", self.expr(&limit.limit))?;
+                if let Some(offset) = &limit.offset {
+                    write!(f, " OFFSET {}", self.expr(offset))?;
+                }
+            }
+            LogicalPlan::Values(values) => {
+                let rows: Vec<String> = values.rows.iter().map(|row| format!("({})", self.exprs(row))).collect();
+                write!(f, "Values: {}", rows.join(", "))?;
+            }
+            LogicalPlan::SetOp(set_op) => write!(f, "SetOp: {}", set_op.op)?,
+            LogicalPlan::Insert(insert) => {
+                write!(f, "Insert: {}", self.relation(insert.relation))?;
+                if !insert.columns.is_empty() {
+                    let columns: Vec<String> = insert
+                        .columns
+                        .iter()
+                        .map(|column| self.column(insert.relation, *column))
+                        .collect();
+                    write!(f, " ({})", columns.join(", "))?;
+                }
+                if let Some(upsert) = &insert.upsert {
+                    f.write_str(" ON CONFLICT")?;
+                    if let Some(target) = &upsert.target {
+                        write!(f, " ({})", self.exprs(&target.columns))?;
+                        if let Some(where_clause) = &target.where_clause {
+                            write!(f, " WHERE {}", self.expr(where_clause))?;
+                        }
+                    }
+                    match &upsert.action {
+                        BoundUpsertAction::Nothing => f.write_str(" DO NOTHING")?,
+                        BoundUpsertAction::Update {
+                            assignments,
+                            where_clause,
+                            ..
+                        } => {
+                            write!(f, " DO UPDATE SET {}", self.assignments(insert.relation, assignments))?;
+                            if let Some(where_clause) = where_clause {
+                                write!(f, " WHERE {}", self.expr(where_clause))?;
+                            }
+                        }
+                    }
+                }
+                self.returning(f, &insert.returning)?;
+            }
+            LogicalPlan::Update(update) => {
+                write!(
+                    f,
+                    "Update: {} SET {}",
+                    self.relation(update.relation),
+                    self.assignments(update.relation, &update.assignments)
+                )?;
+                self.returning(f, &update.returning)?;
+            }
+            LogicalPlan::Delete(delete) => {
+                write!(f, "Delete: {}", self.relation(delete.relation))?;
+                self.returning(f, &delete.returning)?;
+            }
+        }
+        writeln!(f)?;
+        for input in plan.inputs() {
+            self.plan(f, input, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn relation(&self, id: RelationId) -> String {
+        match self.relations.get(id.0 as usize) {
+            Some(relation) if relation.name.is_valid() => relation.name.to_string(),
+            _ => format!("#{}", id.0),
+        }
+    }
+
+    fn results(&self, columns: &[BoundResultColumn]) -> String {
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|column| match &column.expr {
+                BoundExpr::Column(expr) if expr.name == column.name => self.expr(&column.expr),
+                expr => format!("{} AS {}", self.expr(expr), column.name),
+            })
+            .collect();
+        columns.join(", ")
+    }
+
+    fn returning(&self, f: &mut Formatter<'_>, columns: &[BoundResultColumn]) -> Result {
+        if !columns.is_empty() {
+            write!(f, " RETURNING {}", self.results(columns))?;
+        }
+        Ok(())
+    }
+
+    fn column(&self, relation: RelationId, column: ColumnId) -> String {
+        match self.relations.get(relation.0 as usize) {
+            Some(relation) => relation.columns[column.0 as usize].name.to_string(),
+            None => format!("#{}", column.0),
+        }
+    }
+
+    fn assignments(&self, relation: RelationId, assignments: &[BoundAssignment]) -> String {
+        let assignments: Vec<String> = assignments
+            .iter()
+            .map(|assignment| {
+                let columns: Vec<String> = assignment
+                    .columns
+                    .iter()
+                    .map(|column| self.column(relation, *column))
+                    .collect();
+                match columns.as_slice() {
+                    [column] => format!("{column} = {}", self.expr(&assignment.expr)),
+                    _ => format!("({}) = {}", columns.join(", "), self.expr(&assignment.expr)),
+                }
+            })
+            .collect();
+        assignments.join(", ")
+    }
+
+    fn exprs(&self, exprs: &[BoundExpr]) -> String {
+        exprs.iter().map(|expr| self.expr(expr)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn ordering_term(&self, term: &BoundOrderingTerm) -> String {
+        let mut text = self.expr(&term.expr);
+        if let Some(collation) = &term.collation {
+            let _ = write!(text, " COLLATE {collation}");
+        }
+        if let Some(order) = &term.order {
+            let _ = write!(text, " {order}");
+        }
+        if let Some(nulls) = &term.nulls {
+            let _ = write!(text, " {nulls}");
+        }
+        text
+    }
+
+    fn function(&self, function: &BoundFunction) -> String {
+        let distinct = if function.distinct { "DISTINCT " } else { "" };
+        let args = match &function.args {
+            Some(args) => self.exprs(args),
+            None => "*".to_string(),
+        };
+        let mut text = format!("{}({distinct}{args})", function.name);
+        if let Some(filter) = &function.filter {
+            let _ = write!(text, " FILTER (WHERE {})", self.expr(filter));
+        }
+        if let Some(over) = &function.over {
+            let mut window = Vec::new();
+            if !over.partition_by.is_empty() {
+                window.push(format!("PARTITION BY {}", self.exprs(&over.partition_by)));
+            }
+            if !over.order_by.is_empty() {
+                let terms: Vec<String> = over.order_by.iter().map(|term| self.ordering_term(term)).collect();
+                window.push(format!("ORDER BY {}", terms.join(", ")));
+            }
+            if let Some(frame) = &over.frame {
+                window.push(frame.to_string());
+            }
+            let _ = write!(text, " OVER ({})", window.join(" "));
+        }
+        text
+    }
+
+    fn expr(&self, expr: &BoundExpr) -> String {
+        match expr {
+            // The AST prints negative integers, which only come from
+            // hexadecimal literals, in hexadecimal.
+            BoundExpr::Literal(ast::LiteralValue::IntLiteral(literal)) => literal.value.to_string(),
+            BoundExpr::Literal(literal) => literal.to_string(),
+            BoundExpr::Column(column) => format!("{}.{}", self.relation(column.relation), column.name),
+            BoundExpr::BinaryOp(expr) => {
+                let precedence = if matches!(expr.op, BinaryOperator::Add | BinaryOperator::Sub) { ADD } else { MULT };
+                format!(
+                    "{} {} {}",
+                    self.operand(&expr.left, precedence),
+                    expr.op,
+                    self.operand(&expr.right, precedence + 1)
+                )
+            }
+            BoundExpr::UnaryOp(expr) => match expr.op {
+                UnaryOperator::Not => format!("NOT {}", self.operand(&expr.operand, NOT)),
+                _ => {
+                    let operand = self.operand(&expr.operand, ATOM);
+                    // Keep `- -1` from turning into a `--` comment.
+                    let space = if operand.starts_with(['-', '+']) { " " } else { "" };
+                    format!("{}{space}{operand}", expr.op)
+                }
+            },
+            BoundExpr::Compare(expr) => format!(
+                "{} {} {}",
+                self.operand(&expr.left, ADD),
+                expr.op,
+                self.operand(&expr.right, ADD)
+            ),
+            BoundExpr::BoolOp(expr) => {
+                let precedence = if expr.op == BooleanOperator::And { AND } else { OR };
+                format!(
+                    "{} {} {}",
+                    self.operand(&expr.left, precedence),
+                    expr.op,
+                    self.operand(&expr.right, precedence + 1)
+                )
+            }
+            BoundExpr::List(list) => format!("({})", self.exprs(&list.exprs)),
+            BoundExpr::Subquery(subquery) => format!("(#{})", subquery.select.id.0),
+            BoundExpr::Collate(expr) => format!("{} COLLATE {}", self.operand(&expr.expr, ATOM), expr.collation),
+            BoundExpr::Like(expr) => format!(
+                "{} {}LIKE {}",
+                self.operand(&expr.expr, ADD),
+                not(expr.negated),
+                self.operand(&expr.pattern, ADD)
+            ),
+            BoundExpr::IsNull(expr) => format!("{} IS {}NULL", self.operand(&expr.expr, ADD), not(expr.negated)),
+            BoundExpr::Is(expr) => format!(
+                "{} IS {}{}{}",
+                self.operand(&expr.left, ADD),
+                not(expr.negated),
+                if expr.distinct_from { "DISTINCT FROM " } else { "" },
+                self.operand(&expr.right, ADD)
+            ),
+            BoundExpr::Between(expr) => format!(
+                "{} {}BETWEEN {} AND {}",
+                self.operand(&expr.expr, ADD),
+                not(expr.negated),
+                self.operand(&expr.low, ADD),
+                self.operand(&expr.high, ADD)
+            ),
+            BoundExpr::In(expr) => {
+                let list = match &expr.kind {
+                    BoundInKind::List(list) => format!("({})", self.exprs(list)),
+                    BoundInKind::Select(select) => format!("(#{})", select.id.0),
+                    BoundInKind::Table(relation) => self.relation(*relation),
+                };
+                format!("{} {}IN {list}", self.operand(&expr.expr, ADD), not(expr.negated))
+            }
+            BoundExpr::Exists(exists) => format!("{}EXISTS (#{})", not(exists.negated), exists.select.id.0),
+            BoundExpr::Function(function) => self.function(function),
+            BoundExpr::Case(expr) => {
+                let mut text = "CASE".to_string();
+                if let Some(operand) = &expr.operand {
+                    let _ = write!(text, " {}", self.expr(operand));
+                }
+                for branch in &expr.branches {
+                    let _ = write!(
+                        text,
+                        " WHEN {} THEN {}",
+                        self.expr(&branch.condition),
+                        self.expr(&branch.result)
+                    );
+                }
+                if let Some(else_expr) = &expr.else_expr {
+                    let _ = write!(text, " ELSE {}", self.expr(else_expr));
+                }
+                text + " END"
+            }
+            BoundExpr::Cast(expr) => format!("CAST({} AS {})", self.expr(&expr.expr), expr.type_name),
+            BoundExpr::Parameter(parameter) => parameter.kind.to_string(),
+            BoundExpr::Invalid(_) => "<invalid>".to_string(),
+        }
+    }
+
+    /// The text of `expr`, in parentheses if it binds less tightly than
+    /// `precedence`.
+    fn operand(&self, expr: &BoundExpr, precedence: u8) -> String {
+        let text = self.expr(expr);
+        if self::precedence(expr) < precedence {
+            format!("({text})")
+        } else {
+            text
+        }
+    }
+}
+
+const OR: u8 = 1;
+const AND: u8 = 2;
+const NOT: u8 = 3;
+const COMPARE: u8 = 4;
+const ADD: u8 = 5;
+const MULT: u8 = 6;
+const ATOM: u8 = 7;
+
+fn precedence(expr: &BoundExpr) -> u8 {
+    match expr {
+        BoundExpr::BoolOp(expr) if expr.op == BooleanOperator::Or => OR,
+        BoundExpr::BoolOp(_) => AND,
+        BoundExpr::UnaryOp(expr) if expr.op == UnaryOperator::Not => NOT,
+        BoundExpr::Compare(_)
+        | BoundExpr::Like(_)
+        | BoundExpr::IsNull(_)
+        | BoundExpr::Is(_)
+        | BoundExpr::Between(_)
+        | BoundExpr::In(_) => COMPARE,
+        BoundExpr::BinaryOp(expr) if matches!(expr.op, BinaryOperator::Add | BinaryOperator::Sub) => ADD,
+        BoundExpr::BinaryOp(_) => MULT,
+        _ => ATOM,
+    }
+}
+
+fn not(negated: bool) -> &'static str {
+    if negated {
+        "NOT "
+    } else {
+        ""
+    }
+}
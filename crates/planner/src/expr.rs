@@ -0,0 +1,154 @@
+//! Walking bound expressions. Selects in expressions are not walked into,
+//! they are planned on their own.
+
+use binder::bound::{BoundExpr, BoundFunction, BoundInKind, BoundSelect};
+
+/// Returns the operands of `expr`, including the filter and window of a
+/// function.
+pub fn children(expr: &BoundExpr) -> Vec<&BoundExpr> {
+    match expr {
+        BoundExpr::Literal(_)
+        | BoundExpr::Column(_)
+        | BoundExpr::Subquery(_)
+        | BoundExpr::Exists(_)
+        | BoundExpr::Parameter(_)
+        | BoundExpr::Invalid(_) => vec![],
+        BoundExpr::BinaryOp(expr) => vec![&expr.left, &expr.right],
+        BoundExpr::UnaryOp(expr) => vec![&expr.operand],
+        BoundExpr::Compare(expr) => vec![&expr.left, &expr.right],
+        BoundExpr::BoolOp(expr) => vec![&expr.left, &expr.right],
+        BoundExpr::List(expr) => expr.exprs.iter().collect(),
+        BoundExpr::Collate(expr) => vec![&expr.expr],
+        BoundExpr::Like(expr) => vec![&expr.expr, &expr.pattern],
+        BoundExpr::IsNull(expr) => vec![&expr.expr],
+        BoundExpr::Is(expr) => vec![&expr.left, &expr.right],
+        BoundExpr::Between(expr) => vec![&expr.expr, &expr.low, &expr.high],
+        BoundExpr::In(expr) => {
+            let mut children = vec![expr.expr.as_ref()];
+            if let BoundInKind::List(list) = &expr.kind {
+                children.extend(list);
+            }
+            children
+        }
+        BoundExpr::Function(function) => {
+            let mut children: Vec<&BoundExpr> = function.args.iter().flatten().collect();
+            children.extend(function.filter.as_deref());
+            if let Some(over) = &function.over {
+                children.extend(&over.partition_by);
+                children.extend(over.order_by.iter().map(|term| &term.expr));
+            }
+            children
+        }
+        BoundExpr::Case(expr) => {
+            let mut children: Vec<&BoundExpr> = expr.operand.as_deref().into_iter().collect();
+            for branch in &expr.branches {
+                children.push(&branch.condition);
+                children.push(&branch.result);
+            }
+            children.extend(expr.else_expr.as_deref());
+            children
+        }
+        BoundExpr::Cast(expr) => vec![&expr.expr],
+    }
+}
+
+pub fn children_mut(expr: &mut BoundExpr) -> Vec<&mut BoundExpr> {
+    match expr {
+        BoundExpr::Literal(_)
+        | BoundExpr::Column(_)
+        | BoundExpr::Subquery(_)
+        | BoundExpr::Exists(_)
+        | BoundExpr::Parameter(_)
+        | BoundExpr::Invalid(_) => vec![],
+        BoundExpr::BinaryOp(expr) => vec![&mut expr.left, &mut expr.right],
+        BoundExpr::UnaryOp(expr) => vec![&mut expr.operand],
+        BoundExpr::Compare(expr) => vec![&mut expr.left, &mut expr.right],
+        BoundExpr::BoolOp(expr) => vec![&mut expr.left, &mut expr.right],
+        BoundExpr::List(expr) => expr.exprs.iter_mut().collect(),
+        BoundExpr::Collate(expr) => vec![&mut expr.expr],
+        BoundExpr::Like(expr) => vec![&mut expr.expr, &mut expr.pattern],
+        BoundExpr::IsNull(expr) => vec![&mut expr.expr],
+        BoundExpr::Is(expr) => vec![&mut expr.left, &mut expr.right],
+        BoundExpr::Between(expr) => vec![&mut expr.expr, &mut expr.low, &mut expr.high],
+        BoundExpr::In(expr) => {
+            let mut children = vec![expr.expr.as_mut()];
+            if let BoundInKind::List(list) = &mut expr.kind {
+                children.extend(list);
+            }
+            children
+        }
+        BoundExpr::Function(function) => {
+            let mut children: Vec<&mut BoundExpr> = function.args.iter_mut().flatten().collect();
+            children.extend(function.filter.as_deref_mut());
+            if let Some(over) = &mut function.over {
+                children.extend(&mut over.partition_by);
+                children.extend(over.order_by.iter_mut().map(|term| &mut term.expr));
+            }
+            children
+        }
+        BoundExpr::Case(expr) => {
+            let mut children: Vec<&mut BoundExpr> = expr.operand.as_deref_mut().into_iter().collect();
+            for branch in &mut expr.branches {
+                children.push(&mut branch.condition);
+                children.push(&mut branch.result);
+            }
+            children.extend(expr.else_expr.as_deref_mut());
+            children
+        }
+        BoundExpr::Cast(expr) => vec![&mut expr.expr],
+    }
+}
+
+/// The select of a scalar subquery, `EXISTS` or `IN (SELECT ...)`.
+pub fn subquery(expr: &BoundExpr) -> Option<&BoundSelect> {
+    match expr {
+        BoundExpr::Subquery(subquery) => Some(&subquery.select),
+        BoundExpr::Exists(exists) => Some(&exists.select),
+        BoundExpr::In(expr) => match &expr.kind {
+            BoundInKind::Select(select) => Some(select),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Replace the outermost subexpressions of `expr`, `expr` included, for
+/// which `f` returns a replacement.
+pub fn replace(expr: &mut BoundExpr, f: &mut dyn FnMut(&BoundExpr) -> Option<BoundExpr>) {
+    match f(expr) {
+        Some(replacement) => *expr = replacement,
+        None => {
+            for child in children_mut(expr) {
+                replace(child, f);
+            }
+        }
+    }
+}
+
+/// The function computes one value from all rows of a group, rather than
+/// one per row or per window.
+pub fn is_aggregate(function: &BoundFunction) -> bool {
+    if function.over.is_some() {
+        return false;
+    }
+    let args = function.args.as_ref().map_or(0, Vec::len);
+    match function.name.as_str().to_ascii_lowercase().as_str() {
+        "count" | "sum" | "avg" | "total" | "group_concat" => true,
+        // With more arguments these pick the least or greatest of them.
+        "min" | "max" => args == 1,
+        _ => false,
+    }
+}
+
+/// Collect the aggregates in `expr` into `found`. Aggregates in the
+/// arguments of another are left out.
+pub fn aggregates<'a>(expr: &'a BoundExpr, found: &mut Vec<&'a BoundFunction>) {
+    match expr {
+        BoundExpr::Function(function) if is_aggregate(function) => found.push(function),
+        _ => {
+            for child in children(expr) {
+                aggregates(child, found);
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Logical query plans.
+//!
+//! [`plan`] lowers a bound SELECT, INSERT, UPDATE or DELETE to a tree of
+//! [`LogicalPlan`] operators that says what rows to compute, not how. Plans
+//! print as an indented text dump, one operator per line, so rewrites of a
+//! plan and the operators executing it can be checked without going back
+//! to SQL.
+
+use binder::bound::BoundStmt;
+use binder::Bound;
+
+use crate::lower::Planner;
+
+mod display;
+pub mod expr;
+mod lower;
+pub mod plan;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
+pub use crate::plan::{LogicalPlan, Plan};
+
+/// The plan of `stmt`, one of the statements of `bound`. `None` for
+/// statements without one, like `CREATE TABLE`, and if `bound` has errors.
+pub fn plan(bound: &Bound, stmt: &BoundStmt) -> Option<Plan> {
+    if !bound.is_valid() {
+        return None;
+    }
+    Planner::new(bound).plan_stmt(stmt)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::{lines, plan_source};
+
+    use super::*;
+
+    fn dump(source: &str) -> Vec<String> {
+        lines(&plan_source(source))
+    }
+
+    #[test]
+    fn select_clauses() {
+        assert_eq!(
+            dump(
+                "SELECT name, age + 1 AS next FROM users WHERE age > 18 AND NOT name = 'x' \
+                 ORDER BY age DESC LIMIT 10 OFFSET 2;"
+            ),
+            [
+                "Limit: 10 OFFSET 2",
+                "  Project: users.name, users.age + 1 AS next",
+                "    Sort: users.age DESC",
+                "      Filter: users.age > 18 AND NOT users.name = 'x'",
+                "        Scan: users",
+            ]
+        );
+        assert_eq!(dump("SELECT -(1 - (2 - 3)) * 4;"), ["Project: -(1 - (2 - 3)) * 4 AS -(1 - (2 - 3)) * 4", "  Values: ()"]);
+    }
+
+    #[test]
+    fn aggregates_are_read_from_the_groups() {
+        assert_eq!(
+            dump(
+                "SELECT u.name, count(*), sum(o.total) * 2 AS double FROM users AS u \
+                 JOIN orders AS o ON o.user_id = u.id GROUP BY u.name HAVING count(*) > 1 ORDER BY sum(o.total);"
+            ),
+            [
+                "Project: #3.name, #3.count(*), #3.sum(o.total) * 2 AS double",
+                "  Sort: #3.sum(o.total)",
+                "    Filter: #3.count(*) > 1",
+                "      Aggregate: group by [u.name] aggregates [count(*), sum(o.total)]",
+                "        Join: INNER ON o.user_id = u.id",
+                "          Scan: u",
+                "          Scan: o",
+            ]
+        );
+        assert_eq!(
+            dump("SELECT DISTINCT name FROM users, orders AS o WHERE o.id = 1 ORDER BY name;"),
+            [
+                "Sort: #2.name",
+                "  Aggregate: group by [#2.name]",
+                "    Project: users.name",
+                "      Filter: o.id = 1",
+                "        Join: INNER",
+                "          Scan: users",
+                "          Scan: o",
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_and_derived_tables() {
+        assert_eq!(
+            dump("SELECT * FROM users JOIN orders USING (id) LEFT JOIN (SELECT id FROM users) AS s ON s.id = users.id;"),
+            [
+                "Project: users.id, users.name, users.age, orders.user_id, orders.total, s.id",
+                "  Join: LEFT ON s.id = users.id",
+                "    Join: INNER ON users.id = orders.id",
+                "      Scan: users",
+                "      Scan: orders",
+                "    Project: users.id",
+                "      Scan: users",
+            ]
+        );
+    }
+
+    #[test]
+    fn compound_selects_and_ctes() {
+        assert_eq!(
+            dump("SELECT id FROM users UNION SELECT user_id FROM orders ORDER BY 1 LIMIT 3;"),
+            [
+                "Limit: 3",
+                "  Sort: #1.id",
+                "    SetOp: UNION",
+                "      Project: users.id",
+                "        Scan: users",
+                "      Project: orders.user_id",
+                "        Scan: orders",
+            ]
+        );
+        assert_eq!(
+            dump("WITH RECURSIVE n (x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 5) SELECT x FROM n;"),
+            [
+                "Project: n.x",
+                "  Project: #0.1 AS x",
+                "    SetOp: UNION ALL",
+                "      Project: 1 AS 1",
+                "        Values: ()",
+                "      Project: n.x + 1 AS x + 1",
+                "        Filter: n.x < 5",
+                "          Scan: n (recursive)",
+            ]
+        );
+    }
+
+    #[test]
+    fn subqueries_are_planned_separately() {
+        assert_eq!(
+            dump(
+                "SELECT name, (SELECT max(total) FROM orders WHERE user_id = users.id) AS most FROM users \
+                 WHERE EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id) \
+                 AND id NOT IN (SELECT user_id FROM orders);"
+            ),
+            [
+                "Project: users.name, (#2) AS most",
+                "  Filter: EXISTS (#4) AND users.id NOT IN (#6)",
+                "    Scan: users",
+                "Subquery #2:",
+                "  Project: #8.max(orders.total) AS max(total)",
+                "    Aggregate: aggregates [max(orders.total)]",
+                "      Filter: orders.user_id = users.id",
+                "        Scan: orders",
+                "Subquery #4:",
+                "  Project: 1 AS 1",
+                "    Filter: orders.user_id = users.id",
+                "      Scan: orders",
+                "Subquery #6:",
+                "  Project: orders.user_id",
+                "    Scan: orders",
+            ]
+        );
+    }
+
+    #[test]
+    fn data_changes() {
+        assert_eq!(
+            dump(
+                "INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b') \
+                 ON CONFLICT (id) DO UPDATE SET name = excluded.name WHERE users.age > 1 RETURNING users.id;"
+            ),
+            [
+                "Insert: users (id, name) ON CONFLICT (users.id) DO UPDATE SET name = excluded.name \
+                 WHERE users.age > 1 RETURNING users.id",
+                "  Values: (1, 'a'), (2, 'b')",
+            ]
+        );
+        assert_eq!(dump("INSERT INTO users DEFAULT VALUES;"), ["Insert: users", "  Values: ()"]);
+        assert_eq!(
+            dump("UPDATE users SET age = age + 1 FROM orders WHERE orders.user_id = users.id RETURNING name;"),
+            [
+                "Update: users SET age = users.age + 1 RETURNING users.name",
+                "  Filter: orders.user_id = users.id",
+                "    Join: INNER",
+                "      Scan: users",
+                "      Scan: orders",
+            ]
+        );
+        assert_eq!(
+            dump("DELETE FROM users WHERE id IN (1, 2);"),
+            ["Delete: users", "  Filter: users.id IN (1, 2)", "    Scan: users"]
+        );
+    }
+
+    #[test]
+    fn statements_without_plans() {
+        let catalog: Vec<binder::TableSchema> = vec![];
+        let parsed = parser::parse_stmts("CREATE TABLE t (a INTEGER);").unwrap();
+        let bound = binder::bind(&parsed.stmts, &catalog);
+        assert!(bound.is_valid());
+        assert_eq!(plan(&bound, &bound.stmts[0]), None);
+        // The table is not in the catalog yet.
+        let parsed = parser::parse_stmts("SELECT a FROM t;").unwrap();
+        let bound = binder::bind(&parsed.stmts, &catalog);
+        assert_eq!(plan(&bound, &bound.stmts[0]), None);
+    }
+}
@@ -0,0 +1,417 @@
+//! Lowering bound statements to plans.
+//!
+//! A select becomes, from the bottom up: its FROM clause, a [`Filter`] for
+//! WHERE, an [`Aggregate`] if it groups or aggregates, a [`Filter`] for
+//! HAVING, a [`Sort`], the [`Project`] of its result columns, an
+//! [`Aggregate`] for DISTINCT and a [`Limit`]. Above the aggregate, group
+//! expressions and aggregates are read as columns of its relation. A
+//! compound select sorts and limits the rows of the [`SetOp`]s instead.
+//!
+//! References to a common table expression are replaced by the plan of its
+//! select, except those of a recursive one inside its own select.
+
+use std::collections::{BTreeMap, HashMap};
+
+use ast::name::Name;
+use binder::bound::{
+    BoundCte, BoundCteScan, BoundDelete, BoundExpr, BoundFrom, BoundFunction, BoundInsert, BoundInsertSource,
+    BoundLimit, BoundOrderingTerm, BoundResultColumn, BoundSelect, BoundStmt, BoundUpdate, ColumnRef, CteId, Relation,
+    RelationColumn, RelationId, RelationKind,
+};
+use binder::{Bound, ColumnId, TableId};
+use source_index::span::{Span, Spanned};
+
+use crate::display::{expr_key, expr_text};
+use crate::expr::{aggregates, children, replace, subquery};
+use crate::plan::{
+    Aggregate, Delete, Filter, Insert, Join, JoinKind, Limit, LogicalPlan, Plan, Project, Scan, ScanSource, SetOp,
+    Sort, Update, Values,
+};
+
+pub(crate) struct Planner<'a> {
+    bound: &'a Bound,
+    relations: Vec<Relation>,
+    ctes: HashMap<CteId, BoundCte>,
+    /// The common table expressions whose select is being planned.
+    expanding: Vec<CteId>,
+    subqueries: BTreeMap<RelationId, LogicalPlan>,
+}
+
+impl<'a> Planner<'a> {
+    pub fn new(bound: &'a Bound) -> Self {
+        Self {
+            bound,
+            relations: bound.relations().to_vec(),
+            ctes: HashMap::new(),
+            expanding: Vec::new(),
+            subqueries: BTreeMap::new(),
+        }
+    }
+
+    pub fn plan_stmt(mut self, stmt: &BoundStmt) -> Option<Plan> {
+        let root = match stmt {
+            BoundStmt::Select(select) => self.plan_select(select),
+            BoundStmt::Insert(insert) => self.plan_insert(insert),
+            BoundStmt::Update(update) => self.plan_update(update),
+            BoundStmt::Delete(delete) => self.plan_delete(delete),
+            BoundStmt::Other(_) => return None,
+        };
+        self.plan_subqueries(&root);
+        Some(Plan {
+            root,
+            subqueries: self.subqueries,
+            relations: self.relations,
+        })
+    }
+
+    fn plan_subqueries(&mut self, plan: &LogicalPlan) {
+        let mut selects = Vec::new();
+        collect_subqueries(plan, &mut selects);
+        for select in selects {
+            let subquery = self.plan_select(select);
+            self.plan_subqueries(&subquery);
+            self.subqueries.insert(select.id, subquery);
+        }
+    }
+
+    fn add_ctes(&mut self, ctes: &[BoundCte]) {
+        for cte in ctes {
+            self.ctes.insert(cte.id, cte.clone());
+        }
+    }
+
+    /// A relation of the planner, with columns called `names`.
+    fn add_relation(&mut self, names: Vec<Name>) -> RelationId {
+        let id = RelationId(self.relations.len() as u32);
+        self.relations.push(Relation {
+            id,
+            name: Name::empty(),
+            kind: RelationKind::Select,
+            columns: names
+                .into_iter()
+                .map(|name| RelationColumn { name, data_type: None })
+                .collect(),
+        });
+        id
+    }
+
+    fn column_name(&self, relation: RelationId, column: usize) -> Name {
+        self.relations[relation.0 as usize].columns[column].name.clone()
+    }
+
+    fn table(&self, relation: RelationId) -> TableId {
+        match &self.bound.relation(relation).kind {
+            RelationKind::Table(table) => *table,
+            kind => unreachable!("relation {} is not a table but {kind:?}", relation.0),
+        }
+    }
+
+    fn plan_select(&mut self, select: &BoundSelect) -> LogicalPlan {
+        self.add_ctes(&select.ctes);
+        let Some(mut compound) = select.compound.as_ref() else {
+            let plan = self.plan_core(select, &select.order_by);
+            return limit(plan, &select.limit);
+        };
+        let mut plan = self.plan_core(select, &[]);
+        loop {
+            plan = LogicalPlan::SetOp(SetOp {
+                left: Box::new(plan),
+                op: compound.op.clone(),
+                right: Box::new(self.plan_core(&compound.select, &[])),
+            });
+            match &compound.select.compound {
+                Some(next) => compound = next,
+                None => break,
+            }
+        }
+        if !select.order_by.is_empty() {
+            plan = LogicalPlan::Sort(Sort {
+                input: Box::new(plan),
+                terms: select.order_by.clone(),
+            });
+        }
+        limit(plan, &select.limit)
+    }
+
+    /// The plan of `select` without its compound selects, sorted by
+    /// `order_by`.
+    fn plan_core(&mut self, select: &BoundSelect, order_by: &[BoundOrderingTerm]) -> LogicalPlan {
+        let mut plan = match &select.from {
+            Some(from) => self.plan_from(from),
+            None => LogicalPlan::Values(Values {
+                relation: self.add_relation(vec![]),
+                rows: vec![vec![]],
+            }),
+        };
+        if let Some(where_clause) = &select.where_clause {
+            plan = filter(plan, where_clause.clone());
+        }
+
+        let mut columns = select.columns.clone();
+        let mut having = select.having.clone();
+        let mut order_by = order_by.to_vec();
+        let mut found = Vec::new();
+        for expr in columns.iter().map(|column| &column.expr).chain(&having) {
+            aggregates(expr, &mut found);
+        }
+        for term in &order_by {
+            aggregates(&term.expr, &mut found);
+        }
+        if !select.group_by.is_empty() || !found.is_empty() {
+            let mut keys: Vec<String> = select.group_by.iter().map(expr_key).collect();
+            let mut names: Vec<Name> = select.group_by.iter().map(|expr| self.output_name(expr)).collect();
+            let mut functions: Vec<BoundFunction> = Vec::new();
+            for function in found {
+                let expr = BoundExpr::Function(function.clone());
+                let key = expr_key(&expr);
+                if !keys.contains(&key) {
+                    keys.push(key);
+                    names.push(self.output_name(&expr));
+                    functions.push(function.clone());
+                }
+            }
+            let relation = self.add_relation(names.clone());
+            plan = LogicalPlan::Aggregate(Aggregate {
+                input: Box::new(plan),
+                relation,
+                group_by: select.group_by.clone(),
+                aggregates: functions,
+            });
+            let mut grouped = |expr: &BoundExpr| {
+                let key = expr_key(expr);
+                let column = keys.iter().position(|k| *k == key)?;
+                Some(column_ref(expr.span(), relation, column, names[column].clone()))
+            };
+            let exprs = columns.iter_mut().map(|column| &mut column.expr).chain(&mut having);
+            for expr in exprs.chain(order_by.iter_mut().map(|term| &mut term.expr)) {
+                replace(expr, &mut grouped);
+            }
+        }
+        if let Some(having) = having {
+            plan = filter(plan, having);
+        }
+
+        if !select.distinct && !order_by.is_empty() {
+            plan = sort(plan, order_by.clone());
+        }
+        let keys: Vec<String> = columns.iter().map(|column| expr_key(&column.expr)).collect();
+        let outputs: Vec<BoundExpr> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| column_ref(column.span, select.id, i, column.name.clone()))
+            .collect();
+        plan = LogicalPlan::Project(Project {
+            input: Box::new(plan),
+            relation: select.id,
+            columns,
+        });
+        if select.distinct {
+            plan = LogicalPlan::Aggregate(Aggregate {
+                input: Box::new(plan),
+                relation: select.id,
+                group_by: outputs.clone(),
+                aggregates: vec![],
+            });
+            if !order_by.is_empty() {
+                // Only the result columns are left to sort by.
+                for term in &mut order_by {
+                    replace(&mut term.expr, &mut |expr| {
+                        let key = expr_key(expr);
+                        let column = keys.iter().position(|k| *k == key)?;
+                        Some(outputs[column].clone())
+                    });
+                }
+                plan = sort(plan, order_by);
+            }
+        }
+        plan
+    }
+
+    /// The name of the column computing `expr` in a relation of the planner.
+    fn output_name(&self, expr: &BoundExpr) -> Name {
+        match expr {
+            BoundExpr::Column(column) => column.name.clone(),
+            _ => Name::new(expr_text(expr, &self.relations)),
+        }
+    }
+
+    fn plan_from(&mut self, from: &BoundFrom) -> LogicalPlan {
+        match from {
+            BoundFrom::Table(table) => self.scan(table.relation),
+            BoundFrom::Subquery(subquery) => self.plan_select(&subquery.select),
+            BoundFrom::Cte(scan) => self.plan_cte(scan),
+            BoundFrom::Join(join) => LogicalPlan::Join(Join {
+                left: Box::new(self.plan_from(&join.left)),
+                kind: match join.kind {
+                    ast::JoinKind::Comma | ast::JoinKind::Cross | ast::JoinKind::Inner => JoinKind::Inner,
+                    ast::JoinKind::Left => JoinKind::Left,
+                    ast::JoinKind::Right => JoinKind::Right,
+                    ast::JoinKind::Full => JoinKind::Full,
+                },
+                right: Box::new(self.plan_from(&join.right)),
+                condition: join.condition.clone(),
+            }),
+        }
+    }
+
+    fn scan(&self, relation: RelationId) -> LogicalPlan {
+        LogicalPlan::Scan(Scan {
+            relation,
+            source: ScanSource::Table(self.table(relation)),
+            columns: self.all_columns(relation),
+        })
+    }
+
+    fn all_columns(&self, relation: RelationId) -> Vec<ColumnId> {
+        let columns = &self.relations[relation.0 as usize].columns;
+        (0..columns.len() as u32).map(ColumnId).collect()
+    }
+
+    fn plan_cte(&mut self, scan: &BoundCteScan) -> LogicalPlan {
+        if self.expanding.contains(&scan.cte) {
+            return LogicalPlan::Scan(Scan {
+                relation: scan.relation,
+                source: ScanSource::Cte(scan.cte),
+                columns: self.all_columns(scan.relation),
+            });
+        }
+        let cte = self.ctes[&scan.cte].clone();
+        self.expanding.push(cte.id);
+        let input = self.plan_select(&cte.select);
+        self.expanding.pop();
+        let columns = self.bound.relation(scan.relation).columns.iter().enumerate();
+        let columns = columns
+            .map(|(i, column)| BoundResultColumn {
+                span: scan.span,
+                name: column.name.clone(),
+                expr: column_ref(scan.span, cte.select.id, i, self.column_name(cte.select.id, i)),
+            })
+            .collect();
+        LogicalPlan::Project(Project {
+            input: Box::new(input),
+            relation: scan.relation,
+            columns,
+        })
+    }
+
+    fn plan_insert(&mut self, insert: &BoundInsert) -> LogicalPlan {
+        self.add_ctes(&insert.ctes);
+        let (input, columns) = match &insert.source {
+            BoundInsertSource::Values(rows) => {
+                let names = insert
+                    .columns
+                    .iter()
+                    .map(|column| self.column_name(insert.table, column.0 as usize))
+                    .collect();
+                let values = Values {
+                    relation: self.add_relation(names),
+                    rows: rows.clone(),
+                };
+                (LogicalPlan::Values(values), insert.columns.clone())
+            }
+            BoundInsertSource::Select(select) => (self.plan_select(select), insert.columns.clone()),
+            BoundInsertSource::DefaultValues => {
+                let values = Values {
+                    relation: self.add_relation(vec![]),
+                    rows: vec![vec![]],
+                };
+                (LogicalPlan::Values(values), vec![])
+            }
+        };
+        LogicalPlan::Insert(Insert {
+            input: Box::new(input),
+            relation: insert.table,
+            table: self.table(insert.table),
+            columns,
+            upsert: insert.upsert.clone(),
+            returning: insert.returning.clone(),
+        })
+    }
+
+    fn plan_update(&mut self, update: &BoundUpdate) -> LogicalPlan {
+        self.add_ctes(&update.ctes);
+        let mut input = self.scan(update.table);
+        if let Some(from) = &update.from {
+            input = LogicalPlan::Join(Join {
+                left: Box::new(input),
+                kind: JoinKind::Inner,
+                right: Box::new(self.plan_from(from)),
+                condition: None,
+            });
+        }
+        if let Some(where_clause) = &update.where_clause {
+            input = filter(input, where_clause.clone());
+        }
+        LogicalPlan::Update(Update {
+            input: Box::new(input),
+            relation: update.table,
+            table: self.table(update.table),
+            assignments: update.assignments.clone(),
+            returning: update.returning.clone(),
+        })
+    }
+
+    fn plan_delete(&mut self, delete: &BoundDelete) -> LogicalPlan {
+        self.add_ctes(&delete.ctes);
+        let mut input = self.scan(delete.table);
+        if let Some(where_clause) = &delete.where_clause {
+            input = filter(input, where_clause.clone());
+        }
+        LogicalPlan::Delete(Delete {
+            input: Box::new(input),
+            relation: delete.table,
+            table: self.table(delete.table),
+            returning: delete.returning.clone(),
+        })
+    }
+}
+
+fn filter(input: LogicalPlan, predicate: BoundExpr) -> LogicalPlan {
+    LogicalPlan::Filter(Filter {
+        input: Box::new(input),
+        predicate,
+    })
+}
+
+fn sort(input: LogicalPlan, terms: Vec<BoundOrderingTerm>) -> LogicalPlan {
+    LogicalPlan::Sort(Sort {
+        input: Box::new(input),
+        terms,
+    })
+}
+
+fn limit(input: LogicalPlan, limit: &Option<BoundLimit>) -> LogicalPlan {
+    match limit {
+        Some(limit) => LogicalPlan::Limit(Limit {
+            input: Box::new(input),
+            limit: limit.limit.clone(),
+            offset: limit.offset.clone(),
+        }),
+        None => input,
+    }
+}
+
+fn column_ref(span: Span, relation: RelationId, column: usize, name: Name) -> BoundExpr {
+    BoundExpr::Column(ColumnRef {
+        span,
+        relation,
+        column: ColumnId(column as u32),
+        name,
+    })
+}
+
+/// Collect the selects of the subqueries in the expressions of `plan`.
+fn collect_subqueries<'p>(plan: &'p LogicalPlan, selects: &mut Vec<&'p BoundSelect>) {
+    fn walk<'p>(expr: &'p BoundExpr, selects: &mut Vec<&'p BoundSelect>) {
+        selects.extend(subquery(expr));
+        for child in children(expr) {
+            walk(child, selects);
+        }
+    }
+    for expr in plan.exprs() {
+        walk(expr, selects);
+    }
+    for input in plan.inputs() {
+        collect_subqueries(input, selects);
+    }
+}
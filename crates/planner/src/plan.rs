@@ -0,0 +1,309 @@
+//! The logical operators.
+//!
+//! A plan is a tree of operators, each reading the rows of its inputs.
+//! Expressions are the bound expressions of the statement, so a column is
+//! still read by [`RelationId`] and [`ColumnId`]: a [`Scan`] produces the
+//! columns of its relation, a [`Project`] and an [`Aggregate`] the columns
+//! of the relation they name, and every other operator the columns of its
+//! inputs.
+
+use std::collections::BTreeMap;
+
+use binder::bound::{
+    BoundAssignment, BoundExpr, BoundFunction, BoundOrderingTerm, BoundResultColumn, BoundUpsert, BoundUpsertAction,
+    CteId, Relation, RelationId,
+};
+use binder::{ColumnId, TableId};
+
+/// The plan of a statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub root: LogicalPlan,
+    /// The plans of the subqueries in expressions, by the relation of their
+    /// select. A subquery reading columns of an enclosing plan is run for
+    /// every row of it.
+    pub subqueries: BTreeMap<RelationId, LogicalPlan>,
+    /// The relations by id: those of the bound statements, then the unnamed
+    /// ones made by the planner for groups and rows of values.
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogicalPlan {
+    Scan(Scan),
+    Filter(Filter),
+    Project(Project),
+    Join(Join),
+    Aggregate(Aggregate),
+    Sort(Sort),
+    Limit(Limit),
+    Values(Values),
+    SetOp(SetOp),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+}
+
+impl LogicalPlan {
+    /// Returns the operators the rows come from.
+    pub fn inputs(&self) -> Vec<&LogicalPlan> {
+        match self {
+            LogicalPlan::Scan(_) | LogicalPlan::Values(_) => vec![],
+            LogicalPlan::Filter(filter) => vec![&filter.input],
+            LogicalPlan::Project(project) => vec![&project.input],
+            LogicalPlan::Join(join) => vec![&join.left, &join.right],
+            LogicalPlan::Aggregate(aggregate) => vec![&aggregate.input],
+            LogicalPlan::Sort(sort) => vec![&sort.input],
+            LogicalPlan::Limit(limit) => vec![&limit.input],
+            LogicalPlan::SetOp(set_op) => vec![&set_op.left, &set_op.right],
+            LogicalPlan::Insert(insert) => vec![&insert.input],
+            LogicalPlan::Update(update) => vec![&update.input],
+            LogicalPlan::Delete(delete) => vec![&delete.input],
+        }
+    }
+
+    pub fn inputs_mut(&mut self) -> Vec<&mut LogicalPlan> {
+        match self {
+            LogicalPlan::Scan(_) | LogicalPlan::Values(_) => vec![],
+            LogicalPlan::Filter(filter) => vec![&mut filter.input],
+            LogicalPlan::Project(project) => vec![&mut project.input],
+            LogicalPlan::Join(join) => vec![&mut join.left, &mut join.right],
+            LogicalPlan::Aggregate(aggregate) => vec![&mut aggregate.input],
+            LogicalPlan::Sort(sort) => vec![&mut sort.input],
+            LogicalPlan::Limit(limit) => vec![&mut limit.input],
+            LogicalPlan::SetOp(set_op) => vec![&mut set_op.left, &mut set_op.right],
+            LogicalPlan::Insert(insert) => vec![&mut insert.input],
+            LogicalPlan::Update(update) => vec![&mut update.input],
+            LogicalPlan::Delete(delete) => vec![&mut delete.input],
+        }
+    }
+
+    /// Returns the expressions of the operator itself, not of its inputs.
+    pub fn exprs(&self) -> Vec<&BoundExpr> {
+        match self {
+            LogicalPlan::Scan(_) | LogicalPlan::SetOp(_) => vec![],
+            LogicalPlan::Filter(filter) => vec![&filter.predicate],
+            LogicalPlan::Project(project) => results(&project.columns),
+            LogicalPlan::Join(join) => join.condition.iter().collect(),
+            LogicalPlan::Aggregate(aggregate) => {
+                let mut exprs: Vec<&BoundExpr> = aggregate.group_by.iter().collect();
+                for function in &aggregate.aggregates {
+                    exprs.extend(function.args.iter().flatten());
+                    exprs.extend(function.filter.as_deref());
+                }
+                exprs
+            }
+            LogicalPlan::Sort(sort) => sort.terms.iter().map(|term| &term.expr).collect(),
+            LogicalPlan::Limit(limit) => [Some(&limit.limit), limit.offset.as_ref()].into_iter().flatten().collect(),
+            LogicalPlan::Values(values) => values.rows.iter().flatten().collect(),
+            LogicalPlan::Insert(insert) => {
+                let mut exprs: Vec<&BoundExpr> = results(&insert.returning);
+                if let Some(upsert) = &insert.upsert {
+                    if let Some(target) = &upsert.target {
+                        exprs.extend(&target.columns);
+                        exprs.extend(&target.where_clause);
+                    }
+                    if let BoundUpsertAction::Update {
+                        assignments,
+                        where_clause,
+                        ..
+                    } = &upsert.action
+                    {
+                        exprs.extend(assignments.iter().map(|assignment| &assignment.expr));
+                        exprs.extend(where_clause.as_deref());
+                    }
+                }
+                exprs
+            }
+            LogicalPlan::Update(update) => {
+                let mut exprs: Vec<&BoundExpr> = update.assignments.iter().map(|assignment| &assignment.expr).collect();
+                exprs.extend(results(&update.returning));
+                exprs
+            }
+            LogicalPlan::Delete(delete) => results(&delete.returning),
+        }
+    }
+
+    pub fn exprs_mut(&mut self) -> Vec<&mut BoundExpr> {
+        match self {
+            LogicalPlan::Scan(_) | LogicalPlan::SetOp(_) => vec![],
+            LogicalPlan::Filter(filter) => vec![&mut filter.predicate],
+            LogicalPlan::Project(project) => results_mut(&mut project.columns),
+            LogicalPlan::Join(join) => join.condition.iter_mut().collect(),
+            LogicalPlan::Aggregate(aggregate) => {
+                let mut exprs: Vec<&mut BoundExpr> = aggregate.group_by.iter_mut().collect();
+                for function in &mut aggregate.aggregates {
+                    exprs.extend(function.args.iter_mut().flatten());
+                    exprs.extend(function.filter.as_deref_mut());
+                }
+                exprs
+            }
+            LogicalPlan::Sort(sort) => sort.terms.iter_mut().map(|term| &mut term.expr).collect(),
+            LogicalPlan::Limit(limit) => [Some(&mut limit.limit), limit.offset.as_mut()].into_iter().flatten().collect(),
+            LogicalPlan::Values(values) => values.rows.iter_mut().flatten().collect(),
+            LogicalPlan::Insert(insert) => {
+                let mut exprs: Vec<&mut BoundExpr> = results_mut(&mut insert.returning);
+                if let Some(upsert) = &mut insert.upsert {
+                    if let Some(target) = &mut upsert.target {
+                        exprs.extend(&mut target.columns);
+                        exprs.extend(&mut target.where_clause);
+                    }
+                    if let BoundUpsertAction::Update {
+                        assignments,
+                        where_clause,
+                        ..
+                    } = &mut upsert.action
+                    {
+                        exprs.extend(assignments.iter_mut().map(|assignment| &mut assignment.expr));
+                        exprs.extend(where_clause.as_deref_mut());
+                    }
+                }
+                exprs
+            }
+            LogicalPlan::Update(update) => {
+                let mut exprs: Vec<&mut BoundExpr> =
+                    update.assignments.iter_mut().map(|assignment| &mut assignment.expr).collect();
+                exprs.extend(results_mut(&mut update.returning));
+                exprs
+            }
+            LogicalPlan::Delete(delete) => results_mut(&mut delete.returning),
+        }
+    }
+}
+
+fn results(columns: &[BoundResultColumn]) -> Vec<&BoundExpr> {
+    columns.iter().map(|column| &column.expr).collect()
+}
+
+fn results_mut(columns: &mut [BoundResultColumn]) -> Vec<&mut BoundExpr> {
+    columns.iter_mut().map(|column| &mut column.expr).collect()
+}
+
+/// The rows of a table, or of the rows a recursive common table expression
+/// produced so far.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scan {
+    pub relation: RelationId,
+    pub source: ScanSource,
+    /// The columns read, all of them unless the plan was pruned.
+    pub columns: Vec<ColumnId>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScanSource {
+    Table(TableId),
+    /// A reference of a recursive common table expression to itself. Other
+    /// references are replaced by the plan of its select.
+    Cte(CteId),
+}
+
+/// The rows of the input for which the predicate is true.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    pub input: Box<LogicalPlan>,
+    pub predicate: BoundExpr,
+}
+
+/// Computes the columns of `relation` from each row of the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Project {
+    pub input: Box<LogicalPlan>,
+    pub relation: RelationId,
+    pub columns: Vec<BoundResultColumn>,
+}
+
+/// Pairs of rows of both inputs, with the columns of the left input first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Join {
+    pub left: Box<LogicalPlan>,
+    pub kind: JoinKind,
+    pub right: Box<LogicalPlan>,
+    /// `None` for a cross join.
+    pub condition: Option<BoundExpr>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JoinKind {
+    /// `JOIN`, `CROSS JOIN` and `,`.
+    Inner,
+    Left,
+    Right,
+    Full,
+    /// The rows of the left input with a matching row in the right input,
+    /// each once. Only the columns of the left input are produced.
+    Semi,
+    /// The rows of the left input without a matching row in the right
+    /// input.
+    Anti,
+}
+
+/// One row per group of input rows with equal `group_by` values, or a
+/// single row if there is no `group_by`. The columns of `relation` are the
+/// `group_by` expressions followed by the aggregates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aggregate {
+    pub input: Box<LogicalPlan>,
+    pub relation: RelationId,
+    pub group_by: Vec<BoundExpr>,
+    pub aggregates: Vec<BoundFunction>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sort {
+    pub input: Box<LogicalPlan>,
+    pub terms: Vec<BoundOrderingTerm>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Limit {
+    pub input: Box<LogicalPlan>,
+    pub limit: BoundExpr,
+    pub offset: Option<BoundExpr>,
+}
+
+/// Rows of constant expressions, the columns of `relation`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Values {
+    pub relation: RelationId,
+    pub rows: Vec<Vec<BoundExpr>>,
+}
+
+/// The rows of a compound select, read as the columns of the left input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetOp {
+    pub left: Box<LogicalPlan>,
+    pub op: ast::CompoundOperator,
+    pub right: Box<LogicalPlan>,
+}
+
+/// Inserts each input row into the table, its columns in the order of
+/// `columns`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Insert {
+    pub input: Box<LogicalPlan>,
+    pub relation: RelationId,
+    pub table: TableId,
+    /// Empty for `DEFAULT VALUES`.
+    pub columns: Vec<ColumnId>,
+    pub upsert: Option<BoundUpsert>,
+    pub returning: Vec<BoundResultColumn>,
+}
+
+/// Updates the row of the table in each input row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Update {
+    pub input: Box<LogicalPlan>,
+    pub relation: RelationId,
+    pub table: TableId,
+    pub assignments: Vec<BoundAssignment>,
+    pub returning: Vec<BoundResultColumn>,
+}
+
+/// Deletes the row of the table in each input row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delete {
+    pub input: Box<LogicalPlan>,
+    pub relation: RelationId,
+    pub table: TableId,
+    pub returning: Vec<BoundResultColumn>,
+}
@@ -0,0 +1,32 @@
+//! The schema the plan dumps of the tests of this crate and of the crates
+//! rewriting its plans are written against, behind the `test-util` feature.
+
+use catalog::SystemCatalog;
+
+use crate::{plan, Plan};
+
+pub const SCHEMA: &str = "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(20), age INTEGER); \
+                          CREATE TABLE orders (id INTEGER, user_id INTEGER, total NUMERIC);";
+
+/// A catalog with the tables of [`SCHEMA`].
+pub fn catalog() -> SystemCatalog {
+    let mut catalog = SystemCatalog::new();
+    for stmt in &parser::parse_stmts(SCHEMA).unwrap().stmts.body {
+        catalog.apply(stmt).unwrap();
+    }
+    catalog
+}
+
+/// The plan of the first statement of `source`, which must bind against
+/// [`catalog`] without errors.
+pub fn plan_source(source: &str) -> Plan {
+    let parsed = parser::parse_stmts(source).expect("source should parse");
+    let bound = binder::bind(&parsed.stmts, &catalog());
+    assert_eq!(bound.errors(), &[]);
+    plan(&bound, &bound.stmts[0]).expect("statement should have a plan")
+}
+
+/// The lines of the dump of `plan`.
+pub fn lines(plan: &Plan) -> Vec<String> {
+    plan.to_string().lines().map(str::to_string).collect()
+}
@@ -0,0 +1,297 @@
+use std::fmt::Write;
+
+use crate::{line_index::LineIndex, location::Location, span::Span};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A message about the source text, pointing at one or more spans of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable identifier of the kind of diagnostic e.g. `P0004`.
+    pub code: Option<String>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    /// Extra lines printed after the source snippet.
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+/// A span of the source with a message. The primary label is where the
+/// problem is, secondary labels add context such as where a clause started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub primary: bool,
+}
+
+impl Label {
+    pub fn primary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: true,
+        }
+    }
+
+    pub fn secondary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: false,
+        }
+    }
+}
+
+/// Whether rendered diagnostics contain ANSI color codes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Colored,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Renders diagnostics for a single source text:
+///
+/// ```text
+/// error[P0001]: Expected an expression, found `FROM` instead.
+///  --> query.sql:1:8
+///   |
+/// 1 | SELECT FROM t1;
+///   |        ^^^^ expected an expression
+/// ```
+pub struct Renderer<'a> {
+    name: &'a str,
+    source: &'a str,
+    index: LineIndex,
+    style: Style,
+}
+
+impl<'a> Renderer<'a> {
+    /// `name` is shown in front of the position of the primary label,
+    /// usually the path the source was read from.
+    pub fn new(name: &'a str, source: &'a str, style: Style) -> Self {
+        Self {
+            name,
+            source,
+            index: LineIndex::new(source),
+            style,
+        }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        let (severity, color) = match diagnostic.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+        let severity = match &diagnostic.code {
+            Some(code) => format!("{severity}[{code}]"),
+            None => severity.to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(color, &severity),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        );
+
+        let mut labels: Vec<&Label> = diagnostic.labels.iter().collect();
+        labels.sort_by_key(|label| (label.span.start(), !label.primary));
+        let last_line = labels
+            .iter()
+            .map(|label| self.index.line_col(label.span.start()).line)
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat((last_line + 1).to_string().len());
+
+        if let Some(primary) = labels.iter().find(|label| label.primary).or(labels.first()) {
+            let line = self.index.line_col(primary.span.start()).line;
+            let col = self.column(line, primary.span.start()) + 1;
+            let _ = writeln!(out, "{gutter}{} {}:{}:{col}", self.paint(BLUE, "-->"), self.name, line + 1);
+            let _ = writeln!(out, "{gutter} {}", self.paint(BLUE, "|"));
+        }
+
+        let mut prev_line = None;
+        for label in labels {
+            let line = self.index.line_col(label.span.start()).line;
+            if prev_line != Some(line) {
+                if prev_line.is_some_and(|prev| prev + 1 < line) {
+                    let _ = writeln!(out, "{}", self.paint(BLUE, "..."));
+                }
+                let number = format!("{:>width$} |", line + 1, width = gutter.len());
+                let text = self.line_text(line);
+                let _ = writeln!(out, "{} {text}", self.paint(BLUE, &number));
+                prev_line = Some(line);
+            }
+            self.underline(&mut out, &gutter, line, label, color);
+        }
+
+        if !diagnostic.notes.is_empty() {
+            let _ = writeln!(out, "{gutter} {}", self.paint(BLUE, "|"));
+        }
+        for note in &diagnostic.notes {
+            let _ = writeln!(out, "{gutter} {} {note}", self.paint(BLUE, "="));
+        }
+        out
+    }
+
+    // Write the marker row under a source line. Spans running past the end
+    // of the line are underlined to the end of it and empty spans get a
+    // single marker. Primary markers take the color of the severity.
+    fn underline(&self, out: &mut String, gutter: &str, line: usize, label: &Label, color: &str) {
+        let line_span = self.line_span(line);
+        let start = label.span.start().min(line_span.end());
+        let end = label.span.end().min(line_span.end()).max(start);
+        // keep tabs so the markers line up with the source line above
+        let padding: String = self.source[*line_span.start()..*start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = self.source[*start..*end].chars().count().max(1);
+        let (marker, color) = if label.primary {
+            ("^", color)
+        } else {
+            ("-", BLUE)
+        };
+        let mut markers = marker.repeat(width);
+        if !label.message.is_empty() {
+            markers = format!("{markers} {}", label.message);
+        }
+        let _ = writeln!(
+            out,
+            "{gutter} {} {padding}{}",
+            self.paint(BLUE, "|"),
+            self.paint(color, &markers)
+        );
+    }
+
+    // Column of `offset` in characters, which is what terminals display.
+    fn column(&self, line: usize, offset: Location) -> usize {
+        let line_span = self.line_span(line);
+        self.source[*line_span.start()..*offset.min(line_span.end())].chars().count()
+    }
+
+    fn line_span(&self, line: usize) -> Span {
+        self.index
+            .line_span(line)
+            .unwrap_or(Span::empty(Location::new(self.source.len())))
+    }
+
+    fn line_text(&self, line: usize) -> &str {
+        &self.source[self.line_span(line)]
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        match self.style {
+            Style::Plain => text.to_string(),
+            Style::Colored => format!("{color}{text}{RESET}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn span(start: usize, end: usize) -> Span {
+        Span::new(Location::new(start), Location::new(end))
+    }
+
+    #[test]
+    fn render_plain() {
+        let source = "SELECT (c1\n\tFROM t1;";
+        let diagnostic = Diagnostic::error("Expected `)`, found `FROM` instead.")
+            .with_code("P0004")
+            .with_label(Label::primary(span(12, 16), "expected `)`"))
+            .with_label(Label::secondary(span(7, 8), "unclosed `(`"))
+            .with_note("help: close the parenthesis");
+        let rendered = Renderer::new("query.sql", source, Style::Plain).render(&diagnostic);
+        assert_eq!(
+            rendered,
+            "error[P0004]: Expected `)`, found `FROM` instead.
+ --> query.sql:2:2
+  |
+1 | SELECT (c1
+  |        - unclosed `(`
+2 | \tFROM t1;
+  | \t^^^^ expected `)`
+  |
+  = help: close the parenthesis
+"
+        );
+    }
+
+    #[test]
+    fn render_end_of_input_and_gaps() {
+        let source = "SELECT 'é'\n\n\nFROM";
+        let diagnostic = Diagnostic::warning("Unexpected end of input")
+            .with_label(Label::secondary(span(0, 6), "statement starts here"))
+            .with_label(Label::primary(span(18, 18), ""));
+        let rendered = Renderer::new("<stdin>", source, Style::Plain).render(&diagnostic);
+        assert_eq!(
+            rendered,
+            "warning: Unexpected end of input
+ --> <stdin>:4:5
+  |
+1 | SELECT 'é'
+  | ------ statement starts here
+...
+4 | FROM
+  |     ^
+"
+        );
+    }
+
+    #[test]
+    fn render_colored() {
+        let diagnostic = Diagnostic::error("bad").with_label(Label::primary(span(0, 1), "here"));
+        let rendered = Renderer::new("q", "x", Style::Colored).render(&diagnostic);
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m\x1b[1m: bad\x1b[0m\n"));
+        assert!(rendered.contains("\x1b[1;31m^ here\x1b[0m"));
+    }
+}
@@ -1,2 +1,4 @@
+pub mod diagnostic;
+pub mod line_index;
 pub mod location;
 pub mod span;
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::{location::Location, span::Span};
+
+/// A zero based line and column. Columns count UTF-8 bytes from the start of
+/// the line unless converted with [`LineIndex::to_utf16`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Maps byte offsets in a source text to line and column positions.
+///
+/// Lines are terminated by `\n`, a `\r` before it is treated as part of the
+/// terminator when looking up the text of a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    // offset of the first byte of every line
+    line_starts: Vec<Location>,
+    // characters longer than one byte, by line, to convert UTF-16 columns
+    wide_chars: HashMap<usize, Vec<WideChar>>,
+    len: Location,
+    // lines ending with "\r\n"
+    crlf: Vec<bool>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct WideChar {
+    // UTF-8 column of the character
+    col: usize,
+    len_utf8: usize,
+    len_utf16: usize,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![Location::new(0)];
+        let mut wide_chars: HashMap<usize, Vec<WideChar>> = HashMap::new();
+        let mut crlf = Vec::new();
+        let mut line_start = 0;
+        let mut prev = None;
+        for (offset, c) in source.char_indices() {
+            if c == '\n' {
+                crlf.push(prev == Some('\r'));
+                line_start = offset + 1;
+                line_starts.push(Location::new(line_start));
+            } else if !c.is_ascii() {
+                wide_chars.entry(line_starts.len() - 1).or_default().push(WideChar {
+                    col: offset - line_start,
+                    len_utf8: c.len_utf8(),
+                    len_utf16: c.len_utf16(),
+                });
+            }
+            prev = Some(c);
+        }
+        crlf.push(false);
+        Self {
+            line_starts,
+            wide_chars,
+            len: Location::new(source.len()),
+            crlf,
+        }
+    }
+
+    /// Number of lines in the source. An empty source and a source ending
+    /// with a newline both have an empty last line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The line and UTF-8 column of `offset`. Offsets past the end of the
+    /// source are clamped to the end.
+    pub fn line_col(&self, offset: Location) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        LineCol {
+            line,
+            col: *offset - *self.line_starts[line],
+        }
+    }
+
+    /// The byte offset of a line and UTF-8 column, or `None` if the position
+    /// is past the end of its line.
+    pub fn offset(&self, line_col: LineCol) -> Option<Location> {
+        let line = self.line_span(line_col.line)?;
+        let offset = line.start() + Location::new(line_col.col);
+        (offset <= line.end()).then_some(offset)
+    }
+
+    /// The span of a line, not including its terminator.
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) if self.crlf[line] => *next - Location::new(2),
+            Some(next) => *next - Location::new(1),
+            None => self.len,
+        };
+        Some(Span::new(start, end))
+    }
+
+    /// Convert a UTF-8 column to a UTF-16 column, as used by editors speaking
+    /// the language server protocol.
+    pub fn to_utf16(&self, line_col: LineCol) -> LineCol {
+        let mut col = line_col.col;
+        for c in self.wide_chars(line_col.line) {
+            if c.col >= line_col.col {
+                break;
+            }
+            col = col - c.len_utf8 + c.len_utf16;
+        }
+        LineCol { col, ..line_col }
+    }
+
+    /// Convert a UTF-16 column back to a UTF-8 column.
+    pub fn to_utf8(&self, line_col: LineCol) -> LineCol {
+        let mut col = line_col.col;
+        for c in self.wide_chars(line_col.line) {
+            // position of the character in UTF-16 units
+            let col_utf16 = c.col - (col - line_col.col);
+            if col_utf16 >= line_col.col {
+                break;
+            }
+            col = col + c.len_utf8 - c.len_utf16;
+        }
+        LineCol { col, ..line_col }
+    }
+
+    fn wide_chars(&self, line: usize) -> &[WideChar] {
+        self.wide_chars.get(&line).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn line_col(line: usize, col: usize) -> LineCol {
+        LineCol { line, col }
+    }
+
+    #[test]
+    fn offsets_to_line_col() {
+        let source = "SELECT a\nFROM t1\r\n\nWHERE a = 1";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(Location::new(0)), line_col(0, 0));
+        assert_eq!(index.line_col(Location::new(8)), line_col(0, 8));
+        assert_eq!(index.line_col(Location::new(9)), line_col(1, 0));
+        assert_eq!(index.line_col(Location::new(14)), line_col(1, 5));
+        assert_eq!(index.line_col(Location::new(18)), line_col(2, 0));
+        assert_eq!(index.line_col(Location::new(25)), line_col(3, 6));
+        assert_eq!(index.line_col(Location::new(100)), line_col(3, 11));
+
+        assert_eq!(index.offset(line_col(1, 5)), Some(Location::new(14)));
+        assert_eq!(index.offset(line_col(1, 8)), None);
+        assert_eq!(index.offset(line_col(4, 0)), None);
+        assert_eq!(&source[index.line_span(0).unwrap()], "SELECT a");
+        assert_eq!(&source[index.line_span(1).unwrap()], "FROM t1");
+        assert_eq!(&source[index.line_span(2).unwrap()], "");
+        assert_eq!(&source[index.line_span(3).unwrap()], "WHERE a = 1");
+    }
+
+    #[test]
+    fn utf16_columns() {
+        // 'é' is two bytes and one UTF-16 unit, '𝄞' four bytes and two units
+        let source = "SELECT 1;\nSELECT 'é𝄞', x";
+        let index = LineIndex::new(source);
+        let x = index.line_col(Location::new(source.len() - 1));
+        assert_eq!(x, line_col(1, 17));
+        assert_eq!(index.to_utf16(x), line_col(1, 14));
+        assert_eq!(index.to_utf8(line_col(1, 14)), x);
+
+        let after_e = index.line_col(Location::new(source.find('𝄞').unwrap()));
+        assert_eq!(index.to_utf16(after_e), line_col(1, 9));
+        assert_eq!(index.to_utf8(line_col(1, 9)), after_e);
+        assert_eq!(index.to_utf16(line_col(0, 4)), line_col(0, 4));
+    }
+}